@@ -0,0 +1,195 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// This Module chooses how calc runs from its arguments:
+//   calc                 the interactive calculator, or one expression per line of piped input
+//   calc EXPRESSION      evaluates the expression, which may be split over several arguments
+//   calc -f FILE         evaluates each line of a script, where blank lines and lines starting
+//                        with # are skipped, and variables and functions carry over from line
+//                        to line
+// Only -f, -h, --help and -- are options, any other argument starts an expression, so calc -5 + 2
+// and calc -fib(10) print values. A -- in front makes the rest an expression even if it is -f.
+// Values are printed to stdout, one per line, and errors to stderr with the line they came from.
+// Input stops at the first error, and the exit status tells syntax errors apart from errors in
+// the arithmetic, so shell scripts can react to each.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use eval::{evaluate, Environment, LineError};
+use repl;
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+pub const EXIT_SUCCESS : i32 = 0;
+pub const EXIT_FAILURE : i32 = 1;          // bad arguments, or a file that can not be read
+pub const EXIT_SYNTAX_ERROR : i32 = 2;     // a line that is not an expression
+pub const EXIT_MATH_ERROR : i32 = 3;       // an expression with no value, such as 1 / 0
+
+const USAGE : &str = "\
+usage: calc [--] [EXPRESSION...]
+       calc -f FILE
+
+With no arguments calc starts the interactive calculator, or evaluates each line of its input
+when that is not a terminal. Enter :help in the calculator for the operators. Arguments after
+-- are always an expression.
+
+Exit status: 0 on success, 1 for bad arguments or files, 2 for a syntax error, 3 for a math
+error such as division by zero.";
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// What the arguments ask calc to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Input,              // the interactive calculator, or each line of piped input
+    Help,               // print the usage
+    Script(String),     // evaluate each line of the file at the path
+    Expression(String), // evaluate the arguments joined by spaces
+}
+
+// returns what the arguments after the program name ask for, or what is wrong with them.
+fn mode(args : &[String]) -> Result<Mode, String> {
+    match args.first().map(|arg| arg.as_str()) {
+        None => Ok(Mode::Input),
+        Some("-h") | Some("--help") => Ok(Mode::Help),
+        Some("-f") => match args.len() {
+            1 => Err("-f needs a file".to_string()),
+            2 => Ok(Mode::Script(args[1].clone())),
+            count => Err(format!("-f takes one file but was given {}", count - 1)),
+        },
+        Some("--") => Ok(Mode::Expression(args[1..].join(" "))),
+        Some(_) => Ok(Mode::Expression(args.join(" "))),
+    }
+} // End of mode function
+
+// runs calc with the arguments after the program name, returning the exit status.
+pub fn run(args : &[String]) -> i32 {
+    let mode = match mode(args) {
+        Ok(mode) => mode,
+        Err(message) => {
+            eprintln!("calc: {}\n\n{}", message, USAGE);
+            return EXIT_FAILURE;
+        }
+    };
+    let stdout = io::stdout();
+    let stderr = io::stderr();
+    let result = match mode {
+        Mode::Input if io::stdin().is_terminal() => repl::run().map(|_| EXIT_SUCCESS),
+        Mode::Input => {
+            let stdin = io::stdin();
+            run_script(&mut stdin.lock(), "<stdin>", &mut stdout.lock(), &mut stderr.lock())
+        }
+        Mode::Help => writeln!(stdout.lock(), "{}", USAGE).map(|_| EXIT_SUCCESS),
+        Mode::Script(path) => match File::open(&path) {
+            Ok(file) => run_script(&mut BufReader::new(file), &path, &mut stdout.lock(), &mut stderr.lock()),
+            Err(error) => {
+                eprintln!("calc: {}: {}", path, error);
+                return EXIT_FAILURE;
+            }
+        },
+        Mode::Expression(source) => run_expression(&source, &mut stdout.lock(), &mut stderr.lock()),
+    };
+    match result {
+        Ok(status) => status,
+        // the reader of the output has gone, so there is no one left to tell
+        Err(ref error) if error.kind() == io::ErrorKind::BrokenPipe => EXIT_FAILURE,
+        Err(error) => {
+            eprintln!("calc: {}", error);
+            EXIT_FAILURE
+        }
+    }
+} // End of run function
+
+// evaluates an expression, printing its value or the error, and returns the exit status.
+pub fn run_expression<W : Write, E : Write>(source : &str, output : &mut W, errors : &mut E) -> io::Result<i32> {
+    match evaluate(source, &mut Environment::new()) {
+        Ok(value) => {
+            if let Some(value) = value {
+                writeln!(output, "{}", value)?;
+            }
+            Ok(EXIT_SUCCESS)
+        }
+        Err(error) => {
+            writeln!(errors, "calc: {}\n{}", error, error.span().highlight(source))?;
+            Ok(exit_status(&error))
+        }
+    }
+} // End of run_expression function
+
+// evaluates each line of the input until the first error, and returns the exit status. Errors
+// are reported with the name of the input and the number of the line.
+pub fn run_script<R : BufRead, W : Write, E : Write>(input : &mut R, name : &str, output : &mut W, errors : &mut E) -> io::Result<i32> {
+    let mut env = Environment::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        match evaluate(&line, &mut env) {
+            Ok(Some(value)) => writeln!(output, "{}", value)?,
+            Ok(None) => {}
+            Err(error) => {
+                writeln!(errors, "calc: {}:{}: {}\n{}", name, index + 1, error, error.span().highlight(&line))?;
+                return Ok(exit_status(&error));
+            }
+        }
+    }
+    Ok(EXIT_SUCCESS)
+} // End of run_script function
+
+// returns the exit status for an error.
+fn exit_status(error : &LineError) -> i32 {
+    match *error {
+        LineError::Syntax(_) => EXIT_SYNTAX_ERROR,
+        LineError::Eval(_) => EXIT_MATH_ERROR,
+    }
+} // End of exit_status function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the output and exit status of single expressions and scripts.
+#[test]
+fn run_test() {
+    let run = |source : &str| {
+        let (mut output, mut errors) = (Vec::new(), Vec::new());
+        let status = run_expression(source, &mut output, &mut errors).unwrap();
+        (status, String::from_utf8(output).unwrap(), String::from_utf8(errors).unwrap())
+    };
+    let (status, output, errors) = run("2^1000 % 7");
+    assert!(status == EXIT_SUCCESS && output == "2\n" && errors.is_empty(), "\nEvaluated 2^1000 % 7 as {} {:?} {:?}\n", status, output, errors);
+    let (status, output, errors) = run("1 + )");
+    assert!(status == EXIT_SYNTAX_ERROR && output.is_empty() && errors == "calc: syntax error at column 5: expected an expression\n1 + )\n    ^\n", "\nEvaluated 1 + ) as {} {:?}\n", status, errors);
+    let (status, _, _) = run("1 / (2 - 2)");
+    assert!(status == EXIT_MATH_ERROR, "\nEvaluated the status of 1 / (2 - 2) as {}\n", status);
+
+    let script = "# powers of two\nx = 2 ^ 10\n\n  x *= ans\nf(n) = n % 0\nf(x)\n4\n";
+    let (mut output, mut errors) = (Vec::new(), Vec::new());
+    let status = run_script(&mut script.as_bytes(), "powers.calc", &mut output, &mut errors).unwrap();
+    let (output, errors) = (String::from_utf8(output).unwrap(), String::from_utf8(errors).unwrap());
+    assert!(status == EXIT_MATH_ERROR && output == "1024\n1048576\n", "\nEvaluated the script as {} {:?}\n", status, output);
+    assert!(errors.starts_with("calc: powers.calc:6: error at column 1: attempt to divide by zero\n"), "\nEvaluated the script error as {:?}\n", errors);
+
+    let modes = [
+        (vec![], Ok(Mode::Input)),
+        (vec!["--help"], Ok(Mode::Help)),
+        (vec!["-f", "sums.calc"], Ok(Mode::Script("sums.calc".to_string()))),
+        (vec!["-f"], Err("-f needs a file".to_string())),
+        (vec!["-f", "a.calc", "b.calc"], Err("-f takes one file but was given 2".to_string())),
+        (vec!["-fib(10)", "+", "1"], Ok(Mode::Expression("-fib(10) + 1".to_string()))),
+        (vec!["-5"], Ok(Mode::Expression("-5".to_string()))),
+        (vec!["--", "-f"], Ok(Mode::Expression("-f".to_string()))),
+    ];
+    for (args, expected) in modes.iter() {
+        let args : Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        assert!(mode(&args) == *expected, "\nEvaluated the mode of {:?} as {:?}\n", args, mode(&args));
+    }
+} // End of run_test
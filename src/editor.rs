@@ -0,0 +1,481 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// This Module reads lines from the terminal with editing and history, without any libraries.
+// While a line is being read the terminal is switched out of canonical mode with stty, so every
+// key press arrives as soon as it is typed and is not echoed. The keys are decoded from their
+// bytes and escape sequences and applied to a line buffer, and the line is redrawn after each
+// one. The terminal settings are restored before the line is returned, so output is normal
+// between prompts. When stdin is not a terminal, or stty is not available, lines are read
+// plainly instead.
+// History is kept in memory and appended to a file as each line is entered.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+const HISTORY_LIMIT : usize = 1000;     // the most lines of history kept
+
+/* ============================================================================================ */
+/*     Keys                                                                                     */
+/* ============================================================================================ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),     // a printable character
+    Enter,          // Enter or Ctrl-J
+    Backspace,      // Backspace or Ctrl-H
+    Delete,         // Delete
+    Left,           // left arrow or Ctrl-B
+    Right,          // right arrow or Ctrl-F
+    Home,           // Home or Ctrl-A
+    End,            // End or Ctrl-E
+    Up,             // up arrow or Ctrl-P
+    Down,           // down arrow or Ctrl-N
+    Interrupt,      // Ctrl-C
+    EndOfFile,      // Ctrl-D
+    ClearLine,      // Ctrl-U
+    Unknown,        // any other control key or escape sequence
+}
+
+// returns the next key from the input, or None at the end of the input.
+pub fn read_key<R : Read>(input : &mut R) -> io::Result<Option<Key>> {
+    match read_byte(input)? {
+        Some(byte) => decode(byte, input).map(Some),
+        None => Ok(None),
+    }
+} // End of read_key function
+
+// returns the key that starts with the byte, reading the rest of it from the input.
+fn decode<R : Read>(byte : u8, input : &mut R) -> io::Result<Key> {
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        0x01 => Key::Home,
+        0x02 => Key::Left,
+        0x03 => Key::Interrupt,
+        0x04 => Key::EndOfFile,
+        0x05 => Key::End,
+        0x06 => Key::Right,
+        0x0e => Key::Down,
+        0x10 => Key::Up,
+        0x15 => Key::ClearLine,
+        0x1b => read_escape(input)?,
+        0x00..=0x1f => Key::Unknown,
+        _ => {
+            // the lead byte of a UTF-8 character gives its length
+            let length = match byte {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            let mut bytes = vec![byte];
+            for _ in 1..length {
+                match read_byte(input)? {
+                    Some(byte) => bytes.push(byte),
+                    None => break,
+                }
+            }
+            match ::std::str::from_utf8(&bytes) {
+                Ok(text) => Key::Char(text.chars().next().unwrap()),
+                Err(_) => Key::Unknown,
+            }
+        }
+    };
+    Ok(key)
+} // End of decode function
+
+// returns the key of an escape sequence, after its escape byte. Arrows, Home and End are sent
+// as ESC [ letter or ESC O letter, and Home, End and Delete also as ESC [ number ~. Keys held
+// with Shift, Alt or Ctrl add parameters after a ;, as in ESC [ 1 ; 5 C for Ctrl-Right, so every
+// parameter byte is read up to the final byte and only the first parameter is kept. An escape
+// followed by anything else is a lone Escape press, which does nothing, and the byte after it
+// is a key of its own.
+fn read_escape<R : Read>(input : &mut R) -> io::Result<Key> {
+    match read_byte(input)? {
+        Some(b'[') | Some(b'O') => {}
+        Some(byte) => return decode(byte, input),
+        None => return Ok(Key::Unknown),
+    }
+    let (mut number, mut first) = (0u32, true);
+    loop {
+        let byte = match read_byte(input)? {
+            Some(byte) => byte,
+            None => return Ok(Key::Unknown),
+        };
+        match byte {
+            b'0'..=b'9' if first => number = number.saturating_mul(10).saturating_add((byte - b'0') as u32),
+            b';' => first = false,
+            // the rest of the parameter and intermediate bytes
+            0x20..=0x3f => {}
+            0x40..=0x7e => {
+                return Ok(match byte {
+                    b'A' => Key::Up,
+                    b'B' => Key::Down,
+                    b'C' => Key::Right,
+                    b'D' => Key::Left,
+                    b'H' => Key::Home,
+                    b'F' => Key::End,
+                    b'~' => match number {
+                        1 | 7 => Key::Home,
+                        3 => Key::Delete,
+                        4 | 8 => Key::End,
+                        _ => Key::Unknown,
+                    },
+                    _ => Key::Unknown,
+                });
+            }
+            // not a valid sequence, so stop before reading any further
+            _ => return Ok(Key::Unknown),
+        }
+    }
+} // End of read_escape function
+
+// returns the next byte of the input, or None at its end.
+fn read_byte<R : Read>(input : &mut R) -> io::Result<Option<u8>> {
+    let mut byte = [0u8];
+    loop {
+        return match input.read(&mut byte) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(byte[0])),
+            Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => Err(error),
+        };
+    }
+} // End of read_byte function
+
+/* ============================================================================================ */
+/*     Line buffer                                                                              */
+/* ============================================================================================ */
+
+// The line being edited and the position of the cursor in it, counted in characters.
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    chars : Vec<char>,
+    cursor : usize,
+}
+
+impl LineBuffer {
+    // returns the text of the line.
+    pub fn text(&self) -> String {
+        self.chars.iter().collect()
+    } // End of text function
+
+    // returns the cursor position.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    } // End of cursor function
+
+    // returns true if the line is empty.
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    } // End of is_empty function
+
+    // replaces the line, moving the cursor to its end.
+    pub fn set(&mut self, text : &str) {
+        self.chars = text.chars().collect();
+        self.cursor = self.chars.len();
+    } // End of set function
+
+    // applies an editing key, returning false for keys that do not edit the line.
+    pub fn edit(&mut self, key : Key) -> bool {
+        match key {
+            Key::Char(c) => {
+                self.chars.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.chars.remove(self.cursor);
+            }
+            Key::Delete | Key::EndOfFile if self.cursor < self.chars.len() => {
+                self.chars.remove(self.cursor);
+            }
+            Key::Left if self.cursor > 0 => self.cursor -= 1,
+            Key::Right if self.cursor < self.chars.len() => self.cursor += 1,
+            Key::Home => self.cursor = 0,
+            Key::End => self.cursor = self.chars.len(),
+            Key::ClearLine => self.set(""),
+            Key::Backspace | Key::Delete | Key::EndOfFile | Key::Left | Key::Right => {}
+            _ => return false,
+        }
+        true
+    } // End of edit function
+} // End of LineBuffer implementation
+
+/* ============================================================================================ */
+/*     History                                                                                  */
+/* ============================================================================================ */
+
+// The lines entered so far, oldest first, and the file they are saved in.
+pub struct History {
+    entries : Vec<String>,
+    path : Option<PathBuf>,
+}
+
+impl History {
+    // returns an empty history that is not saved.
+    pub fn new() -> History {
+        History {entries: Vec::new(), path: None}
+    } // End of new function
+
+    // returns the history saved in the file, which is created when the first line is added.
+    // Files longer than the limit are trimmed to their newest lines.
+    pub fn load(path : PathBuf) -> History {
+        let mut entries : Vec<String> = match fs::read_to_string(&path) {
+            Ok(text) => text.lines().filter(|line| !line.is_empty()).map(String::from).collect(),
+            Err(_) => Vec::new(),
+        };
+        if entries.len() > HISTORY_LIMIT {
+            entries.drain(..entries.len() - HISTORY_LIMIT);
+            let mut text = entries.join("\n");
+            text.push('\n');
+            // the history still works in memory if the file can not be written
+            let _ = fs::write(&path, text);
+        }
+        History {entries, path: Some(path)}
+    } // End of load function
+
+    // returns the number of lines in the history.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    } // End of len function
+
+    // returns true if the history is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    } // End of is_empty function
+
+    // returns the line at the index, oldest first.
+    pub fn get(&self, index : usize) -> Option<&str> {
+        self.entries.get(index).map(|entry| entry.as_str())
+    } // End of get function
+
+    // adds a line unless it is blank or repeats the newest line, appending it to the file.
+    pub fn add(&mut self, line : &str) {
+        if line.trim().is_empty() || self.entries.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.remove(0);
+        }
+        if let Some(ref path) = self.path {
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                let _ = writeln!(file, "{}", line);
+            }
+        }
+    } // End of add function
+} // End of History implementation
+
+impl Default for History {
+    fn default() -> History {
+        History::new()
+    }
+}
+
+/* ============================================================================================ */
+/*     Terminal                                                                                 */
+/* ============================================================================================ */
+
+// Holds the terminal out of canonical mode, restoring the saved settings when dropped.
+struct RawMode {
+    saved : String,
+}
+
+impl RawMode {
+    // switches the terminal to read single keys without echo or signals, or returns None
+    // if the settings can not be read or changed.
+    fn enable() -> Option<RawMode> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "-ixon", "-icrnl", "min", "1", "time", "0"])?;
+        Some(RawMode {saved: saved.trim().to_string()})
+    } // End of enable function
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        stty(&[self.saved.as_str()]);
+    } // End of drop function
+}
+
+// runs stty on the terminal with the arguments, returning its output if it succeeds.
+fn stty(args : &[&str]) -> Option<String> {
+    let tty = File::open("/dev/tty").ok()?;
+    let output = Command::new("stty").args(args).stdin(tty).stderr(Stdio::null()).output().ok()?;
+    if output.status.success() {String::from_utf8(output.stdout).ok()} else {None}
+} // End of stty function
+
+/* ============================================================================================ */
+/*     Editor                                                                                   */
+/* ============================================================================================ */
+
+// The result of reading a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Line(String),   // a line entered with Enter
+    Interrupted,    // the line was abandoned with Ctrl-C
+    Eof,            // Ctrl-D on an empty line, or the end of the input
+}
+
+// Reads lines with editing and history.
+pub struct Editor {
+    history : History,
+}
+
+impl Editor {
+    pub fn new(history : History) -> Editor {
+        Editor {history}
+    } // End of new function
+
+    // returns the history of entered lines.
+    pub fn history(&self) -> &History {
+        &self.history
+    } // End of history function
+
+    // shows the prompt and reads a line, adding it to the history.
+    pub fn read_line(&mut self, prompt : &str) -> io::Result<Input> {
+        let stdin = io::stdin();
+        let raw = if stdin.is_terminal() {RawMode::enable()} else {None};
+        let input = match raw {
+            Some(_) => self.read_edited(prompt, &mut stdin.lock())?,
+            None => read_plain(prompt, &mut stdin.lock())?,
+        };
+        drop(raw);
+        if let Input::Line(ref line) = input {
+            self.history.add(line);
+        }
+        Ok(input)
+    } // End of read_line function
+
+    // reads a line key by key, redrawing it after each key.
+    fn read_edited<R : Read>(&self, prompt : &str, input : &mut R) -> io::Result<Input> {
+        let mut stdout = io::stdout();
+        let mut line = LineBuffer::default();
+        // the history entry being shown, and the new line kept while browsing
+        let mut index = self.history.len();
+        let mut draft = String::new();
+        redraw(&mut stdout, prompt, &line)?;
+        loop {
+            let key = match read_key(input)? {
+                Some(key) => key,
+                None => return Ok(Input::Eof),
+            };
+            match key {
+                Key::Enter => {
+                    writeln!(stdout)?;
+                    return Ok(Input::Line(line.text()));
+                }
+                Key::Interrupt => {
+                    writeln!(stdout, "^C")?;
+                    return Ok(Input::Interrupted);
+                }
+                Key::EndOfFile if line.is_empty() => {
+                    writeln!(stdout)?;
+                    return Ok(Input::Eof);
+                }
+                Key::Up if index > 0 => {
+                    if index == self.history.len() {
+                        draft = line.text();
+                    }
+                    index -= 1;
+                    line.set(self.history.get(index).unwrap());
+                }
+                Key::Down if index < self.history.len() => {
+                    index += 1;
+                    line.set(self.history.get(index).unwrap_or(&draft));
+                }
+                key => {
+                    line.edit(key);
+                }
+            }
+            redraw(&mut stdout, prompt, &line)?;
+        }
+    } // End of read_edited function
+} // End of Editor implementation
+
+// rewrites the prompt and line over the current terminal line and places the cursor.
+fn redraw<W : Write>(output : &mut W, prompt : &str, line : &LineBuffer) -> io::Result<()> {
+    write!(output, "\r{}{}\x1b[K\r", prompt, line.text())?;
+    let column = prompt.chars().count() + line.cursor();
+    if column > 0 {
+        write!(output, "\x1b[{}C", column)?;
+    }
+    output.flush()
+} // End of redraw function
+
+// shows the prompt and reads a line without editing.
+fn read_plain<R : BufRead>(prompt : &str, input : &mut R) -> io::Result<Input> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(Input::Eof);
+    }
+    let length = line.trim_end_matches(&['\n', '\r'][..]).len();
+    line.truncate(length);
+    Ok(Input::Line(line))
+} // End of read_plain function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests decoding keys from the bytes and escape sequences terminals send.
+#[test]
+fn read_key_test() {
+    let bytes = "a\u{e9}\x1b[A\x1b[B\x1b[C\x1b[D\x1b[H\x1bOF\x1b[3~\x1b[1~\x7f\r\x03\x04\x15\x1b[5~".as_bytes();
+    let mut input = bytes;
+    let mut keys = Vec::new();
+    while let Some(key) = read_key(&mut input).unwrap() {
+        keys.push(key);
+    }
+    let expected = vec![
+        Key::Char('a'), Key::Char('\u{e9}'), Key::Up, Key::Down, Key::Right, Key::Left, Key::Home, Key::End,
+        Key::Delete, Key::Home, Key::Backspace, Key::Enter, Key::Interrupt, Key::EndOfFile, Key::ClearLine, Key::Unknown,
+    ];
+    assert!(keys == expected, "\nEvaluated the keys as {:?}\n", keys);
+
+    // keys with modifiers, function keys and a lone Escape leave nothing behind
+    let mut input = "\x1b[1;5C\x1b[1;5D\x1b[1;2A\x1b[3;3~\x1b[15;2~\x1b[24~\x1bO5H1\x1bx\x1b\x1b[B".as_bytes();
+    let mut keys = Vec::new();
+    while let Some(key) = read_key(&mut input).unwrap() {
+        keys.push(key);
+    }
+    let expected = vec![
+        Key::Right, Key::Left, Key::Up, Key::Delete, Key::Unknown, Key::Unknown, Key::Home, Key::Char('1'), Key::Char('x'), Key::Down,
+    ];
+    assert!(keys == expected, "\nEvaluated the keys with modifiers as {:?}\n", keys);
+} // End of read_key_test
+
+// Tests editing the line buffer and keeping history.
+#[test]
+fn line_buffer_history_test() {
+    let mut line = LineBuffer::default();
+    for key in [Key::Char('1'), Key::Char('3'), Key::Left, Key::Char('2'), Key::End, Key::Char('4'), Key::Home, Key::Delete, Key::Right, Key::Backspace, Key::Backspace].iter() {
+        line.edit(*key);
+    }
+    assert!(line.text() == "34" && line.cursor() == 0, "\nEvaluated the edited line as {:?} at {}\n", line.text(), line.cursor());
+    assert!(!line.edit(Key::Enter) && line.edit(Key::Left) && line.cursor() == 0, "\nEvaluated Enter as an edit\n");
+    line.edit(Key::ClearLine);
+    assert!(line.is_empty(), "\nEvaluated the cleared line as {:?}\n", line.text());
+
+    let path = ::std::env::temp_dir().join(format!("calc_history_test_{}", ::std::process::id()));
+    let _ = fs::remove_file(&path);
+    let mut history = History::load(path.clone());
+    for line in ["1 + 1", "1 + 1", "", "2 * 3"].iter() {
+        history.add(line);
+    }
+    assert!(history.len() == 2 && history.get(1) == Some("2 * 3"), "\nEvaluated the history length as {}\n", history.len());
+    let reloaded = History::load(path.clone());
+    assert!(reloaded.len() == 2 && reloaded.get(0) == Some("1 + 1"), "\nEvaluated the reloaded history length as {}\n", reloaded.len());
+    let _ = fs::remove_file(&path);
+} // End of line_buffer_history_test
@@ -0,0 +1,526 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// This Module evaluates parsed expressions with Integer arithmetic.
+// Division and remainder truncate toward zero like Integer's operators. Errors carry the span
+// of the expression that failed, so the caller can point at it in the input line.
+// Comparisons give 1 when they hold and 0 otherwise, and a condition holds when it is not 0.
+// Variables and functions live in an Environment that the caller keeps between lines. It also
+// holds the value of the last line evaluated, which expressions read as ans or _, and which can
+// not be assigned.
+// Function bodies are scoped lexically: a body sees its own parameters and the session's
+// variables, never the parameters of the function that called it. Since bodies are never
+// evaluated before their call, functions can call themselves and each other. Evaluation
+// recurses once per level of the tree, and a call continues that recursion into the body, so one
+// budget limits the levels nested inside one another, within a line and across calls, and a
+// runaway recursion ends in an error rather than a stack overflow.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::collections::btree_map::{self, BTreeMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::error::Error;
+use std::rc::Rc;
+use integer::{Integer, ArithmeticError};
+use lexer::{Span, SyntaxError};
+use parser::{parse, Expr, ExprKind, BinaryOp, Function};
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+// the largest power evaluated, 2^22 bits or about 1.3 million decimal digits, which take a few
+// seconds to print in a release build. Printing time grows slightly faster than the size, a
+// power of 2^24 bits takes over 15 seconds.
+const MAX_POWER_BITS : u64 = 1 << 22;
+
+// the largest n for fib(n) and lucas(n), whose value has about 0.69 n bits or 219 000 decimal
+// digits and prints in well under a second
+const MAX_SEQUENCE_INDEX : u64 = 1 << 20;
+
+// the stack size for a thread that parses and evaluates lines, with room for the deepest
+// expressions the parser allows and MAX_EVAL_DEPTH levels of evaluation, which take several
+// kilobytes a level in a debug build. Only the part in use is ever committed.
+pub const STACK_SIZE : usize = 256 << 20;
+
+// the stack one level of evaluation may take in a debug build, where frames are largest. The
+// most measured is about 3 kilobytes, for an argument evaluated inside the frame of its call.
+const LEVEL_BYTES : usize = 4 << 10;
+
+// the most levels of evaluation nested inside one another, within a line and across calls. They
+// take half of STACK_SIZE, leaving the rest for the arithmetic at the deepest level.
+pub const MAX_EVAL_DEPTH : usize = STACK_SIZE / 2 / LEVEL_BYTES;
+
+// the functions every session has, and the number of arguments each takes
+const BUILTINS : &[(&str, usize)] = &[("fib", 1), ("lucas", 1)];
+
+/* ============================================================================================ */
+/*     Errors                                                                                   */
+/* ============================================================================================ */
+
+// The reasons an expression can fail to evaluate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalErrorKind {
+    Arithmetic(ArithmeticError),    // an operation with no Integer result, such as 1 / 0
+    TooLarge,                       // a power too large to hold in memory
+    UndefinedVariable(String),      // a variable read before it was assigned
+    ReadOnlyVariable(String),       // an assignment to ans or _
+    UndefinedFunction(String),      // a call to a function that was never defined
+    ArgumentCount(String, usize, usize),    // a call with the wrong number of arguments, as (name, expected, found)
+    NegativeArgument(String),       // a negative argument to a built-in function that needs n >= 0
+    RecursionLimit,                 // evaluation nested more than MAX_EVAL_DEPTH levels deep
+}
+
+// An evaluation error and the columns of the expression that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalError {
+    kind : EvalErrorKind,
+    span : Span,
+}
+
+impl EvalError {
+    pub fn new(kind : EvalErrorKind, span : Span) -> EvalError {
+        EvalError {kind, span}
+    } // End of new function
+
+    // returns the reason for the error.
+    pub fn kind(&self) -> &EvalErrorKind {
+        &self.kind
+    } // End of kind function
+
+    // returns the columns of the expression that failed.
+    pub fn span(&self) -> Span {
+        self.span
+    } // End of span function
+} // End of EvalError implementation
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "error at column {}: ", self.span.start + 1)?;
+        match self.kind {
+            EvalErrorKind::Arithmetic(ref error) => write!(f, "{}", error),
+            EvalErrorKind::TooLarge => write!(f, "the result is too large"),
+            EvalErrorKind::UndefinedVariable(ref name) if is_last_name(name) => write!(f, "there is no result yet for {}", name),
+            EvalErrorKind::UndefinedVariable(ref name) => write!(f, "undefined variable {}", name),
+            EvalErrorKind::ReadOnlyVariable(ref name) => write!(f, "can not assign to {}, which holds the last result", name),
+            EvalErrorKind::UndefinedFunction(ref name) => write!(f, "undefined function {}", name),
+            EvalErrorKind::ArgumentCount(ref name, expected, found) => {
+                let plural = if expected == 1 {""} else {"s"};
+                write!(f, "{} takes {} argument{} but was given {}", name, expected, plural, found)
+            }
+            EvalErrorKind::NegativeArgument(ref name) => write!(f, "the argument of {} must not be negative", name),
+            EvalErrorKind::RecursionLimit => write!(f, "evaluation nested more than {} levels deep", MAX_EVAL_DEPTH),
+        }
+    } // End of fmt function
+} // End of fmt::Display implementation
+
+impl Error for EvalError {}
+
+// The ways a line can fail, before or during evaluation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineError {
+    Syntax(SyntaxError),    // the line is not an expression
+    Eval(EvalError),        // the expression has no value
+}
+
+impl LineError {
+    // returns the columns the error points at.
+    pub fn span(&self) -> Span {
+        match *self {
+            LineError::Syntax(ref error) => error.span(),
+            LineError::Eval(ref error) => error.span(),
+        }
+    } // End of span function
+} // End of LineError implementation
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LineError::Syntax(ref error) => write!(f, "{}", error),
+            LineError::Eval(ref error) => write!(f, "{}", error),
+        }
+    } // End of fmt function
+} // End of fmt::Display implementation
+
+impl Error for LineError {}
+
+/* ============================================================================================ */
+/*     Environment                                                                              */
+/* ============================================================================================ */
+
+// The variables and functions of a session and the value of its last line.
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+    variables : BTreeMap<String, Integer>,
+    functions : BTreeMap<String, Rc<Function>>,
+    last : Option<Integer>,
+}
+
+impl Environment {
+    pub fn new() -> Environment {
+        Environment::default()
+    } // End of new function
+
+    // returns the value of a variable, where ans and _ are the value of the last line.
+    pub fn get(&self, name : &str) -> Option<&Integer> {
+        if is_last_name(name) {self.last.as_ref()} else {self.variables.get(name)}
+    } // End of get function
+
+    // returns the value of the last line evaluated, if any.
+    pub fn last(&self) -> Option<&Integer> {
+        self.last.as_ref()
+    } // End of last function
+
+    // returns the variables in order of their names.
+    pub fn variables(&self) -> btree_map::Iter<'_, String, Integer> {
+        self.variables.iter()
+    } // End of variables function
+
+    // returns the user-defined functions in order of their names.
+    pub fn functions(&self) -> btree_map::Iter<'_, String, Rc<Function>> {
+        self.functions.iter()
+    } // End of functions function
+
+    // forgets every variable and function and the last value.
+    pub fn clear(&mut self) {
+        self.variables.clear();
+        self.functions.clear();
+        self.last = None;
+    } // End of clear function
+} // End of Environment implementation
+
+// returns true for the names of the last value.
+fn is_last_name(name : &str) -> bool {
+    name == "ans" || name == "_"
+} // End of is_last_name function
+
+// The arguments of the function call being evaluated.
+struct Frame<'a> {
+    params : &'a [String],
+    args : Vec<Integer>,
+}
+
+impl<'a> Frame<'a> {
+    // returns the frame of a line, outside any function.
+    fn top() -> Frame<'a> {
+        Frame {params: &[], args: Vec::new()}
+    } // End of top function
+
+    // returns the value of a parameter.
+    fn get(&self, name : &str) -> Option<&Integer> {
+        self.params.iter().position(|param| param == name).map(|index| &self.args[index])
+    } // End of get function
+} // End of Frame implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns the value of the expression, assigning any variables it assigns, or None for a
+// definition, which only adds its function to the environment.
+pub fn eval(expr : &Expr, env : &mut Environment) -> Result<Option<Integer>, EvalError> {
+    match expr.kind {
+        ExprKind::Define(ref name, ref function) => {
+            env.functions.insert(name.clone(), function.clone());
+            Ok(None)
+        }
+        _ => assign(expr, env).map(Some),
+    }
+} // End of eval function
+
+// returns the value of an expression, or of the variable it assigns.
+fn assign(expr : &Expr, env : &mut Environment) -> Result<Integer, EvalError> {
+    let (name, op, value) = match expr.kind {
+        ExprKind::Assign(ref name, op, ref value) => (name, op, value),
+        _ => return evaluate_in(expr, env, &Frame::top(), 0),
+    };
+    let target = Span {start: expr.span.start, end: expr.span.start + name.chars().count()};
+    if is_last_name(name) {
+        return Err(EvalError::new(EvalErrorKind::ReadOnlyVariable(name.clone()), target));
+    }
+    let mut value = assign(value, env)?;
+    if let Some(op) = op {
+        let current = match env.get(name) {
+            Some(current) => current.clone(),
+            None => return Err(EvalError::new(EvalErrorKind::UndefinedVariable(name.clone()), target)),
+        };
+        value = apply(op, current, value, expr.span)?;
+    }
+    env.variables.insert(name.clone(), value.clone());
+    Ok(value)
+} // End of assign function
+
+// returns the value of an expression inside the frame of a call, depth levels of evaluation
+// below the line.
+fn evaluate_in(expr : &Expr, env : &Environment, frame : &Frame, depth : usize) -> Result<Integer, EvalError> {
+    if depth >= MAX_EVAL_DEPTH {
+        return Err(EvalError::new(EvalErrorKind::RecursionLimit, expr.span));
+    }
+    match expr.kind {
+        ExprKind::Number(ref number) => Ok(number.clone()),
+        ExprKind::Variable(ref name) => match frame.get(name).or_else(|| env.get(name)) {
+            Some(value) => Ok(value.clone()),
+            None => Err(EvalError::new(EvalErrorKind::UndefinedVariable(name.clone()), expr.span)),
+        },
+        ExprKind::Negate(ref operand) => Ok(-evaluate_in(operand, env, frame, depth + 1)?),
+        ExprKind::Binary(op, ref left, ref right) => {
+            let a = evaluate_in(left, env, frame, depth + 1)?;
+            let b = evaluate_in(right, env, frame, depth + 1)?;
+            apply(op, a, b, expr.span)
+        }
+        ExprKind::If(ref condition, ref then, ref otherwise) => {
+            if !evaluate_in(condition, env, frame, depth + 1)?.is_zero() {
+                evaluate_in(then, env, frame, depth + 1)
+            } else {
+                evaluate_in(otherwise, env, frame, depth + 1)
+            }
+        }
+        ExprKind::Call(ref name, ref args) => call(name, args, expr.span, env, frame, depth),
+        ExprKind::Assign(..) | ExprKind::Define(..) => unreachable!("assignments and definitions are only parsed at the start of a line"),
+    }
+} // End of evaluate_in function
+
+// returns the value of a call to a user-defined or built-in function at the given depth, with
+// errors pointing at the span of the call.
+fn call(name : &str, args : &[Expr], span : Span, env : &Environment, frame : &Frame, depth : usize) -> Result<Integer, EvalError> {
+    let error = |kind| EvalError::new(kind, span);
+    let function = env.functions.get(name);
+    let expected = match function {
+        Some(function) => function.params.len(),
+        None => match BUILTINS.iter().find(|builtin| builtin.0 == name) {
+            Some(builtin) => builtin.1,
+            None => return Err(error(EvalErrorKind::UndefinedFunction(name.to_string()))),
+        },
+    };
+    if args.len() != expected {
+        return Err(error(EvalErrorKind::ArgumentCount(name.to_string(), expected, args.len())));
+    }
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        values.push(evaluate_in(arg, env, frame, depth + 1)?);
+    }
+    let function = match function {
+        Some(function) => function,
+        None => return call_builtin(name, &values).map_err(error),
+    };
+    let inner = Frame {params: &function.params, args: values};
+    // the body's spans are from the line that defined it, so errors point at the call
+    evaluate_in(&function.body, env, &inner, depth + 1).map_err(|inner| error(inner.kind))
+} // End of call function
+
+// returns the value of a built-in function.
+fn call_builtin(name : &str, args : &[Integer]) -> Result<Integer, EvalErrorKind> {
+    if args[0] < Integer::from(0) {
+        return Err(EvalErrorKind::NegativeArgument(name.to_string()));
+    }
+    let n = match u64::try_from(&args[0]) {
+        Ok(n) if n <= MAX_SEQUENCE_INDEX => n,
+        _ => return Err(EvalErrorKind::TooLarge),
+    };
+    match name {
+        "fib" => Ok(Integer::fibonacci(n)),
+        "lucas" => Ok(Integer::lucas(n)),
+        _ => unreachable!("{} is not a built-in function", name),
+    }
+} // End of call_builtin function
+
+// returns a op b, with errors pointing at the span of the operation.
+fn apply(op : BinaryOp, a : Integer, b : Integer, span : Span) -> Result<Integer, EvalError> {
+    let arithmetic = |error| EvalError::new(EvalErrorKind::Arithmetic(error), span);
+    match op {
+        BinaryOp::Add => Ok(a + b),
+        BinaryOp::Sub => Ok(a - b),
+        BinaryOp::Mul => Ok(a * b),
+        BinaryOp::Less => Ok(Integer::from(u32::from(a < b))),
+        BinaryOp::LessEqual => Ok(Integer::from(u32::from(a <= b))),
+        BinaryOp::Greater => Ok(Integer::from(u32::from(a > b))),
+        BinaryOp::GreaterEqual => Ok(Integer::from(u32::from(a >= b))),
+        BinaryOp::Equal => Ok(Integer::from(u32::from(a == b))),
+        BinaryOp::NotEqual => Ok(Integer::from(u32::from(a != b))),
+        BinaryOp::Div => a.checked_div(&b).ok_or(arithmetic(ArithmeticError::DivisionByZero)),
+        BinaryOp::Rem => a.checked_rem(&b).ok_or(arithmetic(ArithmeticError::DivisionByZero)),
+        BinaryOp::Pow => {
+            // 0, 1 and -1 stay small whatever the exponent
+            let small = a.abs() <= Integer::from(1);
+            // a power of two has exactly b * (bits of a - 1) + 1 bits, any other a fewer than
+            // b * (bits of a)
+            let bits = if a.count_ones() == 1 {&b * (a.bit_length() - 1) + Integer::from(1)} else {&b * a.bit_length()};
+            if !small && b > Integer::from(0) && bits > Integer::from(MAX_POWER_BITS) {
+                return Err(EvalError::new(EvalErrorKind::TooLarge, span));
+            }
+            a.pow(&b).map_err(arithmetic)
+        }
+    }
+} // End of apply function
+
+// parses and evaluates a line of input, whose value becomes the last value if it has one.
+pub fn evaluate(source : &str, env : &mut Environment) -> Result<Option<Integer>, LineError> {
+    let expr = parse(source).map_err(LineError::Syntax)?;
+    let value = eval(&expr, env).map_err(LineError::Eval)?;
+    if value.is_some() {
+        env.last = value.clone();
+    }
+    Ok(value)
+} // End of evaluate function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the values of expressions and the spans of the errors they raise.
+#[test]
+fn eval_test() {
+    let mut env = Environment::new();
+    let cases = [
+        ("1 + 2 * 3", "7"),
+        ("(1 + 2) * 3", "9"),
+        ("2 ^ 3 ^ 2", "512"),
+        ("-2 ^ 2", "-4"),
+        ("(-2) ^ 3", "-8"),
+        ("-7 / 2", "-3"),
+        ("-7 % 2", "-1"),
+        ("2 ^ 100 - 1", "1267650600228229401496703205375"),
+        ("(-1) ^ 100000000000000000000001", "-1"),
+    ];
+    for &(source, expected) in cases.iter() {
+        let value = eval(&parse(source).unwrap(), &mut env).unwrap().unwrap();
+        assert!(value.to_string() == expected, "\nEvaluated {} as {}\n", source, value);
+    }
+
+    let error = eval(&parse("1 + 4 / (2 - 2)").unwrap(), &mut env).unwrap_err();
+    assert!(*error.kind() == EvalErrorKind::Arithmetic(ArithmeticError::DivisionByZero) && error.span() == Span {start: 4, end: 15}, "\nEvaluated the division by zero as {:?}\n", error);
+    let error = eval(&parse("2 ^ -1").unwrap(), &mut env).unwrap_err();
+    assert!(*error.kind() == EvalErrorKind::Arithmetic(ArithmeticError::NegativeExponent), "\nEvaluated 2 ^ -1 as {:?}\n", error);
+    let error = eval(&parse("10 ^ 10 ^ 10").unwrap(), &mut env).unwrap_err();
+    assert!(*error.kind() == EvalErrorKind::TooLarge && error.to_string() == "error at column 1: the result is too large", "\nEvaluated 10 ^ 10 ^ 10 as {:?}\n", error);
+    // powers of two are measured exactly, so the largest one allowed has MAX_POWER_BITS bits
+    let value = eval(&parse("2 ^ (2 ^ 22 - 1) % 10").unwrap(), &mut env).unwrap().unwrap();
+    assert!(value == Integer::from(8), "\nEvaluated 2 ^ (2 ^ 22 - 1) % 10 as {}\n", value);
+    for source in ["2 ^ 2 ^ 22", "3 ^ (2 ^ 21 + 1)"].iter() {
+        let error = eval(&parse(source).unwrap(), &mut env).unwrap_err();
+        assert!(*error.kind() == EvalErrorKind::TooLarge, "\nEvaluated {} as {:?}\n", source, error);
+    }
+    assert!(matches!(evaluate("1 +", &mut env), Err(LineError::Syntax(_))), "\nEvaluated 1 + as not a syntax error\n");
+    assert!(matches!(evaluate("1 % 0", &mut env), Err(LineError::Eval(_))), "\nEvaluated 1 % 0 as not an evaluation error\n");
+} // End of eval_test
+
+// Tests assignment, compound assignment, the last value and the errors for variables.
+#[test]
+fn variables_test() {
+    let mut env = Environment::new();
+    let error = evaluate("ans + 1", &mut env).unwrap_err();
+    assert!(error.to_string() == "error at column 1: there is no result yet for ans", "\nEvaluated ans before any result as {}\n", error);
+    let cases = [
+        ("x = 2 ^ 64", "18446744073709551616"),
+        ("x += 1", "18446744073709551617"),
+        ("x % 7", "3"),
+        ("ans * _", "9"),
+        ("y = z = x - 1", "18446744073709551616"),
+        ("z /= 2 ^ 60", "16"),
+        ("z ^= 2", "256"),
+        ("_ - 6", "250"),
+    ];
+    for &(source, expected) in cases.iter() {
+        let value = evaluate(source, &mut env).unwrap().unwrap();
+        assert!(value.to_string() == expected, "\nEvaluated {} as {}\n", source, value);
+    }
+    let names : Vec<&str> = env.variables().map(|(name, _)| name.as_str()).collect();
+    assert!(names == ["x", "y", "z"] && env.last() == Some(&Integer::from(250)), "\nEvaluated the variables as {:?}\n", names);
+
+    let errors = [
+        ("w += 1", EvalErrorKind::UndefinedVariable("w".to_string()), 0, 1),
+        ("1 + w", EvalErrorKind::UndefinedVariable("w".to_string()), 4, 5),
+        ("ans = 1", EvalErrorKind::ReadOnlyVariable("ans".to_string()), 0, 3),
+        ("z %= 0", EvalErrorKind::Arithmetic(ArithmeticError::DivisionByZero), 0, 6),
+    ];
+    for &(source, ref kind, start, end) in errors.iter() {
+        match evaluate(source, &mut env) {
+            Err(LineError::Eval(ref error)) if error.kind() == kind && error.span() == Span {start, end} => {}
+            result => panic!("\nEvaluated {} as {:?}\n", source, result),
+        }
+    }
+    // failed lines change nothing
+    assert!(env.get("z") == Some(&Integer::from(256)) && env.get("w").is_none() && env.last() == Some(&Integer::from(250)), "\nEvaluated a failed line as changing the environment\n");
+    env.clear();
+    assert!(env.variables().next().is_none() && env.last().is_none(), "\nEvaluated the cleared environment as not empty\n");
+} // End of variables_test
+
+// Tests comparisons, conditionals, definitions, recursion, scoping and the errors of calls.
+#[test]
+fn functions_test() {
+    let mut env = Environment::new();
+    let definitions = [
+        "f(x, y) = x ^ 2 + y",
+        "fact(n) = if n <= 1 then 1 else n * fact(n - 1)",
+        "gcd(a, b) = if b == 0 then a else gcd(b, a % b)",
+        "even(n) = if n == 0 then 1 else odd(n - 1)",
+        "odd(n) = if n == 0 then 0 else even(n - 1)",
+        "scaled(x) = x * k",
+        "outer(y) = inner()",
+        "inner() = y",
+    ];
+    for source in definitions.iter() {
+        assert!(evaluate(source, &mut env) == Ok(None), "\nEvaluated the definition {} as a value\n", source);
+    }
+    assert!(env.last().is_none() && env.functions().count() == definitions.len(), "\nEvaluated the definitions as setting ans\n");
+    let cases = [
+        ("f(3, 4)", "13"),
+        ("fact(30)", "265252859812191058636308480000000"),
+        ("gcd(2 ^ 40 * 3, 6 ^ 20)", "3145728"),
+        ("even(10) + odd(7) * 2", "3"),
+        ("(3 < 4) + (4 < 4) + (4 <= 4) + (5 > 4) + (4 >= 5) + (2 == 2) + (2 != 2)", "4"),
+        ("-3 < -2 == 1", "1"),
+        ("k = 7", "7"),
+        ("scaled(6)", "42"),
+        ("fib(100) + lucas(0)", "354224848179261915077"),
+        ("fib(2 ^ 20) % 10 + lucas(2 ^ 20) % 10", "14"),
+        ("if 0 then 1 / 0 else 2", "2"),
+    ];
+    for &(source, expected) in cases.iter() {
+        let value = evaluate(source, &mut env).unwrap().unwrap();
+        assert!(value.to_string() == expected, "\nEvaluated {} as {}\n", source, value);
+    }
+
+    let errors = [
+        ("f(1)", EvalErrorKind::ArgumentCount("f".to_string(), 2, 1), 0, 4),
+        ("1 + g(2)", EvalErrorKind::UndefinedFunction("g".to_string()), 4, 8),
+        ("fib(1, 2)", EvalErrorKind::ArgumentCount("fib".to_string(), 1, 2), 0, 9),
+        ("fib(-1)", EvalErrorKind::NegativeArgument("fib".to_string()), 0, 7),
+        ("lucas(2 ^ 40)", EvalErrorKind::TooLarge, 0, 13),
+        ("fib(2 ^ 20 + 1)", EvalErrorKind::TooLarge, 0, 15),
+        // the y of outer is not visible in inner, and the error points at the call on this line
+        ("outer(5)", EvalErrorKind::UndefinedVariable("y".to_string()), 0, 8),
+        ("2 * fact(1 / 0)", EvalErrorKind::Arithmetic(ArithmeticError::DivisionByZero), 9, 14),
+    ];
+    for &(source, ref kind, start, end) in errors.iter() {
+        match evaluate(source, &mut env) {
+            Err(LineError::Eval(ref error)) if error.kind() == kind && error.span() == Span {start, end} => {}
+            result => panic!("\nEvaluated {} as {:?}\n", source, result),
+        }
+    }
+    let error = evaluate("f(1)", &mut env).unwrap_err();
+    assert!(error.to_string() == "error at column 1: f takes 2 arguments but was given 1", "\nEvaluated the arity error as {}\n", error);
+
+    // redefining a function changes the calls that refer to it
+    evaluate("f(x, y) = x - y", &mut env).unwrap();
+    assert!(evaluate("f(3, 4)", &mut env) == Ok(Some(Integer::from(-1))), "\nEvaluated the redefined f as the old one\n");
+
+    // the deepest evaluations take more stack than a test thread has. Each call of down nests
+    // three levels, and each call of g a thousand negations as well
+    let (fits, deeper) = (MAX_EVAL_DEPTH / 3 - 2, MAX_EVAL_DEPTH / 3);
+    let depths = ::std::thread::Builder::new().stack_size(STACK_SIZE).spawn(move || {
+        let mut env = Environment::new();
+        evaluate("down(n) = if n == 0 then 0 else 1 + down(n - 1)", &mut env).unwrap();
+        evaluate(&format!("g(n) = if n == 0 then 0 else {}g(n - 1)", "-".repeat(1000)), &mut env).unwrap();
+        let sources = [format!("down({})", fits), format!("down({})", deeper), "g(999)".to_string()];
+        sources.iter().map(|source| (source.clone(), evaluate(source, &mut env))).collect::<Vec<_>>()
+    }).unwrap().join().unwrap();
+    assert!(depths[0].1 == Ok(Some(Integer::from(fits as u64))), "\nEvaluated {} as {:?}\n", depths[0].0, depths[0].1);
+    for (source, result) in depths[1..].iter() {
+        match *result {
+            Err(LineError::Eval(ref error)) if *error.kind() == EvalErrorKind::RecursionLimit && error.span() == Span {start: 0, end: source.len()} => {}
+            _ => panic!("\nEvaluated {} as {:?}\n", source, result),
+        }
+    }
+} // End of functions_test
@@ -9,14 +9,42 @@
 /* ============================================================================================ */
 /*     Modules                                                                                  */
 /* ============================================================================================ */
-use std::ops::{Add, Sub, Div, Mul, Rem, Neg};
+use std::ops::{Add, Sub, Div, Mul, Rem, Neg, AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
 use std::cmp::{PartialEq,PartialOrd,Ordering};
+use std::hash::{Hash, Hasher};
 use std::fmt;
-use std::{u64,i64};
+use std::error::Error;
+use std::str::FromStr;
+
+#[macro_use]
+mod macros;
+mod bits;
+mod combinatorics;
+mod congruence;
+mod convert;
+mod div;
+mod divisor;
+mod factor;
+mod gcd;
+mod lucas;
+mod modular;
+mod mul;
+mod ntt;
+mod prime;
+mod primitive;
+mod radix;
+mod root;
+mod symbol;
+
+pub use self::divisor::Divisors;
+pub use self::factor::FactorLimits;
+pub use self::modular::Montgomery;
+pub use self::mul::MulThresholds;
 
 /* ============================================================================================ */
 /*     Struct                                                                                   */
 /* ============================================================================================ */
+#[derive(Clone)]
 pub struct Integer {
     value : Vec<u64>,   // a vector of 64bit ints that grows in size to hold larger numbers.
     negative: bool,     // determines whether the number is positive or not.
@@ -28,35 +56,17 @@ impl Integer {
 /*     Constructors                                                                             */
 /* ============================================================================================ */
 
-    // generates a Integer from a unsigned 32bit integer.
-    #[inline]
-    pub fn from_u32(init : u32) -> Integer {
-        Integer::from_u64(init as u64)
-    } // End of from_u64 function
-
-    // generate a Integer from a signed 32bit integer
-    #[inline]
-    pub fn from_i32(init : i32) -> Integer {
-        Integer::from_i64(init as i64)
-    }
-
-    // generates a Integer from a unsigned 64bit integer.
-    pub fn from_u64(init : u64) -> Integer {
-        Integer {
-            value: vec![init],
-            negative: false,
-        }
-    } // End of from_u64 function
-
-    // generate a Integer from a signed 64bit integer
-    pub fn from_i64(init : i64) -> Integer {
-        let negative =  if init < 0 {true} else {false};
-        let init : u64 = init.abs() as u64;
-        Integer {
-            value: vec![init],
-            negative: negative,
-        }
-    } // End of from_i64 function
+    // parses a string of digits in the given radix, with an optional sign and underscores
+    // between digits, such as "-ff_ff" in radix 16. Letters may be upper or lower case.
+    // Panics if the radix is not between 2 and 36.
+    pub fn from_str_radix(s : &str, radix : u32) -> Result<Integer, ParseIntegerError> {
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        Ok(Integer::from_parts(radix::from_radix(digits, radix)?, negative))
+    } // End of from_str_radix function
 
 /* ============================================================================================ */
 /*     Methods                                                                                  */
@@ -71,53 +81,328 @@ impl Integer {
 
     // returns a Ordering reguardless of sign
     pub fn abs_cmp(&self,other : &Integer) -> Option<Ordering> {
-        if self.value.len() > other.value.len() {
-            return Some(Ordering::Greater);
-        } else if self.value.len() < other.value.len() {
-            return Some(Ordering::Less);
-        }
+        Some(cmp_limbs(&self.value, &other.value))
+    } // End of abs_cmp function
 
-        for i in self.value.iter().rev().zip(other.value.iter().rev()){
-            let (a,b) = i;
-            if a < b {return Some(Ordering::Less)}
-            if a > b {return Some(Ordering::Greater)}
+    // returns a new Integer that is self ^ other, or an error if other is negative
+    // since the result would not be an Integer.
+    pub fn pow(&self, other : &Integer) -> Result<Integer, ArithmeticError> {
+        if other.negative {
+            return Err(ArithmeticError::NegativeExponent);
+        }
+        Ok(self.pow_limbs(&other.value))
+    } // End of pow function
+
+    // returns a new Integer that is self ^ exponent.
+    pub fn pow_u32(&self, exponent : u32) -> Integer {
+        self.pow_limbs(&[exponent as u64])
+    } // End of pow_u32 function
+
+    // returns a new Integer that is self ^ exponent.
+    pub fn pow_u64(&self, exponent : u64) -> Integer {
+        self.pow_limbs(&[exponent])
+    } // End of pow_u64 function
+
+    // raises self to the power held in the exponent limbs.
+    fn pow_limbs(&self, exponent : &[u64]) -> Integer {
+        let exponent = significant(exponent);
+        let bits = exponent.len() * 64 - exponent[exponent.len()-1].leading_zeros() as usize;
+        let bit = |i : usize| (exponent[i / 64] >> (i % 64)) & 1 == 1;
+        let negative = self.negative && bit(0);
+
+        // check special cases for quicker execution
+        if bits == 0 {
+            return Integer::from(1);
+        } else if self.is_zero() || significant(&self.value) == [1] {
+            return Integer::from_parts(significant(&self.value).to_vec(), negative);
         }
-        return Some(Ordering::Equal);
-    } // End of abs_cmp function
 
-    // returns a new Integer that is the self ^ other
-    pub fn pow(&self,other :&Integer) -> Integer {
-        if (other.negative) {
-            panic!("Integers are not rational numbers!");
+        let result = sliding_window_pow(self.abs(), exponent, Integer::from(1), Integer::square, |x, y| x * y);
+        Integer::from_parts(result.value, negative)
+    } // End of pow_limbs function
+
+    // check to see if zero
+    pub fn is_zero(&self) -> bool {
+        significant(&self.value) == [0]
+    } // End of is_zero function
+
+    // returns self * self, which is cheaper than a general multiplication.
+    pub fn square(&self) -> Integer {
+        Integer::from_parts(mul::square(&self.value), false)
+    } // End of square function
+
+    // returns self ^ exponent mod |modulus| as the least non-negative residue.
+    // Odd moduli use Montgomery multiplication, even moduli use Barrett reduction. To reuse the
+    // setup work across many exponentiations with one odd modulus, build a Montgomery context.
+    pub fn mod_pow(&self, exponent : &Integer, modulus : &Integer) -> Result<Integer, ArithmeticError> {
+        match Montgomery::new(modulus) {
+            Some(context) => context.pow_mod(self, exponent),
+            None => modular::barrett_pow_mod(self, exponent, modulus),
+        }
+    } // End of mod_pow function
+
+    // returns the operand sizes, in limbs, at which multiplication switches algorithm.
+    pub fn mul_thresholds() -> MulThresholds {
+        mul::thresholds()
+    } // End of mul_thresholds function
+
+    // sets the operand sizes, in limbs, at which every later multiplication switches algorithm,
+    // so the crossover points can be tuned for the machine the program runs on.
+    pub fn set_mul_thresholds(thresholds : MulThresholds) {
+        mul::set_thresholds(thresholds)
+    } // End of set_mul_thresholds function
+
+    // returns the digits of the number in the given radix with lower case letters and a leading
+    // '-' for negative numbers. Panics if the radix is not between 2 and 36.
+    pub fn to_str_radix(&self, radix : u32) -> String {
+        let digits = radix::to_radix(&self.value, radix);
+        if self.negative {format!("-{}", digits)} else {digits}
+    } // End of to_str_radix function
+
+    // returns a view of the number whose formatters separate the digits into groups of size
+    // digits, such as format!("{:X}", n.grouped(2)) for space separated bytes.
+    // Panics if size is zero.
+    pub fn grouped(&self, size : usize) -> Grouped<'_> {
+        assert!(size > 0, "digit groups must hold at least one digit");
+        Grouped {
+            integer: self,
+            size,
+        }
+    } // End of grouped function
+
+    // returns the quotient and remainder of self / other in a single division.
+    // The quotient is truncated toward zero and the remainder takes the sign of self,
+    // matching the behaviour of Rust's primitive integers.
+    // Panics if other is zero.
+    pub fn div_rem(&self, other : &Integer) -> (Integer, Integer) {
+        let (quotient, remainder) = div::div_rem(&self.value, &other.value);
+        (Integer::from_parts(quotient, self.negative != other.negative),
+         Integer::from_parts(remainder, self.negative))
+    } // End of div_rem function
+
+    // returns self / other, or None if other is zero.
+    pub fn checked_div(&self, other : &Integer) -> Option<Integer> {
+        if other.is_zero() {
+            None
+        } else {
+            Some(self.div_rem(other).0)
         }
+    } // End of checked_div function
 
-        //check special cases for quicker execution
+    // returns self % other, or None if other is zero.
+    pub fn checked_rem(&self, other : &Integer) -> Option<Integer> {
         if other.is_zero() {
-            return Integer::from_i32(1);
-        } else if self.is_zero() {
-            return Integer::from_i32(0);
+            None
+        } else {
+            Some(self.div_rem(other).1)
+        }
+    } // End of checked_rem function
+
+    // adds the number with the given magnitude and sign to self, reusing self's limbs.
+    fn add_signed(&mut self, value : &[u64], negative : bool) {
+        if self.negative == negative {
+            add_assign_limbs(&mut self.value, value);
+        } else if cmp_limbs(&self.value, value) == Ordering::Less {
+            sub_assign_limbs(&mut self.value, value, true);
+            self.negative = negative;
+        } else {
+            sub_assign_limbs(&mut self.value, value, false);
         }
+        if self.value == [0] {
+            self.negative = false;
+        }
+        self.validate();
+    } // End of add_signed function
+
+    // swaps a result computed into a scratch buffer in as self's magnitude, with the given sign.
+    fn swap_value(&mut self, mut value : Vec<u64>, negative : bool) {
+        trim(&mut value);
+        ::std::mem::swap(&mut self.value, &mut value);
+        self.negative = negative && self.value != [0];
+        self.validate();
+    } // End of swap_value function
+
+    // builds an Integer from a magnitude and sign, zero is never negative.
+    // Every Integer is built through here or from a value that is already canonical.
+    fn from_parts(mut value : Vec<u64>, negative : bool) -> Integer {
+        trim(&mut value);
+        let is_zero = value == [0];
+        let integer = Integer {
+            value,
+            negative: negative && !is_zero,
+        };
+        integer.validate();
+        integer
+    } // End of from_parts function
+
+    // checks, in debug builds, that the number is in canonical form: at least one limb, no zero
+    // limbs above the most significant one, and zero is never negative. Equality, ordering and
+    // is_zero rely on this so that each value has exactly one representation.
+    fn validate(&self) {
+        debug_assert!(!self.value.is_empty(), "Integer has no limbs");
+        debug_assert!(self.value.len() == 1 || self.value[self.value.len()-1] != 0,
+            "Integer has leading zero limbs: {:?}", self.value);
+        debug_assert!(!(self.negative && self.value == [0]), "Integer is a negative zero");
+    } // End of validate function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Limb helpers                                                                             */
+/* ============================================================================================ */
 
-        // calculate the result
-        let mut result = Integer::from_i32(1);
-        let mut count = Integer::from_i32(0);
-        let one = Integer::from_u64(1u64);
-        while &count < other {
-            result = &result * &self;
-            count = &count + &one;
+// returns the limbs without any zero limbs above the most significant one, zero is [0].
+fn significant(value : &[u64]) -> &[u64] {
+    let mut len = value.len();
+    while len > 1 && value[len-1] == 0 {
+        len -= 1;
+    }
+    if len == 0 {&[0]} else {&value[..len]}
+} // End of significant function
+
+// removes the zero limbs above the most significant one, leaving [0] for zero.
+fn trim(value : &mut Vec<u64>) {
+    let len = significant(value).len();
+    value.truncate(len);
+    if value.is_empty() {
+        value.push(0);
+    }
+} // End of trim function
+
+// returns the sum of two magnitudes.
+fn add_limbs(a : &[u64], b : &[u64]) -> Vec<u64> {
+    let (long, short) = if a.len() >= b.len() {(a, b)} else {(b, a)};
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = false;
+    for (i, limb) in long.iter().enumerate() {
+        let addend = if i < short.len() {short[i]} else {0};
+        let (new_num, overflow1) = limb.overflowing_add(addend);
+        let (new_num, overflow2) = new_num.overflowing_add(carry as u64);
+        carry = overflow1 || overflow2;
+        sum.push(new_num);
+    }
+    if carry {
+        sum.push(1);
+    }
+    trim(&mut sum);
+    sum
+} // End of add_limbs function
+
+// returns the difference of two magnitudes, a must be at least as large as b.
+fn sub_limbs(a : &[u64], b : &[u64]) -> Vec<u64> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = false;
+    for (i, limb) in a.iter().enumerate() {
+        let subtrahend = if i < b.len() {b[i]} else {0};
+        let (new_num, overflow1) = limb.overflowing_sub(subtrahend);
+        let (new_num, overflow2) = new_num.overflowing_sub(borrow as u64);
+        borrow = overflow1 || overflow2;
+        difference.push(new_num);
+    }
+    debug_assert!(!borrow && significant(b).len() <= a.len(), "subtracted a larger magnitude from a smaller one");
+    trim(&mut difference);
+    difference
+} // End of sub_limbs function
+
+// adds the magnitude b into a in place, growing a only when the sum needs more limbs.
+fn add_assign_limbs(a : &mut Vec<u64>, b : &[u64]) {
+    let b = significant(b);
+    if a.len() < b.len() {
+        a.resize(b.len(), 0);
+    }
+    let mut carry = false;
+    for i in 0..a.len() {
+        if i >= b.len() && !carry {
+            break;
         }
-        result
+        let addend = if i < b.len() {b[i]} else {0};
+        let (new_num, overflow1) = a[i].overflowing_add(addend);
+        let (new_num, overflow2) = new_num.overflowing_add(carry as u64);
+        carry = overflow1 || overflow2;
+        a[i] = new_num;
+    }
+    if carry {
+        a.push(1);
+    }
+    trim(a);
+} // End of add_assign_limbs function
+
+// replaces a with a - b in place when reverse is false, or with b - a when it is true.
+// The magnitude subtracted from must be at least as large as the other.
+fn sub_assign_limbs(a : &mut Vec<u64>, b : &[u64], reverse : bool) {
+    let b = significant(b);
+    if a.len() < b.len() {
+        a.resize(b.len(), 0);
+    }
+    let mut borrow = false;
+    for i in 0..a.len() {
+        if i >= b.len() && !borrow && !reverse {
+            break;
+        }
+        let other = if i < b.len() {b[i]} else {0};
+        let (minuend, subtrahend) = if reverse {(other, a[i])} else {(a[i], other)};
+        let (new_num, overflow1) = minuend.overflowing_sub(subtrahend);
+        let (new_num, overflow2) = new_num.overflowing_sub(borrow as u64);
+        borrow = overflow1 || overflow2;
+        a[i] = new_num;
+    }
+    debug_assert!(!borrow, "subtracted a larger magnitude from a smaller one");
+    trim(a);
+} // End of sub_assign_limbs function
+
+// raises base to the power held in the exponent limbs with sliding window exponentiation over
+// any multiplication, such as Integer products or products modulo a number.
+// The exponent is scanned from its most significant bit, squaring once per bit and multiplying
+// by a precomputed odd power of the base once per window of up to k bits.
+fn sliding_window_pow<T, S, M>(base : T, exponent : &[u64], one : T, square : S, mul : M) -> T
+    where S : Fn(&T) -> T, M : Fn(&T, &T) -> T {
+    let exponent = significant(exponent);
+    let bits = exponent.len() * 64 - exponent[exponent.len()-1].leading_zeros() as usize;
+    let bit = |i : usize| (exponent[i / 64] >> (i % 64)) & 1 == 1;
+
+    // the odd powers base^1, base^3, .. base^(2^k - 1)
+    let window = match bits {
+        0..=8 => 1,
+        9..=64 => 3,
+        65..=1024 => 4,
+        _ => 5,
+    };
+    let base_squared = square(&base);
+    let mut odd_powers = vec![base];
+    for i in 1..(1 << (window - 1)) {
+        let next = mul(&odd_powers[i-1], &base_squared);
+        odd_powers.push(next);
     }
 
-    // check to see if zero
-    pub fn is_zero(&self) -> bool {
-        if self.value.len() == 1 && self.value[0] == 0 {
-            true
-        } else {
-            false
+    let mut result = one;
+    let mut top = bits;
+    while top > 0 {
+        let i = top - 1;
+        if !bit(i) {
+            result = square(&result);
+            top = i;
+            continue;
         }
-    } // End of is_zero function
-} // End of Integer Implementation
+        // the longest window of at most k bits that starts at bit i and ends in a set bit
+        let mut low = (i + 1).saturating_sub(window);
+        while !bit(low) {
+            low += 1;
+        }
+        let mut window_value = 0;
+        for k in (low..=i).rev() {
+            window_value = (window_value << 1) | bit(k) as usize;
+            result = square(&result);
+        }
+        result = mul(&result, &odd_powers[window_value / 2]);
+        top = low;
+    }
+    result
+} // End of sliding_window_pow function
+
+// compares two magnitudes, ignoring any zero limbs above the most significant one.
+fn cmp_limbs(a : &[u64], b : &[u64]) -> Ordering {
+    let (a, b) = (significant(a), significant(b));
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+} // End of cmp_limbs function
 
 /* ============================================================================================ */
 /*     Operatiors [+ - / %]                                                                     */
@@ -125,68 +410,31 @@ impl Integer {
 
 // Overloads the + operator for two numbers such that Integer + Integer = Integer.
 // Returns a new Integer that is the summation of the two input numbers.
-impl<'a,'b> Add<&'b Integer> for &'a Integer {
+impl<'b> Add<&'b Integer> for &Integer {
     type Output = Integer;
 
     fn add(self, num :&'b Integer) -> Integer {
-        // Initialize the parameters for the new Integer
-        let capacity = self.value.len().max(num.value.len()) + 1;
-        let mut new_value = Vec::with_capacity(capacity);
-        let mut carry = 0;
-        // Sort the two Integers by absolute value
-        let (min,max) = match self.abs_cmp(num) {
-            Some(Ordering::Less) => (self,num),
-            _ => (num,self)
-        };
-        // Loop through the two numbers and add or subtract depending on sign
-        for i in min.value.iter().zip(max.value.iter()){
-            let (a,b) = (i.0.clone(),i.1.clone());
-
-            // If the sign is the same, add the two, else subtract the two
-            if min.negative == max.negative {
-                let (new_num,overflow1) = a.overflowing_add(b);
-                let (new_num,overflow2) = new_num.overflowing_add(carry);
-                carry = if overflow1 || overflow2 {1} else {0};
-                new_value.push(new_num);
-            } else {
-                let (new_num,c) = if b < a || b < (a + carry) {
-                    ((u64::MAX-a)+(b+1),1)
-                } else {
-                    (b-a-carry,0)
-                };
-                carry = c;
-                new_value.push(new_num);
-            }
+        // If the sign is the same, add the magnitudes
+        if self.negative == num.negative {
+            return Integer::from_parts(add_limbs(&self.value, &num.value), self.negative);
         }
 
-        // If there is a carry after the loop then a new block of bits is needed to store the Int
-        if carry > 0 && max.negative == min.negative {
-            new_value.push(carry);
-        }
-
-        // Calculates the sign for the new Integer
-        let sign = if new_value.len() == 1 && new_value[0] == 0 {
-            false
-        } else {
-            max.negative
-        };
-
-        // Create and return the new Integer
-        Integer {
-            value: new_value,
-            negative: sign,
+        // Otherwise subtract the smaller magnitude from the larger, which decides the sign
+        match cmp_limbs(&self.value, &num.value) {
+            Ordering::Less => Integer::from_parts(sub_limbs(&num.value, &self.value), num.negative),
+            _ => Integer::from_parts(sub_limbs(&self.value, &num.value), self.negative),
         }
     } // End of add function
 } // End of Add Implementation
 
 // Overloads the unary - operator such that -Integer is the negation of the number.
 // Returns a new Integer that is the negation of the input numbers.
-impl<'a> Neg for &'a Integer {
+impl Neg for &Integer {
     type Output = Integer;
 
     fn neg(self) -> Integer {
         if self.is_zero(){
-            Integer::from_u64(0)
+            Integer::from(0)
         } else {
             Integer{
                 value: self.value.clone(),
@@ -198,7 +446,7 @@ impl<'a> Neg for &'a Integer {
 
 // Overloads the - operator such that Integer - Integer is the summation of the negation.
 // Returns a new Integer that is the summation of the negation.
-impl<'a,'b> Sub<&'b Integer> for &'a Integer {
+impl<'b> Sub<&'b Integer> for &Integer {
     type Output = Integer;
 
     #[inline]
@@ -209,67 +457,173 @@ impl<'a,'b> Sub<&'b Integer> for &'a Integer {
 
 // Overloads the * operator such that Intger - Integer is the product of the two numbers
 // Returns a new Integer that is the product of the input numbers.
-impl<'a,'b> Mul<&'b Integer> for &'a Integer {
+impl<'b> Mul<&'b Integer> for &Integer {
     type Output = Integer;
 
     fn mul(self, num :&'b Integer) -> Integer {
-        let (min,max) = match self.abs_cmp(num) {
-            Some(Ordering::Less) => (self,num),
-            _ => (num,self)
-        };
-        let mut new_value = Vec::with_capacity(self.value.len()+num.value.len());
-        let mut carry : u64 = 0;
-        for min_i in min.value.iter() {
-            // break the 64 bit number into two 32 bit numbers
-            let (min_low_32, min_high_32) = (min_i & 0xFFFFFFFF, min_i >> 32);
-            let mut init_index = 0;
-            // run the broken up numbers seperately so that 32bit * 32bit numbers produce 64bit
-            for i in 0..2 {
-                //determine which number will run in this loop
-                let min_num = if i == 0 {min_low_32} else {min_high_32};
-                let mut index = init_index + i;
-                for max_i in max.value.iter() {
-                    // break the 64 bit number into two 32 bit numbers
-                    let (max_low_32, max_high_32) = (max_i & 0xFFFFFFFF, max_i >> 32);
-                    // multiply out the two sections
-                    let low_multiplied = min_num * max_low_32;
-                    let high_multiplied = min_num * max_high_32;
-                    // prepare the high_multiplied value for summation
-                    let high_value = (high_multiplied << 32) + carry;
-                    // perform the addition and check for overflow, add the overflow to the carry
-                    let (sum,overflow) = low_multiplied.overflowing_add(high_value);
-                    carry = (high_multiplied >> 32) + if overflow {1u64} else {0u64};
-                    // sum the newly calculated value on the vector
-                    if new_value.len() < index + 1 {
-                        // if the vector is empty, push the number onto the vector
-                        new_value.push(sum);
-                    } else {
-                        // if the vector is !empty, sum the calculated value with the old value
-                        let (sum,overflow) = sum.overflowing_add(new_value[index]);
-                        carry = carry + if overflow {1u64} else {0u64};
-                        new_value[index] = sum;
-                    }
-                    // Iterate through the index so the min_high_32 knows where to begin
-                    index = index + 1;
-                }
-                // Iterate the init_index such that future iterations know where to begin
-                init_index = init_index + 2;
-            }
-        }
-        // Calculates the sign for the new Integer
-        let mut is_zero = false;
-        if new_value.len() == 2 && new_value[0] == 0u64 && new_value[1] == 0u64{
-            is_zero = true;
-            new_value = vec!(0);
-        }
-        let sign = if is_zero {false} else {min.negative != max.negative};
-        Integer {
-            value: new_value,
-            negative: sign,
-        }
+        Integer::from_parts(mul::mul(&self.value, &num.value), self.negative != num.negative)
     } // End of mul function
 } // End of Mul implementation
 
+// Overloads the / operator such that Integer / Integer is the quotient truncated toward zero.
+// Returns a new Integer that is the quotient of the input numbers, panics on division by zero.
+impl<'b> Div<&'b Integer> for &Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn div(self, num : &'b Integer) -> Integer {
+        self.div_rem(num).0
+    } // End of div function
+} // End of Div implementation
+
+// Overloads the % operator such that Integer % Integer is the remainder of truncated division.
+// Returns a new Integer with the sign of the dividend, panics on division by zero.
+impl<'b> Rem<&'b Integer> for &Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn rem(self, num : &'b Integer) -> Integer {
+        self.div_rem(num).1
+    } // End of rem function
+} // End of Rem implementation
+
+// Overloads + for owned operands, adding into whichever operand is owned.
+impl<'b> Add<&'b Integer> for Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn add(mut self, num : &'b Integer) -> Integer {
+        self += num;
+        self
+    } // End of add function
+} // End of Add implementation
+
+impl Add<Integer> for Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn add(self, num : Integer) -> Integer {
+        self + &num
+    } // End of add function
+} // End of Add implementation
+
+impl Add<Integer> for &Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn add(self, num : Integer) -> Integer {
+        num + self
+    } // End of add function
+} // End of Add implementation
+
+// Overloads - for owned operands, subtracting in whichever operand is owned.
+impl<'b> Sub<&'b Integer> for Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn sub(mut self, num : &'b Integer) -> Integer {
+        self -= num;
+        self
+    } // End of sub function
+} // End of Sub implementation
+
+impl Sub<Integer> for Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn sub(self, num : Integer) -> Integer {
+        self - &num
+    } // End of sub function
+} // End of Sub implementation
+
+impl Sub<Integer> for &Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn sub(self, num : Integer) -> Integer {
+        -num + self
+    } // End of sub function
+} // End of Sub implementation
+
+// Overloads the unary - operator for an owned Integer by flipping its sign in place.
+impl Neg for Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn neg(mut self) -> Integer {
+        self.negative = !self.negative && !self.is_zero();
+        self
+    } // End of neg function
+} // End of Neg implementation
+
+forward_binop!(Mul, mul, MulAssign, mul_assign, commutative);
+forward_binop!(Div, div, DivAssign, div_assign);
+forward_binop!(Rem, rem, RemAssign, rem_assign);
+
+/* ============================================================================================ */
+/*     Assignment Operators [+= -= *= /= %=]                                                    */
+/* ============================================================================================ */
+
+// Overloads the += operator, adding into the left operand's limbs in place.
+impl<'b> AddAssign<&'b Integer> for Integer {
+    fn add_assign(&mut self, num : &'b Integer) {
+        self.add_signed(&num.value, num.negative);
+    } // End of add_assign function
+} // End of AddAssign implementation
+
+impl AddAssign<Integer> for Integer {
+    #[inline]
+    fn add_assign(&mut self, num : Integer) {
+        *self += &num;
+    } // End of add_assign function
+} // End of AddAssign implementation
+
+// Overloads the -= operator, subtracting from the left operand's limbs in place.
+impl<'b> SubAssign<&'b Integer> for Integer {
+    fn sub_assign(&mut self, num : &'b Integer) {
+        self.add_signed(&num.value, !num.negative);
+    } // End of sub_assign function
+} // End of SubAssign implementation
+
+impl SubAssign<Integer> for Integer {
+    #[inline]
+    fn sub_assign(&mut self, num : Integer) {
+        *self -= &num;
+    } // End of sub_assign function
+} // End of SubAssign implementation
+
+// Overloads the *= operator. A product cannot be written over the limbs it is read from, so it
+// goes into a scratch buffer that is swapped in for the left operand's limbs.
+impl<'b> MulAssign<&'b Integer> for Integer {
+    fn mul_assign(&mut self, num : &'b Integer) {
+        let product = mul::mul(&self.value, &num.value);
+        let negative = self.negative != num.negative;
+        self.swap_value(product, negative);
+    } // End of mul_assign function
+} // End of MulAssign implementation
+
+// Overloads the /= operator, swapping the quotient in for the left operand's limbs.
+impl<'b> DivAssign<&'b Integer> for Integer {
+    fn div_assign(&mut self, num : &'b Integer) {
+        let (quotient, _) = div::div_rem(&self.value, &num.value);
+        let negative = self.negative != num.negative;
+        self.swap_value(quotient, negative);
+    } // End of div_assign function
+} // End of DivAssign implementation
+
+// Overloads the %= operator, swapping the remainder in for the left operand's limbs.
+impl<'b> RemAssign<&'b Integer> for Integer {
+    fn rem_assign(&mut self, num : &'b Integer) {
+        let (_, remainder) = div::div_rem(&self.value, &num.value);
+        let negative = self.negative;
+        self.swap_value(remainder, negative);
+    } // End of rem_assign function
+} // End of RemAssign implementation
+
+forward_assign!(MulAssign, mul_assign);
+forward_assign!(DivAssign, div_assign);
+forward_assign!(RemAssign, rem_assign);
+
 /* ============================================================================================ */
 /*     Operatiors and Ordering [== != > < >= <=]                                                */
 /* ============================================================================================ */
@@ -280,28 +634,8 @@ impl PartialEq for Integer {
     // Overloads the == operator and checks if two Integers are equal.
     // Returns true if the two Integers are equal to one another, false otherwise.
     fn eq(&self, num : &Integer) -> bool{
-        if self.negative != num.negative {
-            return false;
-        }
-        for i in self.value.iter().zip(num.value.iter()){
-            let (a,b) = i;
-            if a != b {return false};
-        }
-        return true;
+        self.negative == num.negative && significant(&self.value) == significant(&num.value)
     } // End of eq function
-
-    // Overloads the != operator and checks if two Integers are equal.
-    // Returns false if the two Integers are equal to one another, true otherwise.
-    fn ne(&self, num : &Integer) -> bool{
-        for i in self.value.iter().zip(num.value.iter()){
-            if self.negative != num.negative {
-                return true;
-            }
-            let (a,b) = i;
-            if a == b {return false};
-        }
-        return true;
-    } // End of ne function
 } // End of PartialEq implementation
 
 // Implements Partial Ordering [< > <= >=] for type Integer
@@ -309,118 +643,276 @@ impl PartialOrd for Integer {
 
     // Comapres two Integers and returns an Ordering.
     fn partial_cmp(&self, other: &Integer) -> Option<Ordering> {
-        if other.negative && !self.negative {
-            Some(Ordering::Greater)
-        } else if other.negative != self.negative {
-            Some(Ordering::Less)
-        } else {
-            self.abs_cmp(other)
-        }
+        Some(self.cmp(other))
     } // End of partial_cmp function
 
     // Compares two Integers and returns true if the other number is
     // Greater Than or Equal in value false otherwise.
     fn lt(&self, other : &Integer) -> bool {
-        match self.partial_cmp(other) {
-            Some(Ordering::Less) => true,
-            _ => false,
-        }
+        matches!(self.partial_cmp(other), Some(Ordering::Less))
     } // End of lt function
 
     // Compares two Integers and returns false if the other number is
     // Less Than or Equal in value true otherwise.
     fn gt(&self, other : &Integer) -> bool {
-        match self.partial_cmp(other) {
-            Some(Ordering::Greater) => true,
-            _ => false,
-        }
+        matches!(self.partial_cmp(other), Some(Ordering::Greater))
     } // End of gt function
 
     // Compares two Integers and returns true if the other number is
     // Less Than or Equal in value false otherwise.
     fn le(&self, other : &Integer) -> bool {
-        match self.partial_cmp(other) {
-            Some(Ordering::Less) => true,
-            Some(Ordering::Equal) => true,
-            _ => false,
-        }
+        matches!(self.partial_cmp(other), Some(Ordering::Less) | Some(Ordering::Equal))
     } // End of le function
 
     // Compares two Integers and returns false if the other number is
     // Less Than or Equal in value true otherwise.
     fn ge(&self, other : &Integer) -> bool {
-        match self.partial_cmp(other) {
-            Some(Ordering::Greater) => true,
-            Some(Ordering::Equal) => true,
-            _ => false,
-        }
+        matches!(self.partial_cmp(other), Some(Ordering::Greater) | Some(Ordering::Equal))
     } // End of ge function
 } // End of PartialOrd implementation
 
-/*
-impl<'a,'b> Div<&'b Integer> for &'a Integer {
-    type Output = Integer;
-
-    fn div(self, num :&'b Integer) -> Integer {
-        Integer {num: self.num / num.num}
-    }
-}
-
-impl<'a,'b> Rem<&'b Integer> for &'a Integer {
-    type Output = Integer;
+// Integers are totally ordered, and equal exactly when their canonical limbs and sign match.
+impl Eq for Integer {}
 
-    fn rem(self, num :&'b Integer) -> Integer {
-        Integer {num: self.num % num.num}
-    }
-}
+// Implements total Ordering for type Integer, so Integers can be sorted and used as keys.
+impl Ord for Integer {
+    fn cmp(&self, other : &Integer) -> Ordering {
+        if other.negative && !self.negative {
+            Ordering::Greater
+        } else if other.negative != self.negative {
+            Ordering::Less
+        } else if self.negative {
+            // the larger magnitude is the smaller negative number
+            cmp_limbs(&other.value, &self.value)
+        } else {
+            cmp_limbs(&self.value, &other.value)
+        }
+    } // End of cmp function
+} // End of Ord implementation
+
+// Hashes the sign and significant limbs, so equal Integers always hash the same.
+impl Hash for Integer {
+    fn hash<H : Hasher>(&self, state : &mut H) {
+        self.negative.hash(state);
+        significant(&self.value).hash(state);
+    } // End of hash function
+} // End of Hash implementation
+
+// The default Integer is zero.
+impl Default for Integer {
+    fn default() -> Integer {
+        Integer::from(0)
+    } // End of default function
+} // End of Default implementation
 
+/* ============================================================================================ */
+/*      Formatters                                                                              */
+/* ============================================================================================ */
 
+// Defines the {} fmt and converts the Integer to its decimal digits.
+// Width, fill, alignment, the + flag and zero padding are handled the same way as for primitives.
 impl fmt::Display for Integer {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.num)
-    }
-}
+        f.pad_integral(!self.negative, "", &radix::to_radix(&self.value, 10))
+    } // End of fmt function
+} // End of fmt::Display implementation
 
+// Defines the {:b} fmt and converts the Integer to binary, # adds the 0b prefix.
 impl fmt::Binary for Integer {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:064b}", self.num)
-    }
+        f.pad_integral(!self.negative, "0b", &radix::to_radix(&self.value, 2))
+    } // End of fmt function
+} // End of fmt::Binary implementation
+
+// Defines the {:?} fmt, which prints the decimal value like the primitive integers do.
+impl fmt::Debug for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    } // End of fmt function
+} // End of fmt::Debug implementation
+
+// Defines the {:o} fmt and converts the Integer to octal, # adds the 0o prefix.
+impl fmt::Octal for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "0o", &radix::to_radix(&self.value, 8))
+    } // End of fmt function
+} // End of fmt::Octal implementation
+
+// Defines the {:x} fmt and converts the Integer to lower case hexadecimal, # adds the 0x prefix.
+impl fmt::LowerHex for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "0x", &radix::to_radix(&self.value, 16))
+    } // End of fmt function
+} // End of fmt::LowerHex implementation
+
+// Defines the {:X} fmt and converts the Integer to upper case hexadecimal, # adds the 0x prefix.
+impl fmt::UpperHex for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "0x", &radix::to_radix(&self.value, 16).to_uppercase())
+    } // End of fmt function
+} // End of fmt::UpperHex implementation
+
+// A view of an Integer whose formatters separate the digits into groups with spaces.
+// Groups are counted from the least significant digit, so {:X} of Integer::grouped(2)
+// writes the bytes of the number : 1 FF FF.
+pub struct Grouped<'a> {
+    integer : &'a Integer,  // the number being formatted
+    size : usize,           // the number of digits in each group
 }
-*/
+
+impl<'a> Grouped<'a> {
+    // writes the digits of the number in groups, letting the Formatter handle sign, prefix and padding.
+    fn pad(&self, f : &mut fmt::Formatter, prefix : &str, digits : &str) -> fmt::Result {
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / self.size);
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(self.size) {
+                grouped.push(' ');
+            }
+            grouped.push(c);
+        }
+        f.pad_integral(!self.integer.negative, prefix, &grouped)
+    } // End of pad function
+} // End of Grouped implementation
+
+impl<'a> fmt::Display for Grouped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.pad(f, "", &radix::to_radix(&self.integer.value, 10))
+    } // End of fmt function
+} // End of fmt::Display implementation
+
+impl<'a> fmt::Binary for Grouped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.pad(f, "0b", &radix::to_radix(&self.integer.value, 2))
+    } // End of fmt function
+} // End of fmt::Binary implementation
+
+impl<'a> fmt::Octal for Grouped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.pad(f, "0o", &radix::to_radix(&self.integer.value, 8))
+    } // End of fmt function
+} // End of fmt::Octal implementation
+
+impl<'a> fmt::LowerHex for Grouped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.pad(f, "0x", &radix::to_radix(&self.integer.value, 16))
+    } // End of fmt function
+} // End of fmt::LowerHex implementation
+
+impl<'a> fmt::UpperHex for Grouped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.pad(f, "0x", &radix::to_radix(&self.integer.value, 16).to_uppercase())
+    } // End of fmt function
+} // End of fmt::UpperHex implementation
 
 /* ============================================================================================ */
-/*      Formatters                                                                              */
+/*      Errors                                                                                  */
 /* ============================================================================================ */
 
-// Defines the {:X} fmt and converts the two integers to Hexadecimal values
-impl fmt::UpperHex for Integer {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // Initialize the two strings that will be used to format the number
-        let mut string_value = String::with_capacity(self.value.len()*16);
-        let mut fmt_string = String::with_capacity(self.value.len()*24+1);
+// The reasons an arithmetic operation can fail to produce an Integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticError {
+    NegativeExponent,   // an Integer raised to a negative power is not an Integer
+    DivisionByZero,     // the divisor or modulus was zero
+    NegativeRoot,       // an even root of a negative number is not an Integer
+    ZeroRoot,           // the zeroth root of a number is undefined
+    InvalidModulus,     // the modulus must be odd and positive
+    CompositeModulus,   // the modulus must be an odd prime
+}
 
-        // Convert the number into Hex : FFFFFFFFFFFFFFFF
-        for i in self.value.iter().rev() {
-            string_value.push_str(&format!("{:016X}",i));
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ArithmeticError::NegativeExponent => write!(f, "cannot raise an integer to a negative power"),
+            ArithmeticError::DivisionByZero => write!(f, "attempt to divide by zero"),
+            ArithmeticError::NegativeRoot => write!(f, "cannot take an even root of a negative number"),
+            ArithmeticError::ZeroRoot => write!(f, "cannot take the zeroth root of a number"),
+            ArithmeticError::InvalidModulus => write!(f, "the modulus must be odd and positive"),
+            ArithmeticError::CompositeModulus => write!(f, "the modulus must be an odd prime"),
         }
+    } // End of fmt function
+} // End of fmt::Display implementation
+
+impl Error for ArithmeticError {}
 
-        // Add the '-' char if the number is a negative number : -FFFFFFFFFFFFFFFF
-        if self.negative  {
-            fmt_string.push('-');
+// The reasons a value can fail to convert between an Integer and a primitive type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionError {
+    Overflow,           // the Integer is outside the range of the primitive type
+    NotFinite,          // the float was NaN or infinite, which no Integer can hold
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConversionError::Overflow => write!(f, "integer out of range for the target type"),
+            ConversionError::NotFinite => write!(f, "cannot convert a NaN or infinite float to an integer"),
         }
+    } // End of fmt function
+} // End of fmt::Display implementation
 
-        // Space out the number to group it in twos : FF FF FF FF FF FF FF FF
-        for (i,c) in string_value.chars().enumerate(){
-            fmt_string.push(c);
-            if i % 2 != 0 {
-                fmt_string.push(' ');
-            }
+impl Error for ConversionError {}
+
+// The reasons a factorization can stop before every prime factor is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FactorError {
+    TimedOut,           // the time limit passed before the number was fully factored
+    Cancelled,          // the cancel flag was set while the number was being factored
+}
+
+impl fmt::Display for FactorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FactorError::TimedOut => write!(f, "factorization timed out"),
+            FactorError::Cancelled => write!(f, "factorization was cancelled"),
         }
+    } // End of fmt function
+} // End of fmt::Display implementation
+
+impl Error for FactorError {}
+
+/* ============================================================================================ */
+/*      Parsing                                                                                 */
+/* ============================================================================================ */
+
+// The reasons a string can fail to parse as an Integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Empty,          // the string held no digits
+    InvalidDigit,   // the string held a character that is not a digit, sign or separating underscore
+}
 
-        // Writes the formated string back
-        write!(f, "{}", fmt_string)
+// The error returned when a string can not be parsed as an Integer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIntegerError {
+    kind : ParseErrorKind,
+}
+
+impl ParseIntegerError {
+    // returns the reason the string failed to parse.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    } // End of kind function
+} // End of ParseIntegerError implementation
+
+impl fmt::Display for ParseIntegerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseErrorKind::InvalidDigit => write!(f, "invalid digit found in string"),
+        }
     } // End of fmt function
-} // End of fmt::UpperHex implementation
+} // End of fmt::Display implementation
+
+impl Error for ParseIntegerError {}
+
+// Parses a decimal string such as "-1_000_000" or "+007" into an Integer.
+impl FromStr for Integer {
+    type Err = ParseIntegerError;
+
+    fn from_str(s : &str) -> Result<Integer, ParseIntegerError> {
+        Integer::from_str_radix(s, 10)
+    } // End of from_str function
+} // End of FromStr implementation
 
 /* ============================================================================================ */
 /*      Test cases                                                                              */
@@ -431,22 +923,22 @@ impl fmt::UpperHex for Integer {
 // If two Integer have the same value then == returns true, other wise != returns true
 #[test]
 fn integer_equality_test() {
-    let zero = Integer::from_u64(0);
-    let ten1 = Integer::from_u64(10);
-    let ten2 = Integer::from_u64(10);
+    let zero = Integer::from(0);
+    let ten1 = Integer::from(10);
+    let ten2 = Integer::from(10);
     let neg_ten = -&ten1;
-    let twenty = Integer::from_u64(20);
+    let twenty = Integer::from(20);
     let neg_twenty = -&twenty;
 
-    assert!(zero.is_zero(), format!("\nEvaluated zero as not being zero\nzero = {:X}\n", zero));
-    assert!((-&zero).is_zero(), format!("\nEvaluated zero as not being zero\nzero = {:X}\n", (-&zero)));
-    assert!(zero == zero, format!("\nEvaluated zero != zero, when they should be equal.\nzero = {:X}\nzero = {:X}\n", zero, zero));
-    assert!(zero == -&zero, format!("\nEvaluated zero != -zero, when they should be equal.\nzero = {:X}\nzero = {:X}\n", zero, zero));
-    assert!(zero == zero, format!("\nEvaluated zero != zero, when they should be equal.\nzero = {:X}\nzero = {:X}\n", zero, zero));
-    assert!(ten1 == ten2, format!("\nEvaluated ten1 != ten2, when they should be equal.\nten1 = {:X}\nten2 = {:X}\n", ten1, ten2));
-    assert!(zero != ten1, format!("\nEvaluated zero == ten1, when they should not be equal.\nzero = {:X}\nten1 = {:X}\n", zero, ten1));
-    assert!(ten1 != neg_ten, format!("\nEvaluated ten1 == neg_ten, when they should not be equal.\nten1 = {:X}\nneg_ten = {:X}\n", ten1, neg_ten));
-    assert!(neg_twenty != neg_ten, format!("\nEvaluated neg_twenty == neg_ten, when they should not be equal.\neg_twenty = {:X}\nneg_ten = {:X}\n", ten1, neg_ten));
+    assert!(zero.is_zero(), "\nEvaluated zero as not being zero\nzero = {:X}\n", zero);
+    assert!((-&zero).is_zero(), "\nEvaluated zero as not being zero\nzero = {:X}\n", (-&zero));
+    assert!(zero == zero, "\nEvaluated zero != zero, when they should be equal.\nzero = {:X}\nzero = {:X}\n", zero, zero);
+    assert!(zero == -&zero, "\nEvaluated zero != -zero, when they should be equal.\nzero = {:X}\nzero = {:X}\n", zero, zero);
+    assert!(zero == zero, "\nEvaluated zero != zero, when they should be equal.\nzero = {:X}\nzero = {:X}\n", zero, zero);
+    assert!(ten1 == ten2, "\nEvaluated ten1 != ten2, when they should be equal.\nten1 = {:X}\nten2 = {:X}\n", ten1, ten2);
+    assert!(zero != ten1, "\nEvaluated zero == ten1, when they should not be equal.\nzero = {:X}\nten1 = {:X}\n", zero, ten1);
+    assert!(ten1 != neg_ten, "\nEvaluated ten1 == neg_ten, when they should not be equal.\nten1 = {:X}\nneg_ten = {:X}\n", ten1, neg_ten);
+    assert!(neg_twenty != neg_ten, "\nEvaluated neg_twenty == neg_ten, when they should not be equal.\neg_twenty = {:X}\nneg_ten = {:X}\n", ten1, neg_ten);
 } // End of integer_equality_test
 
 // Tests to ensure that the Partial Ordering operators return the proper value
@@ -454,27 +946,119 @@ fn integer_equality_test() {
 // If an Integer has a value greater than another than the > operator should return true
 // If an Integer has a value less than or equal to another than the <= operator should return true
 // If an Integer has a value greather than or equal to another than the >= operator should return true
+// The negated and borrowed comparisons are deliberate, they exercise each operator directly
 #[test]
+#[allow(clippy::nonminimal_bool, clippy::op_ref)]
 fn integer_ordering_test() {
-    let zero = Integer::from_u64(0);
-    let ten1 = Integer::from_u64(10);
-    let ten2 = Integer::from_u64(10);
+    let zero = Integer::from(0);
+    let ten1 = Integer::from(10);
+    let ten2 = Integer::from(10);
     let neg_ten = -&ten1;
-    let max = Integer::from_u64(u64::MAX);
-    let one = Integer::from_u64(1);
+    let max = Integer::from(u64::MAX);
+    let one = Integer::from(1);
     let max_one = &max+&one;
 
-    assert!(!(zero < zero), format!("\nEvaluated zero < zero, when they should be equal.\nzero = {:X}\nzero = {:X}\n", zero, zero));
-    assert!(!(ten1 > ten1), format!("\nEvaluated ten1 > ten1, when they should be equal.\nten1 = {:X}\nten1 = {:X}\n", ten1, ten1));
-    assert!(ten1 <= ten2, format!("\nEvaluated ten1 > ten1, when they should be equal.\nten1 = {:X}\nten1 = {:X}\n", ten1, ten2));
-    assert!(zero < ten1, format!("\nEvaluated zero >= ten1, when it should be less than.\nzero = {:X}\nten1 = {:X}\n", zero, ten1));
-    assert!(ten1 > zero, format!("\nEvaluated zero >= ten1, when it should be less than.\nzero = {:X}\nten1 = {:X}\n", zero, ten1));
-    assert!(neg_ten < ten1, format!("\nEvaluated neg_ten >= ten1, when it should be less than.\nneg_ten = {:X}\nten1 = {:X}\n", neg_ten, ten1));
-    assert!(!(neg_ten > ten1), format!("\nEvaluated neg_ten >= ten1, when it should be less than.\nneg_ten = {:X}\nten1 = {:X}\n", neg_ten, ten1));
-    assert!(&max_one > &max, format!("\nEvaluated max_one <= max, when it should be greater than than.\nmax_one = {:X}\nmax = {:X}\n", max_one, max));
-    assert!(&max < &max_one, format!("\nEvaluated max_one <= max, when it should be greater than than.\nmax_one = {:X}\nmax = {:X}\n", max_one, max));
+    assert!(!(zero < zero), "\nEvaluated zero < zero, when they should be equal.\nzero = {:X}\nzero = {:X}\n", zero, zero);
+    assert!(!(ten1 > ten1), "\nEvaluated ten1 > ten1, when they should be equal.\nten1 = {:X}\nten1 = {:X}\n", ten1, ten1);
+    assert!(ten1 <= ten2, "\nEvaluated ten1 > ten1, when they should be equal.\nten1 = {:X}\nten1 = {:X}\n", ten1, ten2);
+    assert!(zero < ten1, "\nEvaluated zero >= ten1, when it should be less than.\nzero = {:X}\nten1 = {:X}\n", zero, ten1);
+    assert!(ten1 > zero, "\nEvaluated zero >= ten1, when it should be less than.\nzero = {:X}\nten1 = {:X}\n", zero, ten1);
+    assert!(neg_ten < ten1, "\nEvaluated neg_ten >= ten1, when it should be less than.\nneg_ten = {:X}\nten1 = {:X}\n", neg_ten, ten1);
+    assert!(!(neg_ten > ten1), "\nEvaluated neg_ten >= ten1, when it should be less than.\nneg_ten = {:X}\nten1 = {:X}\n", neg_ten, ten1);
+    assert!(&max_one > &max, "\nEvaluated max_one <= max, when it should be greater than than.\nmax_one = {:X}\nmax = {:X}\n", max_one, max);
+    assert!(&max < &max_one, "\nEvaluated max_one <= max, when it should be greater than than.\nmax_one = {:X}\nmax = {:X}\n", max_one, max);
 } // End of integer_ordering_test
 
+// Tests to ensure that every operator leaves its result in canonical form, even when high limbs
+// cancel out, so that equal values compare equal however they were produced
+// Tests to ensure that values with different limb counts compare by magnitude, not length
+#[test]
+fn integer_canonical_test() {
+    let one = Integer::from(1);
+    let big = &Integer::from(u64::MAX).pow_u32(4) + &one;
+    let big_plus_one = &big + &one;
+    let small = Integer::from(12345);
+    let results = [
+        &big - &big,
+        &big_plus_one - &big,
+        &(-&big) + &big_plus_one,
+        &big * &Integer::from(0),
+        &(-&big) * &Integer::from(0),
+        &big % &big,
+        &(-&big) % &big,
+        &small / &big,
+        &(-&small) / &big,
+        &(&big << 300) >> 300,
+        &big & &small,
+        &big ^ &big,
+        !&(-&one),
+        &small - &(&big - &big),
+        big.gcd(&(&big * &small)),
+    ];
+    for result in results.iter() {
+        result.validate();
+    }
+
+    assert!(results[0] == Integer::from(0), "\nEvaluated big - big as {:?}\n", results[0].value);
+    assert!(results[1] == one && results[2] == one, "\nEvaluated (big + 1) - big as {:?}\n", results[1].value);
+    assert!(results[7] < big && results[8] > -&big, "\nEvaluated small / big as {:?}\n", results[7].value);
+    assert!(results[13] == small, "\nEvaluated small - 0 as {:?}\n", results[13].value);
+    assert!(small < &big - &one && -&big < -&small, "\nEvaluated small and big as out of order\n");
+    assert!(results[12].abs_cmp(&one) == Some(Ordering::Less), "\nEvaluated |0| as not less than |1|\n");
+} // End of integer_canonical_test
+
+// Tests to ensure that owned, borrowed and assignment forms of the operators agree
+// Tests to ensure that in place addition and subtraction handle carries, borrows and sign changes
+// Tests to ensure that Integers work as keys of hashed and ordered collections
+#[test]
+fn integer_traits_test() {
+    use std::collections::{BTreeSet, HashMap};
+
+    let max = Integer::from(u64::MAX);
+    let one = Integer::from(1);
+    let big = &max * &max;
+    let seven = Integer::from(-7);
+
+    assert!(big.clone() + one.clone() == &big + &one, "\nEvaluated owned big + 1 as {}\n", big.clone() + one.clone());
+    assert!(&big - seven.clone() == &big - &seven, "\nEvaluated big - owned -7 as {}\n", &big - seven.clone());
+    assert!(big.clone() * &seven == &big * &seven, "\nEvaluated owned big * -7 as {}\n", big.clone() * &seven);
+    assert!(&big / seven.clone() == &big / &seven, "\nEvaluated big / owned -7 as {}\n", &big / seven.clone());
+    assert!(big.clone() % seven.clone() == &big % &seven, "\nEvaluated owned big % -7 as {}\n", big.clone() % seven.clone());
+    assert!(-big.clone() == -&big && -Integer::default() == Integer::default(), "\nEvaluated owned negation incorrectly\n");
+
+    let mut value = max.clone();
+    value += &one;
+    assert!(value == &max + &one, "\nEvaluated max += 1 as {}\n", value);
+    value -= &big;
+    assert!(value == &(&max + &one) - &big, "\nEvaluated 2^64 -= big as {}\n", value);
+    value += big.clone();
+    value -= Integer::from(1);
+    assert!(value == max, "\nEvaluated the round trip back to max as {}\n", value);
+    value *= &seven;
+    value /= Integer::from(-7);
+    value %= &big;
+    assert!(value == max, "\nEvaluated max * -7 / -7 % big as {}\n", value);
+    value <<= 70;
+    value >>= 70;
+    value &= &max;
+    value |= Integer::from(1);
+    value ^= &one;
+    assert!(value == &max - &one, "\nEvaluated the bitwise round trip as {}\n", value);
+    value -= &value.clone();
+    assert!(value.is_zero() && !value.negative, "\nEvaluated x -= x as {:?}\n", value);
+
+    let mut counts = HashMap::new();
+    for number in [&big - &big, Integer::default(), -&big, &(-&big) + &Integer::from(0)].iter() {
+        *counts.entry(number.clone()).or_insert(0) += 1;
+    }
+    assert!(counts[&Integer::from(0)] == 2 && counts[&-&big] == 2, "\nEvaluated the hash map counts as {:?}\n", counts);
+
+    let sorted : Vec<Integer> = [big.clone(), seven.clone(), -&big, one.clone(), Integer::from(-8)]
+        .iter().cloned().collect::<BTreeSet<_>>().into_iter().collect();
+    assert!(sorted == vec![-&big, Integer::from(-8), seven.clone(), one.clone(), big.clone()], "\nEvaluated the sorted order as {:?}\n", sorted);
+    assert!(format!("{:?}", seven) == "-7", "\nEvaluated the debug format of -7 as {:?}\n", seven);
+} // End of integer_traits_test
+
 // Tests to ensure that addition of Integers is working properly
 // Tests to ensure a positive Integer and a positive Integer add together properly
 // Tests to ensure a positive Integer and a negative Integer add together properly
@@ -484,32 +1068,32 @@ fn integer_ordering_test() {
 // Tests when underflow of the Integer occurs that the Integer borrows properly
 #[test]
 fn integer_add_test(){
-    let zero = Integer::from_u64(0);
-    let one = Integer::from_u64(1);
-    let max = Integer::from_u64(u64::MAX);
+    let zero = Integer::from(0);
+    let one = Integer::from(1);
+    let max = Integer::from(u64::MAX);
     let max_one = &max + &one;
     let two_max = &max + &max;
-    let ten = Integer::from_u64(10);
-    let nine = Integer::from_u64(9);
-    let neg_two = -&Integer::from_u64(2);
+    let ten = Integer::from(10);
+    let nine = Integer::from(9);
+    let neg_two = -&Integer::from(2);
     let neg_one = -&one;
-    let mut ten_from_one = Integer::from_u64(0);
+    let mut ten_from_one = Integer::from(0);
     for _ in 0..10 {
         ten_from_one = &ten_from_one + &one;
     }
 
-    assert!(&one + &neg_one == zero, format!("\nEvaluated one + neg_one being != zero, when it should be zero\n"));
-    assert!(&one - &one == zero, format!("\nEvaluated one - one being != zero, when it should be zero\n"));
-    assert!(&neg_one + &neg_one == neg_two, format!("\nEvaluated neg_one + neg_one being != neg_two, when they should be equal\n"));
-    assert!(&neg_one + &one == zero, format!("\nEvaluated neg_one + one being != zero, when it should be zero\n"));
-    assert!(&ten - &nine == one, format!("\nEvaluated ten - nine to being != one, when it should be one\n"));
-    assert!(&ten - &one == nine, format!("\nEvaluated ten - one to being != nine, when it should be nine\n"));
-    assert!(&neg_one + &one == zero, format!("\nEvaluated one + neg_one being != zero, when it should be zero\n"));
-    assert!(&zero + &zero == zero, format!("\nEvaluated zero + zero being != zero, when it should be zero\n"));
-    assert!(&ten == &ten_from_one, format!("\nEvaluated ten != to &ten_from_one when it should be equal\nten = {:X}\nten_from_one = {:X}",ten,ten_from_one));
-    assert!(&max_one - &max == one, format!("\nEvaluated max_one - max to be != one\nmax_one = {:X}\nmax = {:X}",max_one,max));
-    assert!(&max_one - &max_one == zero, format!("\nEvaluated max_one - max_one to be != zero\nmax_one = {:X}\nmax_one = {:X}",max_one,max_one));
-    assert!(&two_max - &max == max, format!("\nEvaluated two_max - max != max, when it should be max\ntwo_max = {:X}\nmax = {:X}\n",two_max,max));
+    assert!(&one + &neg_one == zero, "\nEvaluated one + neg_one being != zero, when it should be zero\n");
+    assert!(&one - &one == zero, "\nEvaluated one - one being != zero, when it should be zero\n");
+    assert!(&neg_one + &neg_one == neg_two, "\nEvaluated neg_one + neg_one being != neg_two, when they should be equal\n");
+    assert!(&neg_one + &one == zero, "\nEvaluated neg_one + one being != zero, when it should be zero\n");
+    assert!(&ten - &nine == one, "\nEvaluated ten - nine to being != one, when it should be one\n");
+    assert!(&ten - &one == nine, "\nEvaluated ten - one to being != nine, when it should be nine\n");
+    assert!(&neg_one + &one == zero, "\nEvaluated one + neg_one being != zero, when it should be zero\n");
+    assert!(&zero + &zero == zero, "\nEvaluated zero + zero being != zero, when it should be zero\n");
+    assert!(ten == ten_from_one, "\nEvaluated ten != to &ten_from_one when it should be equal\nten = {:X}\nten_from_one = {:X}",ten,ten_from_one);
+    assert!(&max_one - &max == one, "\nEvaluated max_one - max to be != one\nmax_one = {:X}\nmax = {:X}",max_one,max);
+    assert!(&max_one - &max_one == zero, "\nEvaluated max_one - max_one to be != zero\nmax_one = {:X}\nmax_one = {:X}",max_one,max_one);
+    assert!(&two_max - &max == max, "\nEvaluated two_max - max != max, when it should be max\ntwo_max = {:X}\nmax = {:X}\n",two_max,max);
 } // End of integer_add_test
 
 // Tests to ensure that small values are added together properly
@@ -517,11 +1101,11 @@ fn integer_add_test(){
 fn integer_loop_add_test() {
     for i in -10i64..10i64 {
         for j in -10i64..10i64 {
-            let answer = Integer::from_i64(i+j);
-            let i_num = Integer::from_i64(i);
-            let j_num = Integer::from_i64(j);
+            let answer = Integer::from(i+j);
+            let i_num = Integer::from(i);
+            let j_num = Integer::from(j);
             let result = &i_num + &j_num;
-            assert!(answer == result, format!("\nEvaluated {} + {} != {}",i,j,i+j));
+            assert!(answer == result, "\nEvaluated {} + {} != {}",i,j,i+j);
         }
     }
 }
@@ -533,21 +1117,21 @@ fn integer_loop_add_test() {
 // Test to ensure that when u64 numbers overflow, the overflow is handled properly
 #[test]
 fn interger_mul_test(){
-    let zero = Integer::from_u64(0);
-    let two = Integer::from_u64(2);
-    let ten = Integer::from_u64(10);
+    let zero = Integer::from(0);
+    let two = Integer::from(2);
+    let ten = Integer::from(10);
     let neg_ten = -&ten;
-    let hundred = Integer::from_u64(100);
+    let hundred = Integer::from(100);
     let neg_hundred = -&hundred;
-    let max = Integer::from_u64(u64::MAX);
+    let max = Integer::from(u64::MAX);
     let max_ten = &(&(&(&max + &max) + &(&max + &max)) + &(&(&max + &max) + &(&max + &max))) + &(&max + &max);
 
-    assert!(&ten * &ten == hundred, format!("\nEvaluated ten * ten != hundred\nresult = {:X}\n",&ten * &ten));
-    assert!(&zero * &ten == zero, format!("\nEvaluated zero * ten != zero\nresult = {:X}\n",&ten * &ten));
-    assert!(&ten * &neg_ten == neg_hundred, format!("\nEvaluated neg_ten * ten != neg_hundred\nresult = {:X}\n",&ten * &neg_ten));
-    assert!(&neg_ten * &neg_ten == hundred, format!("\nEvaluated neg_ten * neg_ten != hundred\nresult = {:X}\n",&neg_ten * &neg_ten));
-    assert!(&max * &two == &max + &max, format!("\nEvaluated max * two != max + max\nproduct = {:X}\nsum = {:X}\n",&max * &two,&max + &max));
-    assert!(&max * &ten == max_ten, format!("\nEvaluated max * ten != max_ten\nproduct = {:X}\nsum = {:X}\n",&max * &ten,max_ten));
+    assert!(&ten * &ten == hundred, "\nEvaluated ten * ten != hundred\nresult = {:X}\n",&ten * &ten);
+    assert!(&zero * &ten == zero, "\nEvaluated zero * ten != zero\nresult = {:X}\n",&ten * &ten);
+    assert!(&ten * &neg_ten == neg_hundred, "\nEvaluated neg_ten * ten != neg_hundred\nresult = {:X}\n",&ten * &neg_ten);
+    assert!(&neg_ten * &neg_ten == hundred, "\nEvaluated neg_ten * neg_ten != hundred\nresult = {:X}\n",&neg_ten * &neg_ten);
+    assert!(&max * &two == &max + &max, "\nEvaluated max * two != max + max\nproduct = {:X}\nsum = {:X}\n",&max * &two,&max + &max);
+    assert!(&max * &ten == max_ten, "\nEvaluated max * ten != max_ten\nproduct = {:X}\nsum = {:X}\n",&max * &ten,max_ten);
 } // End of interger_mul_test
 
 // Tests to ensure that small values are multiplied together properly
@@ -555,11 +1139,11 @@ fn interger_mul_test(){
 fn integer_loop_mul_test() {
     for i in -10i64..10i64 {
         for j in -10i64..10i64 {
-            let answer = Integer::from_i64(i*j);
-            let i_num = Integer::from_i64(i);
-            let j_num = Integer::from_i64(j);
+            let answer = Integer::from(i*j);
+            let i_num = Integer::from(i);
+            let j_num = Integer::from(j);
             let result = &i_num * &j_num;
-            assert!(answer == result, format!("\nEvaluated {} * {} != {}",i,j,i*j));
+            assert!(answer == result, "\nEvaluated {} * {} != {}",i,j,i*j);
         }
     }
 }
@@ -569,11 +1153,208 @@ fn integer_loop_mul_test() {
 fn integer_loop_pow_test() {
     for i in 0u32..10u32 {
         for j in 0u32..10u32 {
-            let answer = Integer::from_u32(i.pow(j));
-            let i_num = Integer::from_u32(i);
-            let j_num = Integer::from_u32(j);
-            let result = i_num.pow(&j_num);
-            assert!(answer == result, format!("\nEvaluated {} ^ {} != {}, was {:X}",i,j,i.pow(j),result));
+            let answer = Integer::from(i.pow(j));
+            let i_num = Integer::from(i);
+            let j_num = Integer::from(j);
+            let result = i_num.pow(&j_num).unwrap();
+            assert!(answer == result, "\nEvaluated {} ^ {} != {}, was {:X}",i,j,i.pow(j),result);
         }
     }
 }
+
+// Tests to ensure that small values are divided with truncation toward zero
+// and that the remainder takes the sign of the dividend
+#[test]
+fn integer_loop_div_rem_test() {
+    for i in -10i64..10i64 {
+        for j in -10i64..10i64 {
+            if j == 0 {
+                continue;
+            }
+            let i_num = Integer::from(i);
+            let j_num = Integer::from(j);
+            let quotient = &i_num / &j_num;
+            let remainder = &i_num % &j_num;
+            assert!(quotient == Integer::from(i / j), "\nEvaluated {} / {} != {}, was {:X}", i, j, i / j, quotient);
+            assert!(remainder == Integer::from(i % j), "\nEvaluated {} % {} != {}, was {:X}", i, j, i % j, remainder);
+        }
+    }
+} // End of integer_loop_div_rem_test
+
+// Tests to ensure that multi-limb dividends and divisors produce the right quotient and remainder
+// Tests to ensure that division by zero is reported by checked_div and checked_rem
+#[test]
+fn integer_div_rem_test() {
+    let zero = Integer::from(0);
+    let seven = Integer::from(7);
+    let max = Integer::from(u64::MAX);
+    // 2^192 + 6 = (2^64 - 1)(2^128 + 2^64 + 1) + 7
+    let dividend = Integer { value: vec![6, 0, 0, 1], negative: false };
+    let quotient = Integer { value: vec![1, 1, 1], negative: false };
+    // 2^256 - 1 = (2^128 - 1)(2^128 + 1)
+    let all_ones = Integer { value: vec![u64::MAX; 4], negative: false };
+    let half_ones = Integer { value: vec![u64::MAX; 2], negative: false };
+    let half_ones_quotient = Integer { value: vec![1, 0, 1], negative: false };
+
+    assert!(&dividend / &max == quotient, "\nEvaluated (2^192 + 6) / max != quotient\nresult = {:X}\n", &dividend / &max);
+    assert!(&dividend % &max == seven, "\nEvaluated (2^192 + 6) % max != 7\nresult = {:X}\n", &dividend % &max);
+    assert!((-&dividend).div_rem(&max).1 == -&seven, "\nEvaluated -(2^192 + 6) % max != -7\n");
+    assert!((-&dividend).div_rem(&max).0 == -&quotient, "\nEvaluated -(2^192 + 6) / max != -quotient\n");
+    assert!(&all_ones / &half_ones == half_ones_quotient, "\nEvaluated (2^256 - 1) / (2^128 - 1) != 2^128 + 1\nresult = {:X}\n", &all_ones / &half_ones);
+    assert!((&all_ones % &half_ones).is_zero(), "\nEvaluated (2^256 - 1) % (2^128 - 1) != 0\n");
+    assert!(&seven / &all_ones == zero, "\nEvaluated 7 / (2^256 - 1) != 0\n");
+    assert!(seven.checked_div(&zero).is_none(), "\nEvaluated 7 / 0 as Some, when it should be None\n");
+    assert!(seven.checked_rem(&zero).is_none(), "\nEvaluated 7 % 0 as Some, when it should be None\n");
+    assert!(all_ones.checked_div(&seven).is_some(), "\nEvaluated (2^256 - 1) / 7 as None, when it should be Some\n");
+} // End of integer_div_rem_test
+
+// Tests to ensure that Integers print in decimal and honour the width, fill, sign and alignment flags
+#[test]
+fn integer_display_test() {
+    let max_one = &Integer::from(u64::MAX) + &Integer::from(1);
+    let neg_ten = Integer::from(-10);
+
+    assert!(format!("{}", Integer::from(0)) == "0", "\nEvaluated zero as {}\n", Integer::from(0));
+    assert!(format!("{}", max_one) == "18446744073709551616", "\nEvaluated 2^64 as {}\n", max_one);
+    assert!(format!("{}", Integer::from(i64::MIN)) == i64::MIN.to_string(), "\nEvaluated i64::MIN as {}\n", Integer::from(i64::MIN));
+    assert!(format!("{:+}", Integer::from(5)) == "+5", "\nEvaluated {{:+}} of 5 as {:+}\n", Integer::from(5));
+    assert!(format!("{:>6}", neg_ten) == "   -10", "\nEvaluated {{:>6}} of -10 as '{:>6}'\n", neg_ten);
+    assert!(format!("{:*<6}", neg_ten) == "-10***", "\nEvaluated {{:*<6}} of -10 as '{:*<6}'\n", neg_ten);
+    assert!(format!("{:^7}", neg_ten) == "  -10  ", "\nEvaluated {{:^7}} of -10 as '{:^7}'\n", neg_ten);
+    assert!(format!("{:06}", neg_ten) == "-00010", "\nEvaluated {{:06}} of -10 as '{:06}'\n", neg_ten);
+} // End of integer_display_test
+
+// Tests to ensure that decimal strings parse with signs, underscores and leading zeros
+// Tests to ensure that malformed strings are rejected with the right error kind
+// Tests to ensure that values large enough to use the divide and conquer conversion round trip
+#[test]
+fn integer_from_str_test() {
+    let max_one = &Integer::from(u64::MAX) + &Integer::from(1);
+
+    assert!("18446744073709551616".parse::<Integer>() == Ok(max_one), "\nEvaluated \"18446744073709551616\" as != 2^64\n");
+    assert!("-1_000".parse::<Integer>() == Ok(Integer::from(-1000)), "\nEvaluated \"-1_000\" as != -1000\n");
+    assert!("+0007".parse::<Integer>() == Ok(Integer::from(7)), "\nEvaluated \"+0007\" as != 7\n");
+    assert!("-0".parse::<Integer>().map(|zero| zero.negative) == Ok(false), "\nEvaluated \"-0\" as a negative zero\n");
+    assert!("".parse::<Integer>().map_err(|e| e.kind()) == Err(ParseErrorKind::Empty), "\nEvaluated \"\" as not empty\n");
+    assert!("-".parse::<Integer>().map_err(|e| e.kind()) == Err(ParseErrorKind::Empty), "\nEvaluated \"-\" as not empty\n");
+    assert!("_1".parse::<Integer>().map_err(|e| e.kind()) == Err(ParseErrorKind::InvalidDigit), "\nEvaluated \"_1\" as valid\n");
+    assert!("12a".parse::<Integer>().map_err(|e| e.kind()) == Err(ParseErrorKind::InvalidDigit), "\nEvaluated \"12a\" as valid\n");
+    assert!("--1".parse::<Integer>().map_err(|e| e.kind()) == Err(ParseErrorKind::InvalidDigit), "\nEvaluated \"--1\" as valid\n");
+
+    let mut digits = String::from("-9");
+    for i in 0..1500 {
+        digits.push_str(&((i * 7) % 10).to_string());
+    }
+    let large = digits.parse::<Integer>().unwrap();
+    assert!(large.value.len() > 64, "\nExpected 1501 digits to need more than 64 limbs\n");
+    assert!(large.to_string() == digits, "\nEvaluated the 1501 digit round trip as\n{}\n", large);
+
+    let power_of_ten = format!("1{}", "0".repeat(2000));
+    let parsed = power_of_ten.parse::<Integer>().unwrap();
+    assert!(parsed.to_string() == power_of_ten, "\nEvaluated the 10^2000 round trip as\n{}\n", parsed);
+} // End of integer_from_str_test
+
+// Tests to ensure that every radix from 2 to 36 round trips through to_str_radix and from_str_radix
+// Tests to ensure that multi-limb values convert to the known digits in a few radices
+#[test]
+fn integer_radix_test() {
+    let max_one = &Integer::from(u64::MAX) + &Integer::from(1);
+    let mut large = String::from("-1");
+    for i in 0..700 {
+        large.push_str(&((i * 3) % 10).to_string());
+    }
+    let large = large.parse::<Integer>().unwrap();
+
+    for radix in 2..37 {
+        for number in [Integer::from(0), Integer::from(-35), max_one.abs(), large.abs(), -&large].iter() {
+            let digits = number.to_str_radix(radix);
+            let parsed = Integer::from_str_radix(&digits, radix).unwrap();
+            assert!(&parsed == number, "\nEvaluated the radix {} round trip of {} as {}\n", radix, number, parsed);
+        }
+    }
+    assert!(max_one.to_str_radix(36) == "3w5e11264sgsg", "\nEvaluated 2^64 in radix 36 as {}\n", max_one.to_str_radix(36));
+    assert!(max_one.to_str_radix(7) == "45012021522523134134602", "\nEvaluated 2^64 in radix 7 as {}\n", max_one.to_str_radix(7));
+    assert!(max_one.to_str_radix(8) == "2000000000000000000000", "\nEvaluated 2^64 in radix 8 as {}\n", max_one.to_str_radix(8));
+    assert!(Integer::from_str_radix("-FF_ff", 16) == Ok(Integer::from(-0xFFFF)), "\nEvaluated \"-FF_ff\" in radix 16 as != -65535\n");
+    assert!(Integer::from_str_radix("12", 2).map_err(|e| e.kind()) == Err(ParseErrorKind::InvalidDigit), "\nEvaluated \"12\" in radix 2 as valid\n");
+} // End of integer_radix_test
+
+// Tests to ensure that the radix formatters honour the # prefix flag and that grouping is optional
+#[test]
+fn integer_radix_fmt_test() {
+    let max_one = &Integer::from(u64::MAX) + &Integer::from(1);
+    let neg = Integer::from(-0x1FF);
+
+    assert!(format!("{:x}", max_one) == "10000000000000000", "\nEvaluated {{:x}} of 2^64 as {:x}\n", max_one);
+    assert!(format!("{:#X}", neg) == "-0x1FF", "\nEvaluated {{:#X}} of -0x1FF as {:#X}\n", neg);
+    assert!(format!("{:#b}", Integer::from(5)) == "0b101", "\nEvaluated {{:#b}} of 5 as {:#b}\n", Integer::from(5));
+    assert!(format!("{:#o}", Integer::from(8)) == "0o10", "\nEvaluated {{:#o}} of 8 as {:#o}\n", Integer::from(8));
+    assert!(format!("{:#08x}", Integer::from(255)) == "0x0000ff", "\nEvaluated {{:#08x}} of 255 as {:#08x}\n", Integer::from(255));
+    assert!(format!("{:X}", neg.grouped(2)) == "-1 FF", "\nEvaluated grouped {{:X}} of -0x1FF as {:X}\n", neg.grouped(2));
+    assert!(format!("{:#x}", max_one.grouped(4)) == "0x1 0000 0000 0000 0000", "\nEvaluated grouped {{:#x}} of 2^64 as {:#x}\n", max_one.grouped(4));
+    assert!(format!("{}", Integer::from(1234567).grouped(3)) == "1 234 567", "\nEvaluated grouped {{}} of 1234567 as {}\n", Integer::from(1234567).grouped(3));
+} // End of integer_radix_fmt_test
+
+// Tests to ensure that products large enough for Karatsuba and Toom-Cook 3 match known values
+// Tests to ensure that squaring agrees with multiplication
+#[test]
+fn integer_large_mul_test() {
+    // (10^n - 1)^2 = 10^2n - 2*10^n + 1, which is n-1 nines, an eight, n-1 zeros and a one
+    for &n in [500usize, 2000, 12000].iter() {
+        let nines = "9".repeat(n).parse::<Integer>().unwrap();
+        let expected = format!("{}8{}1", "9".repeat(n - 1), "0".repeat(n - 1));
+        let product = &nines * &(-&nines);
+        assert!(product.to_string() == format!("-{}", expected), "\nEvaluated (10^{} - 1) * -(10^{} - 1) incorrectly\n", n, n);
+        assert!(nines.square().to_string() == expected, "\nEvaluated (10^{} - 1)^2 incorrectly\n", n);
+        assert!((-&nines).square() == &nines * &nines, "\nEvaluated -(10^{} - 1) squared as != the product\n", n);
+    }
+} // End of integer_large_mul_test
+
+// Tests to ensure that negative exponents are reported as an error instead of panicking
+// Tests to ensure that negative bases take the sign of the exponent's parity
+// Tests to ensure that large exponents agree with repeated multiplication and the decimal digits
+#[test]
+fn integer_pow_test() {
+    let three = Integer::from(3);
+    let neg_two = Integer::from(-2);
+
+    assert!(three.pow(&Integer::from(-1)) == Err(ArithmeticError::NegativeExponent), "\nEvaluated 3 ^ -1 as not an error\n");
+    assert!(neg_two.pow_u32(63) == Integer::from(i64::MIN), "\nEvaluated -2 ^ 63 as {}\n", neg_two.pow_u32(63));
+    assert!(neg_two.pow_u64(64).to_string() == "18446744073709551616", "\nEvaluated -2 ^ 64 as {}\n", neg_two.pow_u64(64));
+    assert!(Integer::from(-1).pow_u64(u64::MAX) == Integer::from(-1), "\nEvaluated -1 ^ (2^64 - 1) as != -1\n");
+    assert!(Integer::from(0).pow_u32(0) == Integer::from(1), "\nEvaluated 0 ^ 0 as != 1\n");
+
+    let mut repeated = Integer::from(1);
+    for exponent in 0..300u32 {
+        assert!(three.pow_u32(exponent) == repeated, "\nEvaluated 3 ^ {} as {}, expected {}\n", exponent, three.pow_u32(exponent), repeated);
+        repeated = &repeated * &three;
+    }
+    let ten_thousand = Integer::from(10).pow(&Integer::from(10_000)).unwrap();
+    assert!(ten_thousand.to_string() == format!("1{}", "0".repeat(10_000)), "\nEvaluated 10 ^ 10000 incorrectly\n");
+} // End of integer_pow_test
+
+// Tests to ensure that mod_pow reduces negative bases and handles odd, even and unit moduli
+// Tests to ensure that a zero modulus and a negative exponent are reported as errors
+// Tests to ensure that a Montgomery context can be reused across exponentiations
+#[test]
+fn integer_mod_pow_test() {
+    let two = Integer::from(2);
+    let prime = "170141183460469231731687303715884105727".parse::<Integer>().unwrap();    // 2^127 - 1
+    let prime_minus_one = "170141183460469231731687303715884105726".parse::<Integer>().unwrap();
+
+    assert!(two.mod_pow(&prime_minus_one, &prime) == Ok(Integer::from(1)), "\nEvaluated 2 ^ (p - 1) mod p as != 1 for p = 2^127 - 1\n");
+    assert!(Integer::from(-3).mod_pow(&Integer::from(3), &Integer::from(10)) == Ok(Integer::from(3)), "\nEvaluated -3 ^ 3 mod 10 as != 3\n");
+    assert!(Integer::from(7).mod_pow(&Integer::from(0), &Integer::from(1)) == Ok(Integer::from(0)), "\nEvaluated 7 ^ 0 mod 1 as != 0\n");
+    assert!(Integer::from(7).mod_pow(&Integer::from(5), &Integer::from(-12)) == Ok(Integer::from(7)), "\nEvaluated 7 ^ 5 mod -12 as != 7\n");
+    assert!(two.mod_pow(&Integer::from(5), &Integer::from(0)) == Err(ArithmeticError::DivisionByZero), "\nEvaluated 2 ^ 5 mod 0 as not an error\n");
+    assert!(two.mod_pow(&Integer::from(-1), &prime) == Err(ArithmeticError::NegativeExponent), "\nEvaluated 2 ^ -1 mod p as not an error\n");
+
+    let context = Montgomery::new(&prime).unwrap();
+    assert!(Montgomery::new(&Integer::from(10)).is_none(), "\nCreated a Montgomery context for an even modulus\n");
+    assert!(context.modulus() == prime, "\nEvaluated the Montgomery modulus as {}\n", context.modulus());
+    for base in 2..20u64 {
+        let result = context.pow_mod(&Integer::from(base), &prime);
+        assert!(result == Ok(Integer::from(base)), "\nEvaluated {} ^ p mod p as != {} for p = 2^127 - 1\n", base, base);
+    }
+    assert!(context.mul_mod(&prime_minus_one, &prime_minus_one) == Integer::from(1), "\nEvaluated (p - 1)^2 mod p as != 1\n");
+} // End of integer_mod_pow_test
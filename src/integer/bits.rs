@@ -0,0 +1,383 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Bitwise operators, shifts and bit queries for Integer.
+// Integers are stored as sign and magnitude, but the operators here behave as if every number
+// were written in two's complement with infinitely many copies of its sign bit to the left,
+// the same semantics as Python's int. So -1 is ...1111, -2 is ...1110 and !x == -x - 1.
+// Operands are converted to two's complement limbs just wide enough to hold their sign bit,
+// combined limb by limb, and the result is converted back to sign and magnitude.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr, BitAndAssign, BitOrAssign, BitXorAssign, ShlAssign, ShrAssign};
+use super::{Integer, add_limbs, significant, sub_limbs, trim};
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns bit n of the two's complement form of the number, negative numbers have
+    // infinitely many set bits above their magnitude.
+    pub fn bit(&self, n : u64) -> bool {
+        if self.negative {
+            // the two's complement of -m is !(m - 1)
+            !limb_bit(&sub_limbs(&self.value, &[1]), n)
+        } else {
+            limb_bit(&self.value, n)
+        }
+    } // End of bit function
+
+    // sets bit n of the two's complement form of the number to value.
+    pub fn set_bit(&mut self, n : u64, value : bool) {
+        let width = self.value.len().max(n as usize / 64 + 1) + 1;
+        let mut twos = to_twos_complement(self, width);
+        let (limb, offset) = (n as usize / 64, n % 64);
+        if value {
+            twos[limb] |= 1 << offset;
+        } else {
+            twos[limb] &= !(1 << offset);
+        }
+        *self = from_twos_complement(twos);
+    } // End of set_bit function
+
+    // returns the number of ones in the magnitude, like Python's int.bit_count.
+    pub fn count_ones(&self) -> u64 {
+        self.value.iter().map(|limb| limb.count_ones() as u64).sum()
+    } // End of count_ones function
+
+    // returns the number of zero bits below the lowest set bit, or None for zero.
+    // This is the same for a number and its negation.
+    pub fn trailing_zeros(&self) -> Option<u64> {
+        self.value.iter().position(|limb| *limb != 0)
+            .map(|i| i as u64 * 64 + self.value[i].trailing_zeros() as u64)
+    } // End of trailing_zeros function
+
+    // returns the number of bits needed to write the magnitude, zero for zero.
+    pub fn bit_length(&self) -> u64 {
+        let value = significant(&self.value);
+        value.len() as u64 * 64 - value[value.len()-1].leading_zeros() as u64
+    } // End of bit_length function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Operators [& | ^ ! << >> &= |= ^= <<= >>=]                                               */
+/* ============================================================================================ */
+
+// Overloads the & operator such that Integer & Integer is the two's complement and of the numbers.
+impl<'b> BitAnd<&'b Integer> for &Integer {
+    type Output = Integer;
+
+    fn bitand(self, num : &'b Integer) -> Integer {
+        let mut result = self.clone();
+        result &= num;
+        result
+    } // End of bitand function
+} // End of BitAnd implementation
+
+// Overloads the | operator such that Integer | Integer is the two's complement or of the numbers.
+impl<'b> BitOr<&'b Integer> for &Integer {
+    type Output = Integer;
+
+    fn bitor(self, num : &'b Integer) -> Integer {
+        let mut result = self.clone();
+        result |= num;
+        result
+    } // End of bitor function
+} // End of BitOr implementation
+
+// Overloads the ^ operator such that Integer ^ Integer is the two's complement xor of the numbers.
+impl<'b> BitXor<&'b Integer> for &Integer {
+    type Output = Integer;
+
+    fn bitxor(self, num : &'b Integer) -> Integer {
+        let mut result = self.clone();
+        result ^= num;
+        result
+    } // End of bitxor function
+} // End of BitXor implementation
+
+// Overloads the &= operator, combining into the left operand's limbs in place.
+impl<'b> BitAndAssign<&'b Integer> for Integer {
+    fn bitand_assign(&mut self, num : &'b Integer) {
+        bitwise_assign(self, num, |a, b| a & b);
+    } // End of bitand_assign function
+} // End of BitAndAssign implementation
+
+// Overloads the |= operator, combining into the left operand's limbs in place.
+impl<'b> BitOrAssign<&'b Integer> for Integer {
+    fn bitor_assign(&mut self, num : &'b Integer) {
+        bitwise_assign(self, num, |a, b| a | b);
+    } // End of bitor_assign function
+} // End of BitOrAssign implementation
+
+// Overloads the ^= operator, combining into the left operand's limbs in place.
+impl<'b> BitXorAssign<&'b Integer> for Integer {
+    fn bitxor_assign(&mut self, num : &'b Integer) {
+        bitwise_assign(self, num, |a, b| a ^ b);
+    } // End of bitxor_assign function
+} // End of BitXorAssign implementation
+
+// Overloads the unary ! operator such that !Integer flips every bit, which is -Integer - 1.
+impl Not for &Integer {
+    type Output = Integer;
+
+    fn not(self) -> Integer {
+        if self.negative {
+            Integer::from_parts(sub_limbs(&self.value, &[1]), false)
+        } else {
+            Integer::from_parts(add_limbs(&self.value, &[1]), true)
+        }
+    } // End of not function
+} // End of Not implementation
+
+// Overloads the << operator such that Integer << n is Integer * 2^n.
+impl Shl<u64> for &Integer {
+    type Output = Integer;
+
+    fn shl(self, n : u64) -> Integer {
+        let (limbs, bits) = (n as usize / 64, (n % 64) as u32);
+        let mut value = vec![0u64; limbs];
+        value.reserve(self.value.len() + 1);
+        let mut carry = 0;
+        for limb in self.value.iter() {
+            value.push(if bits == 0 {*limb} else {(limb << bits) | carry});
+            carry = if bits == 0 {0} else {limb >> (64 - bits)};
+        }
+        value.push(carry);
+        Integer::from_parts(value, self.negative)
+    } // End of shl function
+} // End of Shl implementation
+
+// Overloads the >> operator such that Integer >> n is Integer / 2^n rounded toward negative
+// infinity, so the sign bits shifted in from the left keep negative numbers negative.
+impl Shr<u64> for &Integer {
+    type Output = Integer;
+
+    fn shr(self, n : u64) -> Integer {
+        let (limbs, bits) = (n as usize / 64, (n % 64) as u32);
+        if limbs >= self.value.len() {
+            return Integer::from(if self.negative {-1} else {0});
+        }
+        let mut value = Vec::with_capacity(self.value.len() - limbs);
+        for i in limbs..self.value.len() {
+            let high = if bits == 0 || i + 1 >= self.value.len() {0} else {self.value[i+1] << (64 - bits)};
+            value.push((self.value[i] >> bits) | high);
+        }
+
+        // a negative number with set bits shifted out rounds down, one further from zero
+        let low_mask = if bits == 0 {0} else {(1u64 << bits) - 1};
+        let lost = self.value[..limbs].iter().any(|limb| *limb != 0) || self.value[limbs] & low_mask != 0;
+        if self.negative && lost {
+            value = add_limbs(&value, &[1]);
+        }
+        Integer::from_parts(value, self.negative)
+    } // End of shr function
+} // End of Shr implementation
+
+// Overloads the bitwise operators and shifts for owned operands.
+forward_binop!(BitAnd, bitand, BitAndAssign, bitand_assign, commutative);
+forward_binop!(BitOr, bitor, BitOrAssign, bitor_assign, commutative);
+forward_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign, commutative);
+forward_assign!(BitAndAssign, bitand_assign);
+forward_assign!(BitOrAssign, bitor_assign);
+forward_assign!(BitXorAssign, bitxor_assign);
+
+impl Not for Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn not(self) -> Integer {
+        !&self
+    } // End of not function
+} // End of Not implementation
+
+impl Shl<u64> for Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn shl(self, n : u64) -> Integer {
+        &self << n
+    } // End of shl function
+} // End of Shl implementation
+
+impl Shr<u64> for Integer {
+    type Output = Integer;
+
+    #[inline]
+    fn shr(self, n : u64) -> Integer {
+        &self >> n
+    } // End of shr function
+} // End of Shr implementation
+
+impl ShlAssign<u64> for Integer {
+    #[inline]
+    fn shl_assign(&mut self, n : u64) {
+        *self = &*self << n;
+    } // End of shl_assign function
+} // End of ShlAssign implementation
+
+impl ShrAssign<u64> for Integer {
+    #[inline]
+    fn shr_assign(&mut self, n : u64) {
+        *self = &*self >> n;
+    } // End of shr_assign function
+} // End of ShrAssign implementation
+
+/* ============================================================================================ */
+/*     Helpers                                                                                  */
+/* ============================================================================================ */
+
+// returns bit n of a magnitude.
+fn limb_bit(value : &[u64], n : u64) -> bool {
+    let limb = n as usize / 64;
+    limb < value.len() && (value[limb] >> (n % 64)) & 1 == 1
+} // End of limb_bit function
+
+// applies a limb operation to the two's complement forms of a and b, leaving the result in a.
+// Only the limbs both magnitudes span are stored, the infinitely many sign bits above them are
+// combined once. a's limbs are negated in place and b's are negated as they are read.
+fn bitwise_assign<F : Fn(u64, u64) -> u64>(a : &mut Integer, b : &Integer, op : F) {
+    let width = a.value.len().max(b.value.len());
+    a.value.resize(width, 0);
+    if a.negative {
+        negate(&mut a.value);
+    }
+    let mut carry = true;
+    for i in 0..width {
+        let mut limb = if i < b.value.len() {b.value[i]} else {0};
+        if b.negative {
+            let (sum, overflow) = (!limb).overflowing_add(carry as u64);
+            limb = sum;
+            carry = overflow;
+        }
+        a.value[i] = op(a.value[i], limb);
+    }
+
+    // turn the limbs back into a magnitude, a negative result whose stored limbs are all zero
+    // is -2^(64 * width) and needs one more limb
+    let sign = |negative : bool| if negative {u64::MAX} else {0};
+    let negative = op(sign(a.negative), sign(b.negative)) != 0;
+    if negative {
+        negate(&mut a.value);
+        if a.value.iter().all(|limb| *limb == 0) {
+            a.value.push(1);
+        }
+    }
+    trim(&mut a.value);
+    a.negative = negative;
+    a.validate();
+} // End of bitwise_assign function
+
+// returns the two's complement form of the number in width limbs, which must leave room for
+// the sign bit above the magnitude.
+fn to_twos_complement(number : &Integer, width : usize) -> Vec<u64> {
+    let mut twos = number.value.clone();
+    twos.resize(width, 0);
+    if number.negative {
+        negate(&mut twos);
+    }
+    twos
+} // End of to_twos_complement function
+
+// converts a two's complement form back to an Integer, the top bit is the sign.
+fn from_twos_complement(mut twos : Vec<u64>) -> Integer {
+    let negative = twos[twos.len()-1] >> 63 == 1;
+    if negative {
+        negate(&mut twos);
+    }
+    Integer::from_parts(twos, negative)
+} // End of from_twos_complement function
+
+// negates a fixed width two's complement value in place, !x + 1.
+fn negate(twos : &mut [u64]) {
+    let mut carry = true;
+    for limb in twos.iter_mut() {
+        let (sum, overflow) = (!*limb).overflowing_add(carry as u64);
+        *limb = sum;
+        carry = overflow;
+    }
+} // End of negate function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that the bitwise operators and shifts match i64 for every pair of small values,
+// since i64 uses the same two's complement semantics within its range.
+#[test]
+fn bitwise_matches_i64_test() {
+    for i in -70i64..70 {
+        let a = Integer::from(i);
+        assert!(!&a == Integer::from(!i), "\nEvaluated !{} as {}\n", i, !&a);
+        for j in -70i64..70 {
+            let b = Integer::from(j);
+            assert!(&a & &b == Integer::from(i & j), "\nEvaluated {} & {} as {}\n", i, j, &a & &b);
+            assert!(&a | &b == Integer::from(i | j), "\nEvaluated {} | {} as {}\n", i, j, &a | &b);
+            assert!(&a ^ &b == Integer::from(i ^ j), "\nEvaluated {} ^ {} as {}\n", i, j, &a ^ &b);
+        }
+        for n in 0..8u64 {
+            assert!(&a << n == Integer::from(i << n), "\nEvaluated {} << {} as {}\n", i, n, &a << n);
+            assert!(&a >> n == Integer::from(i >> n), "\nEvaluated {} >> {} as {}\n", i, n, &a >> n);
+            assert!(a.bit(n) == ((i >> n) & 1 == 1), "\nEvaluated bit {} of {} as {}\n", n, i, a.bit(n));
+        }
+    }
+} // End of bitwise_matches_i64_test
+
+// Tests that the owned and assignment forms of the bitwise operators match i128 across limb
+// boundaries, and that assigning into a number at least as long as the other keeps its limbs.
+#[test]
+fn bitwise_assign_test() {
+    let values = [0i128, 1, -1, i64::MIN as i128, 1 << 63, u64::MAX as i128, -(u64::MAX as i128), 1 << 64, -(1 << 64), -(1 << 100) - 3];
+    for i in values.iter() {
+        for j in values.iter() {
+            let (a, b) = (Integer::from(*i), Integer::from(*j));
+            let mut and = a.clone();
+            and &= &b;
+            let mut or = a.clone();
+            or |= b.clone();
+            let mut xor = a.clone();
+            xor ^= &b;
+            assert!(and == Integer::from(i & j) && a.clone() & &b == and && &a & b.clone() == and, "\nEvaluated {} &= {} as {}\n", i, j, and);
+            assert!(or == Integer::from(i | j) && a.clone() | b.clone() == or && &a | b.clone() == or, "\nEvaluated {} |= {} as {}\n", i, j, or);
+            assert!(xor == Integer::from(i ^ j) && a.clone() ^ &b == xor && &a ^ b.clone() == xor, "\nEvaluated {} ^= {} as {}\n", i, j, xor);
+        }
+    }
+
+    let big = -(Integer::from(1) << 200);
+    let (low, mask, even) = (Integer::from(-5), Integer::from(u64::MAX), Integer::from(-2));
+    let mut number = big.clone();
+    let limbs = number.value.as_ptr();
+    number ^= &low;
+    number |= &mask;
+    number &= &even;
+    let expected = &(&(&big ^ &low) | &mask) & &even;
+    assert!(number == expected && number.value.as_ptr() == limbs, "\nEvaluated the bitwise assignments to 2^200 as {} at a new address\n", number);
+} // End of bitwise_assign_test
+
+// Tests the bit helpers and shifts across limb boundaries.
+#[test]
+fn bit_helpers_test() {
+    let one = Integer::from(1);
+    let big = &one << 200;
+    assert!(big.bit_length() == 201, "\nEvaluated the bit length of 2^200 as {}\n", big.bit_length());
+    assert!(big.trailing_zeros() == Some(200), "\nEvaluated the trailing zeros of 2^200 as {:?}\n", big.trailing_zeros());
+    assert!((-&big).trailing_zeros() == Some(200), "\nEvaluated the trailing zeros of -2^200 as {:?}\n", (-&big).trailing_zeros());
+    assert!(Integer::from(0).trailing_zeros().is_none(), "\nEvaluated the trailing zeros of 0 as Some\n");
+    assert!(&big >> 200 == one, "\nEvaluated 2^200 >> 200 as {}\n", &big >> 200);
+    assert!(&(-&big) >> 201 == Integer::from(-1), "\nEvaluated -2^200 >> 201 as {}\n", &(-&big) >> 201);
+    assert!(&(-&big) >> 1000 == Integer::from(-1), "\nEvaluated -2^200 >> 1000 as {}\n", &(-&big) >> 1000);
+    assert!(&(-&big) >> 64 == -&(&one << 136), "\nEvaluated -2^200 >> 64 as {}\n", &(-&big) >> 64);
+    assert!((!&(-&big)).count_ones() == 200, "\nEvaluated the ones in 2^200 - 1 as {}\n", (!&(-&big)).count_ones());
+    assert!(!(-&big).bit(199) && (-&big).bit(200) && (-&big).bit(5000), "\nEvaluated the bits of -2^200 incorrectly\n");
+
+    let mut number = Integer::from(0);
+    number.set_bit(130, true);
+    assert!(number == &one << 130, "\nEvaluated setting bit 130 of 0 as {}\n", number);
+    let mut negative = Integer::from(-1);
+    negative.set_bit(0, false);
+    assert!(negative == Integer::from(-2), "\nEvaluated clearing bit 0 of -1 as {}\n", negative);
+    negative.set_bit(100, false);
+    assert!(negative == -&(&(&one << 100) | &Integer::from(2)), "\nEvaluated clearing bit 100 of -2 as {}\n", negative);
+} // End of bit_helpers_test
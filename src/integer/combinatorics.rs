@@ -0,0 +1,277 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Factorials, binomial coefficients and other combinatorial numbers.
+// Multiplying 1 * 2 * ... * n one factor at a time makes every step a multiplication of a huge
+// number by a small one, which is quadratic in the size of the result. Instead the results are
+// built from their prime factorizations, which follow from Legendre's formula, and the primes
+// are multiplied in balanced product trees so the large multiplications have operands of equal
+// size and can use the fast multiplication algorithms.
+// The factorial uses Luschny's prime swing, n! = (n/2)!^2 * swing(n), where the swing is the
+// product of the prime powers p^e with e the number of odd terms of floor(n / p^i).
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::Integer;
+use super::prime::primes_up_to;
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+const PRODUCT_LEAF : usize = 16;        // product trees multiply this many values directly
+const SIEVE_LIMIT : u64 = 1 << 26;      // largest n whose primes are sieved for a binomial
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns n! = 1 * 2 * ... * n.
+    pub fn factorial(n : u64) -> Integer {
+        factorial(n, &primes_up_to(n))
+    } // End of factorial function
+
+    // returns n!! = n * (n - 2) * (n - 4) * ... down to 1 or 2.
+    pub fn double_factorial(n : u64) -> Integer {
+        let k = n / 2;
+        if n.is_multiple_of(2) {
+            // (2k)!! = 2^k k!
+            return Integer::factorial(k) << k;
+        }
+        // n! = n!! (n - 1)!! = n!! 2^k k!, so the odd primes of n!! are those of n! / k!
+        let primes = primes_up_to(n);
+        let exponents : Vec<u64> = primes.iter().map(|&p| {
+            if p == 2 {0} else {legendre(n, p) - legendre(k, p)}
+        }).collect();
+        from_exponents(&primes, &exponents)
+    } // End of double_factorial function
+
+    // returns the binomial coefficient n choose k, which is 0 for k > n.
+    pub fn binomial(n : u64, k : u64) -> Integer {
+        if k > n {
+            return Integer::from(0);
+        }
+        let k = k.min(n - k);
+        if n <= SIEVE_LIMIT {
+            let primes = primes_up_to(n);
+            let exponents : Vec<u64> = primes.iter().map(|&p| {
+                legendre(n, p) - legendre(k, p) - legendre(n - k, p)
+            }).collect();
+            from_exponents(&primes, &exponents)
+        } else {
+            // too many primes to sieve, but k is small enough that n (n-1) ... (n-k+1) / k!
+            // is a reasonable size
+            &range_product(n - k + 1, n) / &Integer::factorial(k)
+        }
+    } // End of binomial function
+
+    // returns the multinomial coefficient (k1 + k2 + ...)! / (k1! k2! ...), the number of
+    // ways to split k1 + k2 + ... items into groups of the given sizes.
+    pub fn multinomial(ks : &[u64]) -> Integer {
+        let total : u64 = ks.iter().sum();
+        if total > SIEVE_LIMIT {
+            // the product of (k1 + ... + ki choose ki) over each group
+            let mut sum = 0;
+            return ks.iter().fold(Integer::from(1), |product, &k| {
+                sum += k;
+                product * Integer::binomial(sum, k)
+            });
+        }
+        let primes = primes_up_to(total);
+        let exponents : Vec<u64> = primes.iter().map(|&p| {
+            legendre(total, p) - ks.iter().map(|&k| legendre(k, p)).sum::<u64>()
+        }).collect();
+        from_exponents(&primes, &exponents)
+    } // End of multinomial function
+
+    // returns the product of the primes up to n.
+    pub fn primorial(n : u64) -> Integer {
+        product(&primes_up_to(n))
+    } // End of primorial function
+
+    // returns the nth Catalan number (2n choose n) / (n + 1).
+    pub fn catalan(n : u64) -> Integer {
+        Integer::binomial(2 * n, n) / (n + 1)
+    } // End of catalan function
+
+    // returns the unsigned Stirling number of the first kind, the number of permutations of
+    // n items with k cycles. The signed number is (-1)^(n-k) times this.
+    pub fn stirling1(n : u64, k : u64) -> Integer {
+        // c(i, j) = (i - 1) c(i - 1, j) + c(i - 1, j - 1)
+        stirling(n, k, |i, _| i - 1)
+    } // End of stirling1 function
+
+    // returns the Stirling number of the second kind, the number of ways to partition n items
+    // into k non-empty sets.
+    pub fn stirling2(n : u64, k : u64) -> Integer {
+        // S(i, j) = j S(i - 1, j) + S(i - 1, j - 1)
+        stirling(n, k, |_, j| j)
+    } // End of stirling2 function
+
+    // returns the nth Bell number, the number of partitions of n items, with the Bell triangle:
+    // each row starts with the last entry of the row before and adds it on at each step.
+    pub fn bell(n : u64) -> Integer {
+        let mut row = vec![Integer::from(1)];
+        for _ in 0..n {
+            let mut next = Vec::with_capacity(row.len() + 1);
+            next.push(row[row.len() - 1].clone());
+            for value in row.iter() {
+                let sum = &next[next.len() - 1] + value;
+                next.push(sum);
+            }
+            row = next;
+        }
+        row.swap_remove(0)
+    } // End of bell function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns n! with the prime swing, given every prime up to n.
+fn factorial(n : u64, primes : &[u64]) -> Integer {
+    if n < 2 {
+        return Integer::from(1);
+    }
+    let swing : Vec<u64> = primes.iter().take_while(|&&p| p <= n).filter_map(|&p| {
+        let (mut q, mut exponent) = (n, 0);
+        while q >= p {
+            q /= p;
+            exponent += q & 1;
+        }
+        // p^exponent <= n, so the power fits in a u64
+        if exponent > 0 {Some(p.pow(exponent as u32))} else {None}
+    }).collect();
+    factorial(n / 2, primes).square() * product(&swing)
+} // End of factorial function
+
+// returns the exponent of the prime p in n!, the sum of floor(n / p^i).
+fn legendre(mut n : u64, p : u64) -> u64 {
+    let mut exponent = 0;
+    while n >= p {
+        n /= p;
+        exponent += n;
+    }
+    exponent
+} // End of legendre function
+
+// returns the product of p^e over the primes and their exponents. Working down the bits of the
+// exponents squares the result once per bit and multiplies in the primes with that bit set.
+fn from_exponents(primes : &[u64], exponents : &[u64]) -> Integer {
+    let bits = exponents.iter().map(|e| 64 - e.leading_zeros()).max().unwrap_or(0);
+    let mut result = Integer::from(1);
+    for bit in (0..bits).rev() {
+        let chosen : Vec<u64> = primes.iter().zip(exponents.iter())
+            .filter(|&(_, e)| (e >> bit) & 1 == 1)
+            .map(|(&p, _)| p)
+            .collect();
+        result = result.square() * product(&chosen);
+    }
+    result
+} // End of from_exponents function
+
+// returns the product of the values by splitting them into a balanced tree.
+fn product(values : &[u64]) -> Integer {
+    if values.len() <= PRODUCT_LEAF {
+        return values.iter().fold(Integer::from(1), |product, &value| product * value);
+    }
+    let (low, high) = values.split_at(values.len() / 2);
+    product(low) * product(high)
+} // End of product function
+
+// returns low * (low + 1) * ... * high, or 1 for an empty range, as a balanced tree.
+fn range_product(low : u64, high : u64) -> Integer {
+    if low > high {
+        return Integer::from(1);
+    }
+    if high - low < PRODUCT_LEAF as u64 {
+        return (low..=high).fold(Integer::from(1), |product, value| product * value);
+    }
+    let middle = low + (high - low) / 2;
+    range_product(low, middle) * range_product(middle + 1, high)
+} // End of range_product function
+
+// returns row n, entry k of a Stirling triangle with T(i, j) = weight(i, j) T(i - 1, j) +
+// T(i - 1, j - 1) and T(0, 0) = 1, keeping one row and updating it from the right.
+fn stirling<F : Fn(u64, u64) -> u64>(n : u64, k : u64, weight : F) -> Integer {
+    if k > n {
+        return Integer::from(0);
+    }
+    let mut row = vec![Integer::from(0); k as usize + 1];
+    row[0] = Integer::from(1);
+    for i in 1..=n {
+        for j in (1..=k.min(i)).rev() {
+            let scaled = &row[j as usize] * weight(i, j);
+            row[j as usize] = scaled + &row[j as usize - 1];
+        }
+        row[0] = Integer::from(0);
+    }
+    row.swap_remove(k as usize)
+} // End of stirling function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests factorials and binomials against repeated multiplication and Pascal's triangle.
+#[test]
+fn factorial_binomial_test() {
+    let mut expected = Integer::from(1);
+    for n in 0..300u64 {
+        if n > 0 {
+            expected *= n;
+        }
+        assert!(Integer::factorial(n) == expected, "\nEvaluated {}! as {}\n", n, Integer::factorial(n));
+    }
+    let thousand = Integer::factorial(1000).to_string();
+    assert!(thousand.len() == 2568 && thousand.starts_with("402387260077") && thousand.ends_with(&"0".repeat(249)), "\nEvaluated 1000! as {}\n", thousand);
+
+    let mut row = vec![Integer::from(1)];
+    for n in 0..80u64 {
+        for k in 0..=n + 1 {
+            let expected = if k <= n {row[k as usize].clone()} else {Integer::from(0)};
+            assert!(Integer::binomial(n, k) == expected, "\nEvaluated {} choose {} as {}\n", n, k, Integer::binomial(n, k));
+        }
+        let mut next = vec![Integer::from(1); n as usize + 2];
+        for k in 1..=n as usize {
+            next[k] = &row[k-1] + &row[k];
+        }
+        row = next;
+    }
+    let n = 10_000_000_000u64;
+    let expected = Integer::from(n) * (n - 1) * (n - 2) / 6u32;
+    assert!(Integer::binomial(n, 3) == expected && Integer::binomial(n, n - 3) == expected, "\nEvaluated {} choose 3 as {}\n", n, Integer::binomial(n, 3));
+
+    let mut double = [Integer::from(1), Integer::from(1)];
+    for n in 0..100u64 {
+        if n > 1 {
+            double[n as usize % 2] *= n;
+        }
+        assert!(Integer::double_factorial(n) == double[n as usize % 2], "\nEvaluated {}!! as {}\n", n, Integer::double_factorial(n));
+    }
+    assert!(Integer::multinomial(&[2, 3, 4]) == Integer::from(1260), "\nEvaluated multinomial(2, 3, 4) as {}\n", Integer::multinomial(&[2, 3, 4]));
+    assert!(Integer::multinomial(&[]) == Integer::from(1), "\nEvaluated the empty multinomial as {}\n", Integer::multinomial(&[]));
+    assert!(Integer::primorial(30) == Integer::from(6_469_693_230u64) && Integer::primorial(1) == Integer::from(1), "\nEvaluated 30# as {}\n", Integer::primorial(30));
+} // End of factorial_binomial_test
+
+// Tests the Catalan, Stirling and Bell numbers against their known first values.
+#[test]
+fn combinatorial_numbers_test() {
+    let catalan = [1u64, 1, 2, 5, 14, 42, 132, 429, 1430, 4862, 16796];
+    let bell = [1u64, 1, 2, 5, 15, 52, 203, 877, 4140, 21147, 115975];
+    for n in 0..catalan.len() {
+        assert!(Integer::catalan(n as u64) == Integer::from(catalan[n]), "\nEvaluated Catalan {} as {}\n", n, Integer::catalan(n as u64));
+        assert!(Integer::bell(n as u64) == Integer::from(bell[n]), "\nEvaluated Bell {} as {}\n", n, Integer::bell(n as u64));
+    }
+
+    // each row of the triangles sums to n! and Bell n respectively
+    for n in 0..30u64 {
+        let first = (0..=n).fold(Integer::from(0), |sum, k| sum + Integer::stirling1(n, k));
+        let second = (0..=n).fold(Integer::from(0), |sum, k| sum + Integer::stirling2(n, k));
+        assert!(first == Integer::factorial(n), "\nEvaluated the first kind row {} as summing to {}\n", n, first);
+        assert!(second == Integer::bell(n), "\nEvaluated the second kind row {} as summing to {}\n", n, second);
+    }
+    assert!(Integer::stirling1(10, 3) == Integer::from(1_172_700) && Integer::stirling2(10, 3) == Integer::from(9330), "\nEvaluated the Stirling numbers (10, 3) incorrectly\n");
+    assert!(Integer::stirling2(3, 5).is_zero() && Integer::stirling1(0, 0) == Integer::from(1), "\nEvaluated the Stirling edge cases incorrectly\n");
+} // End of combinatorial_numbers_test
@@ -0,0 +1,275 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Systems of congruences and square roots modulo any modulus.
+// The Chinese remainder theorem merges congruences two at a time: x = a1 mod m1 and
+// x = a2 mod m2 have a solution exactly when gcd(m1, m2) divides a2 - a1, and it is then unique
+// mod lcm(m1, m2). Square roots mod n are found mod each prime power of n and merged the same
+// way. Mod an odd prime the root comes from Tonelli-Shanks, or from Cipolla's method when p - 1
+// has many factors of two, and Hensel's lemma lifts it to higher powers of the prime.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::{Integer, Montgomery};
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns (x, m) where x in [0, m) solves x = residue mod |modulus| for each pair and m is
+    // the lcm of the moduli, or None if the congruences are inconsistent or a modulus is zero.
+    // No congruences give (0, 1).
+    pub fn crt(congruences : &[(Integer, Integer)]) -> Option<(Integer, Integer)> {
+        let mut solution = (Integer::from(0), Integer::from(1));
+        for (residue, modulus) in congruences.iter() {
+            if modulus.is_zero() {
+                return None;
+            }
+            let (x, m) = solution;
+            let modulus = modulus.abs();
+
+            // x + m t = residue mod modulus, so m t = residue - x mod modulus, which needs
+            // g = gcd(m, modulus) to divide the difference, and then t = (difference / g) s
+            // mod (modulus / g) with s the Bezout coefficient of m
+            let (g, s, _) = m.extended_gcd(&modulus);
+            let (quotient, remainder) = (residue - &x).div_rem(&g);
+            if !remainder.is_zero() {
+                return None;
+            }
+            let step = &modulus / &g;
+            let t = modulo(&(quotient * s), &step);
+            let lcm = &m * &step;
+            solution = (modulo(&(x + &m * &t), &lcm), lcm);
+        }
+        Some(solution)
+    } // End of crt function
+
+    // returns a square root of self mod |modulus| in [0, |modulus|), or None if there is none
+    // or the modulus is zero. If r is a root so is |modulus| - r, and a modulus with k odd
+    // prime factors has up to 2^k roots, of which this returns one.
+    // The modulus is factored, so it should be prime or have a known easy factorization.
+    pub fn sqrt_mod(&self, modulus : &Integer) -> Option<Integer> {
+        if modulus.is_zero() {
+            return None;
+        }
+        let mut roots = Vec::new();
+        for (p, k) in modulus.abs().factor() {
+            let root = sqrt_mod_prime_power(self, &p, k)?;
+            roots.push((root, p.pow_u32(k)));
+        }
+        Integer::crt(&roots).map(|(root, _)| root)
+    } // End of sqrt_mod function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns the least non-negative residue of x modulo the positive n.
+fn modulo(x : &Integer, n : &Integer) -> Integer {
+    let remainder = x % n;
+    if remainder.negative {remainder + n} else {remainder}
+} // End of modulo function
+
+// returns a square root of a mod p^k.
+fn sqrt_mod_prime_power(a : &Integer, p : &Integer, k : u32) -> Option<Integer> {
+    let modulus = p.pow_u32(k);
+    let a = modulo(a, &modulus);
+    if a.is_zero() {
+        return Some(a);
+    }
+
+    // a = p^v b with b a unit has roots p^(v/2) s for the roots s of b mod p^(k-v), when v is even
+    let mut v = 0;
+    let mut b = a;
+    loop {
+        let (quotient, remainder) = b.div_rem(p);
+        if !remainder.is_zero() {
+            break;
+        }
+        b = quotient;
+        v += 1;
+    }
+    if v % 2 == 1 {
+        return None;
+    }
+    let e = k - v;
+    let b = modulo(&b, &p.pow_u32(e));
+    let s = if *p == Integer::from(2) {sqrt_unit_mod_2k(&b, e)?} else {sqrt_unit_mod_prime_power(&b, p, e)?};
+    Some(modulo(&(s * p.pow_u32(v / 2)), &modulus))
+} // End of sqrt_mod_prime_power function
+
+// returns a square root of the unit b mod p^e for an odd prime p, lifting a root r mod p^i
+// to r - (r^2 - b) / 2r mod p^(2i), which doubles the precision each step.
+fn sqrt_unit_mod_prime_power(b : &Integer, p : &Integer, e : u32) -> Option<Integer> {
+    let mut root = sqrt_mod_prime(&modulo(b, p), p)?;
+    let mut precision = 1;
+    while precision < e {
+        precision = (2 * precision).min(e);
+        let modulus = p.pow_u32(precision);
+        let inverse = (&root * 2u32).mod_inverse(&modulus).unwrap();
+        root = modulo(&(&root - &((root.square() - b) * inverse)), &modulus);
+    }
+    Some(root)
+} // End of sqrt_unit_mod_prime_power function
+
+// returns a square root of the odd b mod 2^e. Odd squares are 1 mod 8, and a root r mod 2^i
+// for i >= 3 becomes one mod 2^(i+1) by adding 2^(i-1) when r^2 - b is not divisible by 2^(i+1).
+fn sqrt_unit_mod_2k(b : &Integer, e : u32) -> Option<Integer> {
+    let low = b.value[0] & 7;
+    if (e == 2 && low & 3 != 1) || (e >= 3 && low != 1) {
+        return None;
+    }
+    let mut root = Integer::from(1);
+    for i in 3..e {
+        if (&root.square() - b).bit(i as u64) {
+            root += Integer::from(1) << (i as u64 - 1);
+        }
+    }
+    Some(root)
+} // End of sqrt_unit_mod_2k function
+
+// returns a square root of a mod the odd prime p, or None if a is not a square.
+fn sqrt_mod_prime(a : &Integer, p : &Integer) -> Option<Integer> {
+    if a.is_zero() {
+        return Some(Integer::from(0));
+    }
+    if a.jacobi(p).unwrap() != 1 {
+        return None;
+    }
+    let context = Montgomery::new(p).unwrap();
+    if p.value[0] & 3 == 3 {
+        // a^((p+1)/4) squares to a^((p+1)/2) = a (a/p) = a
+        return Some(context.pow_mod(a, &((p + 1u32) >> 2)).unwrap());
+    }
+
+    // p - 1 = q 2^s with q odd. Tonelli-Shanks takes up to s^2 / 2 multiplications on top of
+    // its exponentiations, so Cipolla's method is faster once s^2 passes the size of p.
+    let s = (p - 1u32).trailing_zeros().unwrap();
+    if s * s > p.bit_length() {
+        return Some(cipolla(a, p, &context));
+    }
+    let q = (p - 1u32) >> s;
+    let mut z = Integer::from(2);
+    while z.jacobi(p).unwrap() != -1 {
+        z += 1u32;
+    }
+    let mut m = s;
+    let mut c = context.pow_mod(&z, &q).unwrap();
+    let mut t = context.pow_mod(a, &q).unwrap();
+    let mut root = context.pow_mod(a, &((&q + 1u32) >> 1)).unwrap();
+    let one = Integer::from(1);
+    while t != one {
+        // the least i with t^(2^i) = 1, then b = c^(2^(m-i-1)) fixes the next bit
+        let mut i = 0;
+        let mut power = t.clone();
+        while power != one {
+            power = context.mul_mod(&power, &power);
+            i += 1;
+        }
+        let mut b = c.clone();
+        for _ in 0..m - i - 1 {
+            b = context.mul_mod(&b, &b);
+        }
+        m = i;
+        c = context.mul_mod(&b, &b);
+        t = context.mul_mod(&t, &c);
+        root = context.mul_mod(&root, &b);
+    }
+    Some(root)
+} // End of sqrt_mod_prime function
+
+// Cipolla's method: with t chosen so w = t^2 - a is not a square, (t + sqrt(w))^((p+1)/2) in
+// the field F_p(sqrt(w)) is a square root of a that lies in F_p.
+fn cipolla(a : &Integer, p : &Integer, context : &Montgomery) -> Integer {
+    let mut t = Integer::from(1);
+    let w = loop {
+        let w = modulo(&(t.square() - a), p);
+        if w.jacobi(p).unwrap() == -1 {
+            break w;
+        }
+        t += 1u32;
+    };
+    // (x1 + y1 sqrt(w)) (x2 + y2 sqrt(w)) = (x1 x2 + y1 y2 w) + (x1 y2 + x2 y1) sqrt(w)
+    let multiply = |(x1, y1) : &(Integer, Integer), (x2, y2) : &(Integer, Integer)| {
+        let yy = context.mul_mod(y1, y2);
+        (modulo(&(context.mul_mod(x1, x2) + context.mul_mod(&yy, &w)), p),
+         modulo(&(context.mul_mod(x1, y2) + context.mul_mod(x2, y1)), p))
+    };
+    let exponent = (p + 1u32) >> 1;
+    let mut result = (Integer::from(1), Integer::from(0));
+    for i in (0..exponent.bit_length()).rev() {
+        result = multiply(&result, &result);
+        if exponent.bit(i) {
+            result = multiply(&result, &(t.clone(), Integer::from(1)));
+        }
+    }
+    result.0
+} // End of cipolla function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the Chinese remainder theorem with coprime, shared and inconsistent moduli.
+#[test]
+fn crt_test() {
+    let pairs = |values : &[(i64, i64)]| -> Vec<(Integer, Integer)> {
+        values.iter().map(|&(r, m)| (Integer::from(r), Integer::from(m))).collect()
+    };
+    let solution = Integer::crt(&pairs(&[(2, 3), (3, 5), (2, 7)]));
+    assert!(solution == Some((Integer::from(23), Integer::from(105))), "\nEvaluated the CRT of 2 mod 3, 3 mod 5, 2 mod 7 as {:?}\n", solution);
+    let solution = Integer::crt(&pairs(&[(3, 4), (5, 6), (-1, -10)]));
+    assert!(solution == Some((Integer::from(59), Integer::from(60))), "\nEvaluated the CRT with shared factors as {:?}\n", solution);
+    assert!(Integer::crt(&pairs(&[(1, 4), (2, 6)])).is_none(), "\nEvaluated 1 mod 4 and 2 mod 6 as consistent\n");
+    assert!(Integer::crt(&pairs(&[(1, 0)])).is_none(), "\nEvaluated a zero modulus as consistent\n");
+    assert!(Integer::crt(&[]) == Some((Integer::from(0), Integer::from(1))), "\nEvaluated the empty CRT incorrectly\n");
+
+    // every residue pair for moduli 12 and 18 against brute force
+    for a in 0..12 {
+        for b in 0..18 {
+            let expected = (0..36).find(|x| x % 12 == a && x % 18 == b);
+            let solution = Integer::crt(&pairs(&[(a, 12), (b, 18)]));
+            assert!(solution == expected.map(|x| (Integer::from(x), Integer::from(36))), "\nEvaluated the CRT of {} mod 12, {} mod 18 as {:?}\n", a, b, solution);
+        }
+    }
+
+    let p = (Integer::from(1) << 127) - 1u32;
+    let q = (Integer::from(1) << 89) - 1u32;
+    let x = "123456789012345678901234567890123456789".parse::<Integer>().unwrap();
+    let solution = Integer::crt(&[(&x % &p, p.clone()), (&x % &q, q.clone())]).unwrap();
+    assert!(solution == (&x % &(&p * &q), &p * &q), "\nEvaluated the CRT of two Mersenne primes as {:?}\n", solution);
+} // End of crt_test
+
+// Tests modular square roots against brute force for prime, prime power and composite moduli,
+// and on large primes that take each of the three methods.
+#[test]
+fn sqrt_mod_test() {
+    for m in 1..150i64 {
+        let modulus = Integer::from(m);
+        for a in -3..m {
+            let exists = (0..m).any(|r| (r * r - a).rem_euclid(m) == 0);
+            match Integer::from(a).sqrt_mod(&modulus) {
+                Some(root) => {
+                    assert!(root >= Integer::from(0) && root < modulus && modulo(&(root.square() - a), &modulus).is_zero(), "\nEvaluated a root of {} mod {} as {}\n", a, m, root);
+                }
+                None => assert!(!exists, "\nEvaluated {} mod {} as having no root\n", a, m),
+            }
+        }
+    }
+    assert!(Integer::from(4).sqrt_mod(&Integer::from(0)).is_none(), "\nEvaluated a root mod 0\n");
+
+    // 2^127 - 1 is 3 mod 4, 2^64 - 59 is 5 mod 8 and 7 * 2^26 + 1 has p - 1 = 7 * 2^26
+    let primes = [(Integer::from(1) << 127) - 1u32, Integer::from(18446744073709551557u64), Integer::from(7 * (1u64 << 26) + 1)];
+    let a = "98765432109876543210987654321".parse::<Integer>().unwrap();
+    for p in primes.iter() {
+        let square = modulo(&a.square(), p);
+        let root = square.sqrt_mod(p).unwrap();
+        assert!(modulo(&(root.square() - &square), p).is_zero(), "\nEvaluated a root of {} mod {} as {}\n", square, p, root);
+        let cube = p.pow_u32(3);
+        let square = modulo(&a.square(), &cube);
+        let root = square.sqrt_mod(&cube).unwrap();
+        assert!(modulo(&(root.square() - &square), &cube).is_zero(), "\nEvaluated a root of {} mod {}^3 as {}\n", square, p, root);
+    }
+} // End of sqrt_mod_test
@@ -0,0 +1,241 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Conversions from Integer back to Rust's primitive integers and floats.
+// TryFrom fails with ConversionError::Overflow when the value is out of the target's range,
+// while the wrapping accessors keep the low bits of the two's complement form, like `as` does
+// between primitives. Floats are rounded to nearest with ties to even, the same rounding `as`
+// uses, by rounding a 64 bit prefix of the magnitude that remembers whether any lower bit is set.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::convert::TryFrom;
+use super::{Integer, ConversionError, significant};
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns the float nearest to the number, ties to even, or an infinity if it is too large.
+    pub fn to_f64(&self) -> f64 {
+        let (prefix, shift) = self.rounding_prefix();
+        let magnitude = if shift > 1023 {
+            f64::INFINITY
+        } else {
+            // multiplying by a power of two is exact until it overflows to infinity
+            prefix as f64 * f64::from_bits((1023 + shift) << 52)
+        };
+        if self.negative {-magnitude} else {magnitude}
+    } // End of to_f64 function
+
+    // returns the float nearest to the number, ties to even, or an infinity if it is too large.
+    // Rounds once from the Integer rather than through f64, which could round twice.
+    pub fn to_f32(&self) -> f32 {
+        let (prefix, shift) = self.rounding_prefix();
+        let magnitude = if shift > 127 {
+            f32::INFINITY
+        } else {
+            prefix as f32 * f32::from_bits((127 + shift as u32) << 23)
+        };
+        if self.negative {-magnitude} else {magnitude}
+    } // End of to_f32 function
+
+    // returns the float truncated toward zero, like `as` between floats and integers, or an
+    // error if it is NaN or infinite.
+    pub fn from_f64(value : f64) -> Result<Integer, ConversionError> {
+        if !value.is_finite() {
+            return Err(ConversionError::NotFinite);
+        }
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7FF) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+
+        // value = mantissa * 2^(exponent - 1075), subnormals have no implicit leading one
+        let (mantissa, exponent) = if exponent == 0 {(fraction, -1074)} else {(fraction | 1 << 52, exponent - 1075)};
+        let magnitude = if exponent >= 0 {
+            &Integer::from(mantissa) << exponent as u64
+        } else {
+            Integer::from(mantissa.checked_shr(-exponent as u32).unwrap_or(0))
+        };
+        Ok(if value < 0.0 {-magnitude} else {magnitude})
+    } // End of from_f64 function
+
+    // returns the low 128 bits of the two's complement form of the number.
+    pub fn to_u128_wrapping(&self) -> u128 {
+        let value = &self.value;
+        let low = value[0] as u128 | if value.len() > 1 {(value[1] as u128) << 64} else {0};
+        if self.negative {low.wrapping_neg()} else {low}
+    } // End of to_u128_wrapping function
+
+    // returns the low 128 bits of the two's complement form of the number as a signed value.
+    #[inline]
+    pub fn to_i128_wrapping(&self) -> i128 {
+        self.to_u128_wrapping() as i128
+    } // End of to_i128_wrapping function
+
+    // returns the low 64 bits of the two's complement form of the number.
+    #[inline]
+    pub fn to_u64_wrapping(&self) -> u64 {
+        self.to_u128_wrapping() as u64
+    } // End of to_u64_wrapping function
+
+    // returns the low 64 bits of the two's complement form of the number as a signed value.
+    #[inline]
+    pub fn to_i64_wrapping(&self) -> i64 {
+        self.to_u128_wrapping() as i64
+    } // End of to_i64_wrapping function
+
+    // returns the low 32 bits of the two's complement form of the number.
+    #[inline]
+    pub fn to_u32_wrapping(&self) -> u32 {
+        self.to_u128_wrapping() as u32
+    } // End of to_u32_wrapping function
+
+    // returns the low 32 bits of the two's complement form of the number as a signed value.
+    #[inline]
+    pub fn to_i32_wrapping(&self) -> i32 {
+        self.to_u128_wrapping() as i32
+    } // End of to_i32_wrapping function
+
+    // returns (prefix, shift) with |self| rounding the same way as prefix * 2^shift.
+    // The prefix holds the top 64 bits of the magnitude, with its lowest bit set if any bit
+    // shifted out was set. A float keeps at most 53 bits, so that sticky bit is far enough
+    // below the rounding position to break ties without changing any other rounding.
+    fn rounding_prefix(&self) -> (u64, u64) {
+        let shift = self.bit_length().saturating_sub(64);
+        if shift == 0 {
+            return (self.value[0], 0);
+        }
+        let prefix = (&self.abs() >> shift).value[0];
+        let sticky = self.trailing_zeros().unwrap_or(0) < shift;
+        (prefix | sticky as u64, shift)
+    } // End of rounding_prefix function
+
+    // returns the magnitude as a u128 and the sign, or None if the magnitude needs more limbs.
+    fn to_u128_parts(&self) -> Option<(u128, bool)> {
+        match significant(&self.value) {
+            [low] => Some((*low as u128, self.negative)),
+            [low, high] => Some(((*high as u128) << 64 | *low as u128, self.negative)),
+            _ => None,
+        }
+    } // End of to_u128_parts function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Macros                                                                                   */
+/* ============================================================================================ */
+
+// Implements TryFrom<&Integer> and TryFrom<Integer> for unsigned primitives.
+macro_rules! try_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl<'a> TryFrom<&'a Integer> for $t {
+            type Error = ConversionError;
+
+            fn try_from(integer : &'a Integer) -> Result<$t, ConversionError> {
+                match integer.to_u128_parts() {
+                    Some((magnitude, false)) => <$t>::try_from(magnitude).map_err(|_| ConversionError::Overflow),
+                    _ => Err(ConversionError::Overflow),
+                }
+            }
+        }
+
+        impl TryFrom<Integer> for $t {
+            type Error = ConversionError;
+
+            #[inline]
+            fn try_from(integer : Integer) -> Result<$t, ConversionError> {
+                <$t>::try_from(&integer)
+            }
+        }
+    )*};
+}
+
+// Implements TryFrom<&Integer> and TryFrom<Integer> for signed primitives.
+macro_rules! try_from_signed {
+    ($($t:ty),*) => {$(
+        impl<'a> TryFrom<&'a Integer> for $t {
+            type Error = ConversionError;
+
+            fn try_from(integer : &'a Integer) -> Result<$t, ConversionError> {
+                // i128::MIN has the magnitude 2^127 and wraps to itself when negated
+                let value = match integer.to_u128_parts() {
+                    Some((magnitude, false)) if magnitude <= i128::MAX as u128 => magnitude as i128,
+                    Some((magnitude, true)) if magnitude <= i128::MIN.unsigned_abs() => (magnitude as i128).wrapping_neg(),
+                    _ => return Err(ConversionError::Overflow),
+                };
+                <$t>::try_from(value).map_err(|_| ConversionError::Overflow)
+            }
+        }
+
+        impl TryFrom<Integer> for $t {
+            type Error = ConversionError;
+
+            #[inline]
+            fn try_from(integer : Integer) -> Result<$t, ConversionError> {
+                <$t>::try_from(&integer)
+            }
+        }
+    )*};
+}
+
+try_from_unsigned!(u8, u16, u32, u64, u128, usize);
+try_from_signed!(i8, i16, i32, i64, i128, isize);
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the TryFrom bounds of each width and the wrapping accessors against `as`.
+#[test]
+fn try_from_and_wrapping_test() {
+    let values = [0i128, 1, -1, 127, 128, -128, -129, 255, 256, u32::MAX as i128, i64::MIN as i128,
+        u64::MAX as i128, 1 << 64, i128::MAX, i128::MIN];
+    for &value in values.iter() {
+        let integer = Integer::from(value);
+        assert!(i8::try_from(&integer).ok() == i8::try_from(value).ok(), "\nEvaluated {} as i8 incorrectly\n", value);
+        assert!(u8::try_from(&integer).ok() == u8::try_from(value).ok(), "\nEvaluated {} as u8 incorrectly\n", value);
+        assert!(i32::try_from(&integer).ok() == i32::try_from(value).ok(), "\nEvaluated {} as i32 incorrectly\n", value);
+        assert!(u64::try_from(&integer).ok() == u64::try_from(value).ok(), "\nEvaluated {} as u64 incorrectly\n", value);
+        assert!(i128::try_from(integer.clone()) == Ok(value), "\nEvaluated {} as i128 incorrectly\n", value);
+        assert!(u128::try_from(&integer).ok() == u128::try_from(value).ok(), "\nEvaluated {} as u128 incorrectly\n", value);
+        assert!(integer.to_u64_wrapping() == value as u64, "\nEvaluated {} wrapped to u64 as {}\n", value, integer.to_u64_wrapping());
+        assert!(integer.to_i32_wrapping() == value as i32, "\nEvaluated {} wrapped to i32 as {}\n", value, integer.to_i32_wrapping());
+    }
+
+    let huge = Integer::from(u128::MAX) * 3u32;
+    assert!(u128::try_from(&huge) == Err(ConversionError::Overflow), "\nEvaluated 3 (2^128 - 1) as a u128\n");
+    assert!(i128::try_from(&(Integer::from(i128::MIN) - 1)) == Err(ConversionError::Overflow), "\nEvaluated i128::MIN - 1 as an i128\n");
+    assert!(huge.to_u128_wrapping() == u128::MAX.wrapping_mul(3), "\nEvaluated 3 (2^128 - 1) wrapped as {}\n", huge.to_u128_wrapping());
+    assert!((-huge).to_i64_wrapping() == -(u64::MAX.wrapping_mul(3) as i64), "\nEvaluated -3 (2^128 - 1) wrapped to i64 incorrectly\n");
+} // End of try_from_and_wrapping_test
+
+// Tests that float conversions round to nearest even, overflow to infinity and truncate on the
+// way back, matching `as` on the values where primitives can check them.
+#[test]
+fn float_conversion_test() {
+    let values = [0u128, 1, (1 << 53) - 1, 1 << 53, (1 << 53) + 1, (1 << 53) + 3, (1 << 54) + 2, (1 << 54) + 6,
+        u64::MAX as u128, (1 << 100) + (1 << 47), (1 << 100) + (1 << 47) + 1, (1 << 100) + (3 << 47), u128::MAX];
+    for &value in values.iter() {
+        let integer = Integer::from(value);
+        assert!(integer.to_f64() == value as f64, "\nEvaluated {} as the f64 {}\n", value, integer.to_f64());
+        assert!(integer.to_f32() == value as f32, "\nEvaluated {} as the f32 {}\n", value, integer.to_f32());
+        assert!((-&integer).to_f64() == -(value as f64), "\nEvaluated -{} as the f64 {}\n", value, (-&integer).to_f64());
+    }
+
+    // the sticky bit decides a tie that only a bit far below the prefix breaks
+    let above_tie = (Integer::from(1) << 1000) + (Integer::from(1) << 947) + (Integer::from(1) << 3);
+    assert!(above_tie.to_f64() == 2f64.powi(1000) + 2f64.powi(948), "\nEvaluated 2^1000 + 2^947 + 8 as {}\n", above_tie.to_f64());
+    assert!((Integer::from(1) << 1024).to_f64() == f64::INFINITY, "\nEvaluated 2^1024 as finite\n");
+    assert!((Integer::from(1) << 128).to_f32() == f32::INFINITY, "\nEvaluated 2^128 as a finite f32\n");
+    assert!(((Integer::from(1) << 1024) - 1u32).to_f64() == f64::INFINITY, "\nEvaluated 2^1024 - 1 as finite\n");
+
+    for &float in [0.0, -0.5, 1.5, -2.75, 1e15, 9.007199254740993e15, -1.7e38, 5e-324].iter() {
+        let integer = Integer::from_f64(float).unwrap();
+        assert!(integer == Integer::from(float as i128), "\nEvaluated {} as the Integer {}\n", float, integer);
+    }
+    let max = Integer::from_f64(f64::MAX).unwrap();
+    assert!(max.to_f64() == f64::MAX && max.bit_length() == 1024, "\nEvaluated f64::MAX as {}\n", max);
+    assert!(Integer::from_f64(f64::NAN) == Err(ConversionError::NotFinite), "\nEvaluated NaN as an Integer\n");
+    assert!(Integer::from_f64(f64::NEG_INFINITY) == Err(ConversionError::NotFinite), "\nEvaluated -inf as an Integer\n");
+} // End of float_conversion_test
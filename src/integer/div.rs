@@ -0,0 +1,331 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Long division over the u64 limb vectors used by Integer.
+// Inputs and outputs are magnitudes stored least significant limb first, signs are handled by
+// the Div and Rem operators in the parent module. Multi-limb divisors use Knuth's Algorithm D
+// (The Art of Computer Programming Vol. 2, 4.3.1) with u128 intermediates, which takes time
+// proportional to the product of the quotient and divisor lengths.
+// When both are long the division is done with multiplications instead: Newton's iteration finds
+// the reciprocal floor(2^(128n) / v) of the n limb divisor v, after which every n limbs of the
+// quotient cost two multiplications. With NTT multiplication this is close to linear time.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::cmp::Ordering;
+use super::{add_assign_limbs, cmp_limbs, significant, sub_assign_limbs, sub_limbs, trim, mul};
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+// the smallest divisor and quotient, in limbs, that are divided with a reciprocal
+const NEWTON_THRESHOLD : usize = 64;
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// Divides the magnitude u by the magnitude v, returning (quotient, remainder).
+// The divisor must not be zero.
+pub fn div_rem(u: &[u64], v: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let (u, v) = (significant(u), significant(v));
+    assert!(!(v.len() == 1 && v[0] == 0), "attempt to divide by zero");
+
+    // a smaller dividend divides to zero and is its own remainder
+    if cmp_limbs(u, v) == Ordering::Less {
+        return (vec![0], u.to_vec());
+    }
+    if v.len() == 1 {
+        let (quotient, remainder) = div_rem_limb(u, v[0]);
+        return (quotient, vec![remainder]);
+    }
+    if v.len() >= NEWTON_THRESHOLD && u.len() - v.len() >= NEWTON_THRESHOLD {
+        return Reciprocal::new(v).div_rem(u);
+    }
+    knuth_d(u, v)
+} // End of div_rem function
+
+// Divides the magnitude u by a single limb, returning (quotient, remainder).
+pub fn div_rem_limb(u: &[u64], d: u64) -> (Vec<u64>, u64) {
+    let mut quotient = vec![0; u.len()];
+    let mut remainder : u128 = 0;
+    for (i, limb) in u.iter().enumerate().rev() {
+        let current = (remainder << 64) | *limb as u128;
+        quotient[i] = (current / d as u128) as u64;
+        remainder = current % d as u128;
+    }
+    trim(&mut quotient);
+    (quotient, remainder as u64)
+} // End of div_rem_limb function
+
+// Knuth's Algorithm D. Requires u >= v and v to have at least two significant limbs.
+fn knuth_d(u: &[u64], v: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let n = v.len();
+    let m = u.len() - n;
+
+    // D1: normalize so the top bit of the divisor is set, this keeps the qhat estimate within 2
+    let shift = v[n-1].leading_zeros();
+    let vn = shift_left(v, shift);
+    let mut un = shift_left(u, shift);
+    un.push(if shift == 0 {0} else {u[u.len()-1] >> (64 - shift)});
+
+    let mut quotient = vec![0u64; m + 1];
+    let base : u128 = 1 << 64;
+    let (v_high, v_next) = (vn[n-1] as u128, vn[n-2] as u128);
+
+    // D2 - D7: produce one quotient limb per iteration, most significant first
+    for j in (0..=m).rev() {
+        // D3: estimate qhat from the top two limbs of the running remainder
+        let top = ((un[j+n] as u128) << 64) | un[j+n-1] as u128;
+        let mut qhat = top / v_high;
+        let mut rhat = top % v_high;
+        while qhat >= base || qhat * v_next > ((rhat << 64) | un[j+n-2] as u128) {
+            qhat -= 1;
+            rhat += v_high;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // D4: multiply and subtract qhat * v from the current window of u
+        let mut borrow : u64 = 0;
+        let mut carry : u128 = 0;
+        for i in 0..n {
+            let product = qhat * vn[i] as u128 + carry;
+            carry = product >> 64;
+            let (diff, overflow1) = un[i+j].overflowing_sub(product as u64);
+            let (diff, overflow2) = diff.overflowing_sub(borrow);
+            un[i+j] = diff;
+            borrow = (overflow1 || overflow2) as u64;
+        }
+        let (diff, overflow1) = un[j+n].overflowing_sub(carry as u64);
+        let (diff, overflow2) = diff.overflowing_sub(borrow);
+        un[j+n] = diff;
+
+        // D5 - D6: the estimate was one too large (rare), so add the divisor back
+        if overflow1 || overflow2 {
+            qhat -= 1;
+            let mut carry = false;
+            for i in 0..n {
+                let (sum, overflow1) = un[i+j].overflowing_add(vn[i]);
+                let (sum, overflow2) = sum.overflowing_add(carry as u64);
+                un[i+j] = sum;
+                carry = overflow1 || overflow2;
+            }
+            un[j+n] = un[j+n].wrapping_add(carry as u64);
+        }
+        quotient[j] = qhat as u64;
+    }
+
+    // D8: unnormalize the remainder
+    let mut remainder = shift_right(&un[..n], shift);
+    trim(&mut quotient);
+    trim(&mut remainder);
+    (quotient, remainder)
+} // End of knuth_d function
+
+// returns floor(2^(128n) / v) for a divisor v of n limbs whose top bit is set.
+// The reciprocal of the top h = n/2 + 1 limbs, lowered by 4 so it can not overshoot, gives x with
+// at least 64h correct bits. One Newton step x + x * (2^(128n) - v * x) / 2^(128n) doubles that
+// to more than 64n bits while staying below the true value, so at most a couple of units are
+// added back at the end.
+fn reciprocal(v: &[u64]) -> Vec<u64> {
+    let n = v.len();
+    let mut power = vec![0u64; 2 * n + 1];
+    power[2 * n] = 1;
+    if n < NEWTON_THRESHOLD {
+        return div_rem(&power, v).0;
+    }
+
+    let h = n / 2 + 1;
+    let y = sub_limbs(&reciprocal(&v[n-h..]), &[4]);
+    // the error of x = y * 2^(64(n-h)), which is never negative
+    let mut product = vec![0u64; n - h];
+    product.extend_from_slice(&mul::mul(v, &y));
+    let error = sub_limbs(&power, &product);
+    let correction = mul::mul(&y, &error);
+
+    let mut x = vec![0u64; n - h];
+    x.extend_from_slice(&y);
+    if correction.len() > n + h {
+        add_assign_limbs(&mut x, &correction[n+h..]);
+    }
+    let mut remainder = sub_limbs(&power, &mul::mul(v, &x));
+    while cmp_limbs(&remainder, v) != Ordering::Less {
+        add_assign_limbs(&mut x, &[1]);
+        sub_assign_limbs(&mut remainder, v, false);
+    }
+    x
+} // End of reciprocal function
+
+// Shifts the limbs left by less than 64 bits, dropping any bits shifted out of the top limb.
+fn shift_left(v: &[u64], shift: u32) -> Vec<u64> {
+    if shift == 0 {
+        return v.to_vec();
+    }
+    let mut result = Vec::with_capacity(v.len() + 1);
+    let mut carry = 0;
+    for limb in v {
+        result.push((limb << shift) | carry);
+        carry = limb >> (64 - shift);
+    }
+    result
+} // End of shift_left function
+
+// Shifts the limbs right by less than 64 bits.
+fn shift_right(v: &[u64], shift: u32) -> Vec<u64> {
+    if shift == 0 {
+        return v.to_vec();
+    }
+    let mut result = vec![0; v.len()];
+    for i in 0..v.len() {
+        let high = if i + 1 < v.len() {v[i+1] << (64 - shift)} else {0};
+        result[i] = (v[i] >> shift) | high;
+    }
+    result
+} // End of shift_right function
+
+/* ============================================================================================ */
+/*     Reciprocal                                                                               */
+/* ============================================================================================ */
+
+// A divisor with its reciprocal, for dividing many values by the same divisor.
+// The divisor is shifted so its top bit is set, and each dividend is shifted to match.
+pub struct Reciprocal {
+    divisor : Vec<u64>,     // the divisor v shifted left, n limbs
+    shift : u32,            // the number of bits the divisor was shifted by
+    inverse : Vec<u64>,     // floor(2^(128n) / v)
+}
+
+impl Reciprocal {
+    // creates the reciprocal of a divisor, which must not be zero.
+    pub fn new(v: &[u64]) -> Reciprocal {
+        let v = significant(v);
+        assert!(!(v.len() == 1 && v[0] == 0), "attempt to divide by zero");
+        let shift = v[v.len()-1].leading_zeros();
+        let divisor = shift_left(v, shift);
+        let inverse = reciprocal(&divisor);
+        Reciprocal { divisor, shift, inverse }
+    } // End of new function
+
+    // Divides the magnitude u by the divisor, returning (quotient, remainder).
+    // The dividend is taken n limbs at a time, most significant first, so each step divides a
+    // value below v * 2^(64n) and yields n limbs of the quotient.
+    pub fn div_rem(&self, u: &[u64]) -> (Vec<u64>, Vec<u64>) {
+        let n = self.divisor.len();
+        let u = significant(u);
+        let mut un = shift_left(u, self.shift);
+        un.push(if self.shift == 0 {0} else {u[u.len()-1] >> (64 - self.shift)});
+
+        let blocks = un.len().div_ceil(n);
+        let mut quotient = vec![0u64; blocks * n];
+        let mut remainder = vec![0u64];
+        for block in (0..blocks).rev() {
+            let end = un.len().min((block + 1) * n);
+            let mut current = un[block*n..end].to_vec();
+            if remainder != [0] {
+                current.resize(n, 0);
+                current.extend_from_slice(&remainder);
+            }
+            let (digits, rest) = self.div_rem_block(&current);
+            quotient[block*n..block*n + digits.len()].copy_from_slice(&digits);
+            remainder = rest;
+        }
+        trim(&mut quotient);
+        let mut remainder = shift_right(&remainder, self.shift);
+        trim(&mut remainder);
+        (quotient, remainder)
+    } // End of div_rem function
+
+    // Divides a value below 2^(128n) by the shifted divisor. Only the top n + 1 limbs of u are
+    // multiplied by the inverse, the estimate floor(top * inverse / 2^(64(n+1))) is still at most
+    // two below the quotient.
+    fn div_rem_block(&self, u: &[u64]) -> (Vec<u64>, Vec<u64>) {
+        let n = self.divisor.len();
+        if u.len() < n {
+            return (vec![0], u.to_vec());
+        }
+        let product = mul::mul(&u[n-1..], &self.inverse);
+        let mut quotient = if product.len() > n + 1 {product[n+1..].to_vec()} else {vec![0]};
+        let mut remainder = sub_limbs(u, &mul::mul(&quotient, &self.divisor));
+        while cmp_limbs(&remainder, &self.divisor) != Ordering::Less {
+            add_assign_limbs(&mut quotient, &[1]);
+            sub_assign_limbs(&mut remainder, &self.divisor, false);
+        }
+        (quotient, remainder)
+    } // End of div_rem_block function
+} // End of Reciprocal implementation
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that the quotient and remainder reconstruct the dividend for multi-limb operands,
+// including divisors whose top limb forces the add back step of Algorithm D.
+#[test]
+fn knuth_d_reconstruct_test() {
+    let cases : Vec<(Vec<u64>, Vec<u64>)> = vec![
+        (vec![0, 0, 0x8000_0000_0000_0000], vec![1, 0x8000_0000_0000_0000]),
+        (vec![u64::MAX, u64::MAX, u64::MAX, u64::MAX], vec![u64::MAX, u64::MAX]),
+        (vec![3, 0, 0x7FFF_8000_0000_0000], vec![1, 0, 0x8000_0000_0000_0000]),
+        (vec![0x1234_5678, 0x9ABC_DEF0, 0x0FED_CBA9, 42], vec![0xFFFF_FFFF, 1]),
+        (vec![0, 0, 0, 1], vec![u64::MAX, u64::MAX, 0]),
+    ];
+    for (u, v) in cases {
+        let (q, r) = div_rem(&u, &v);
+        assert!(cmp_limbs(&r, significant(&v)) == Ordering::Less, "\nremainder {:?} is not less than divisor {:?}\n", r, v);
+
+        // rebuild q * v + r with schoolbook multiplication and compare against u
+        let mut rebuilt = vec![0u64; q.len() + v.len() + 1];
+        for (i, a) in q.iter().enumerate() {
+            let mut carry : u128 = 0;
+            for (k, b) in v.iter().enumerate() {
+                let t = (*a as u128) * (*b as u128) + rebuilt[i+k] as u128 + carry;
+                rebuilt[i+k] = t as u64;
+                carry = t >> 64;
+            }
+            rebuilt[i+v.len()] = carry as u64;
+        }
+        let mut carry = false;
+        for i in 0..rebuilt.len() {
+            let addend = if i < r.len() {r[i]} else {0};
+            let (sum, overflow1) = rebuilt[i].overflowing_add(addend);
+            let (sum, overflow2) = sum.overflowing_add(carry as u64);
+            rebuilt[i] = sum;
+            carry = overflow1 || overflow2;
+        }
+        assert!(significant(&rebuilt) == significant(&u), "\nq * v + r != u\nu = {:?}\nv = {:?}\nq = {:?}\nr = {:?}\n", u, v, q, r);
+    }
+} // End of knuth_d_reconstruct_test
+
+// Tests that dividing with a reciprocal agrees with Algorithm D for divisors at and above the
+// Newton threshold, including divisors of all ones and a lone top bit, and dividends many times
+// the length of the divisor.
+#[test]
+fn reciprocal_div_rem_test() {
+    // a simple linear congruential generator keeps the operands reproducible
+    let mut state : u64 = 0x2545_F491_4F6C_DD1D;
+    let mut random = |len: usize| -> Vec<u64> {
+        (0..len).map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            state
+        }).collect()
+    };
+    let mut top_bit = vec![0u64; 200];
+    top_bit[199] = 1 << 63;
+    let divisors = vec![random(NEWTON_THRESHOLD), random(150), vec![u64::MAX; 130], top_bit, random(301)];
+    for v in divisors {
+        for &len in [v.len() + NEWTON_THRESHOLD, 2 * v.len(), 5 * v.len() + 7].iter() {
+            let u = random(len);
+            let expected = knuth_d(&u, &v);
+            let (q, r) = Reciprocal::new(&v).div_rem(&u);
+            assert!(q == expected.0 && r == expected.1, "\nEvaluated a {} by {} limb division with a reciprocal wrongly\n", u.len(), v.len());
+        }
+        let inverse = reciprocal(&shift_left(&v, v[v.len()-1].leading_zeros()));
+        let mut power = vec![0u64; 2 * v.len() + 1];
+        power[2 * v.len()] = 1;
+        let expected = knuth_d(&power, &shift_left(&v, v[v.len()-1].leading_zeros())).0;
+        assert!(inverse == expected, "\nEvaluated the reciprocal of a {} limb divisor wrongly\n", v.len());
+    }
+} // End of reciprocal_div_rem_test
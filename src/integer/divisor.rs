@@ -0,0 +1,203 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Multiplicative functions and divisors, all computed from the prime factorization.
+// Each function depends only on the magnitude, so -n gives the same result as n, and 0, which
+// every Integer divides, gives 0 from each function and no divisors.
+// Factoring is the expensive step, so numbers with two large prime factors take as long as
+// Integer::factor does for them.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::Integer;
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns Euler's totient, the count of 1 <= k <= |n| coprime to n:
+    // the product of p^(e-1) (p - 1) over the prime powers of n.
+    pub fn euler_phi(&self) -> Integer {
+        if self.is_zero() {
+            return Integer::from(0);
+        }
+        prime_powers(self).iter().fold(Integer::from(1), |phi, (p, e)| {
+            phi * p.pow_u32(e - 1) * (p - 1u32)
+        })
+    } // End of euler_phi function
+
+    // returns the Carmichael function, the least m with a^m = 1 mod n for every a coprime
+    // to n: the lcm over the prime powers of n of phi(p^e), halved for powers of 2 from 8 on.
+    pub fn carmichael_lambda(&self) -> Integer {
+        if self.is_zero() {
+            return Integer::from(0);
+        }
+        prime_powers(self).iter().fold(Integer::from(1), |lambda, (p, e)| {
+            let mut order = p.pow_u32(e - 1) * (p - 1u32);
+            if *p == Integer::from(2) && *e >= 3 {
+                order >>= 1;
+            }
+            lambda.lcm(&order)
+        })
+    } // End of carmichael_lambda function
+
+    // returns the number of positive divisors, the product of e + 1 over the prime powers.
+    pub fn divisor_count(&self) -> Integer {
+        if self.is_zero() {
+            return Integer::from(0);
+        }
+        prime_powers(self).iter().fold(Integer::from(1), |count, &(_, e)| count * (e + 1))
+    } // End of divisor_count function
+
+    // returns sigma_k, the sum of d^k over the positive divisors d, so k = 0 counts them and
+    // k = 1 sums them. Each prime power contributes 1 + p^k + ... + p^(ke).
+    pub fn divisor_sum(&self, k : u32) -> Integer {
+        if self.is_zero() {
+            return Integer::from(0);
+        }
+        if k == 0 {
+            return self.divisor_count();
+        }
+        prime_powers(self).iter().fold(Integer::from(1), |sum, (p, e)| {
+            let power = p.pow_u32(k);
+            sum * ((power.pow_u32(e + 1) - 1u32) / (power - 1u32))
+        })
+    } // End of divisor_sum function
+
+    // returns the Mobius function: 0 if a square above 1 divides n, otherwise -1 to the
+    // number of prime factors.
+    pub fn mobius(&self) -> i32 {
+        if self.is_zero() {
+            return 0;
+        }
+        let factors = prime_powers(self);
+        if factors.iter().any(|&(_, e)| e > 1) {
+            0
+        } else if factors.len() % 2 == 1 {
+            -1
+        } else {
+            1
+        }
+    } // End of mobius function
+
+    // returns an iterator over the positive divisors, starting from 1 and working through
+    // the exponents of the primes in increasing order, so the divisors are not sorted.
+    pub fn divisors(&self) -> Divisors {
+        let factors = if self.is_zero() {Vec::new()} else {prime_powers(self)};
+        Divisors {
+            powers: vec![Integer::from(1); factors.len()],
+            exponents: vec![0; factors.len()],
+            factors,
+            done: self.is_zero(),
+        }
+    } // End of divisors function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Divisors                                                                                 */
+/* ============================================================================================ */
+
+// An iterator over the positive divisors of an Integer, created by Integer::divisors.
+// It counts through every exponent vector like an odometer, so it holds only the
+// factorization however many divisors there are.
+pub struct Divisors {
+    factors : Vec<(Integer, u32)>,  // the prime factorization of |n|
+    exponents : Vec<u32>,           // the exponent of each prime in the next divisor
+    powers : Vec<Integer>,          // each prime raised to its exponent
+    done : bool,                    // true once every divisor has been returned
+}
+
+impl Iterator for Divisors {
+    type Item = Integer;
+
+    fn next(&mut self) -> Option<Integer> {
+        if self.done {
+            return None;
+        }
+        let divisor = self.powers.iter().fold(Integer::from(1), |product, power| product * power);
+
+        // step the lowest exponent that can grow and reset those below it
+        self.done = true;
+        for i in 0..self.factors.len() {
+            if self.exponents[i] < self.factors[i].1 {
+                self.exponents[i] += 1;
+                self.powers[i] = &self.powers[i] * &self.factors[i].0;
+                self.done = false;
+                break;
+            }
+            self.exponents[i] = 0;
+            self.powers[i] = Integer::from(1);
+        }
+        Some(divisor)
+    } // End of next function
+} // End of Iterator implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns the prime factorization of |n| for a non-zero n.
+fn prime_powers(n : &Integer) -> Vec<(Integer, u32)> {
+    n.abs().factor()
+} // End of prime_powers function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests each function against brute force over the divisors of small values of both signs.
+#[test]
+fn small_divisor_functions_test() {
+    for n in -300i64..300 {
+        let integer = Integer::from(n);
+        let m = n.abs();
+        let divisors : Vec<i64> = (1..=m).filter(|d| m % d == 0).collect();
+        let gcd = |mut a : i64, mut b : i64| {
+            while b != 0 {
+                let r = a % b;
+                a = b;
+                b = r;
+            }
+            a
+        };
+
+        let phi = (1..=m).filter(|&k| gcd(k, m) == 1).count() as i64;
+        assert!(integer.euler_phi() == Integer::from(phi), "\nEvaluated phi({}) as {}\n", n, integer.euler_phi());
+        let lambda = if m == 0 {0} else {
+            (1..=m.max(1)).find(|&l| (1..=m).filter(|&a| gcd(a, m) == 1).all(|a| (0..l).fold(1 % m, |x, _| x * a % m) == 1 % m)).unwrap()
+        };
+        assert!(integer.carmichael_lambda() == Integer::from(lambda), "\nEvaluated lambda({}) as {}\n", n, integer.carmichael_lambda());
+        assert!(integer.divisor_count() == Integer::from(divisors.len() as u64), "\nEvaluated d({}) as {}\n", n, integer.divisor_count());
+        for k in 0..4u32 {
+            let sum : i64 = divisors.iter().map(|d| d.pow(k)).sum();
+            assert!(integer.divisor_sum(k) == Integer::from(sum), "\nEvaluated sigma_{}({}) as {}\n", k, n, integer.divisor_sum(k));
+        }
+        let squarefree = m != 0 && (2..=m).all(|d| m % (d * d) != 0);
+        let omega = divisors.iter().filter(|&&d| d > 1 && (2..d).all(|q| d % q != 0)).count();
+        let mobius = if !squarefree {0} else if omega % 2 == 1 {-1} else {1};
+        assert!(integer.mobius() == mobius, "\nEvaluated mu({}) as {}\n", n, integer.mobius());
+
+        let mut found : Vec<Integer> = integer.divisors().collect();
+        found.sort();
+        let expected : Vec<Integer> = divisors.iter().map(|&d| Integer::from(d)).collect();
+        assert!(found == expected, "\nEvaluated the divisors of {} as {:?}\n", n, found);
+    }
+} // End of small_divisor_functions_test
+
+// Tests the functions on multi-limb values with known factorizations.
+#[test]
+fn large_divisor_functions_test() {
+    // 2^64 + 1 = 274177 * 67280421310721
+    let n = (Integer::from(1) << 64) + 1u32;
+    let (p, q) = (Integer::from(274_177), Integer::from(67_280_421_310_721u64));
+    assert!(n.euler_phi() == (&p - 1u32) * (&q - 1u32), "\nEvaluated phi(2^64 + 1) as {}\n", n.euler_phi());
+    assert!(n.carmichael_lambda() == (&p - 1u32).lcm(&(&q - 1u32)), "\nEvaluated lambda(2^64 + 1) as {}\n", n.carmichael_lambda());
+    assert!(n.divisor_sum(1) == (&p + 1u32) * (&q + 1u32) && n.mobius() == 1, "\nEvaluated sigma(2^64 + 1) as {}\n", n.divisor_sum(1));
+
+    // 2^100 3^2 has 101 * 3 divisors and lambda 2^98
+    let m = -(Integer::from(9) << 100);
+    assert!(m.divisor_count() == Integer::from(303) && m.divisors().count() == 303, "\nEvaluated d(-9 2^100) as {}\n", m.divisor_count());
+    assert!(m.carmichael_lambda() == Integer::from(3) << 98 && m.mobius() == 0, "\nEvaluated lambda(-9 2^100) as {}\n", m.carmichael_lambda());
+    assert!(Integer::from(0).divisors().next().is_none() && Integer::from(1).divisors().collect::<Vec<_>>() == vec![Integer::from(1)], "\nEvaluated the divisors of 0 and 1 incorrectly\n");
+} // End of large_divisor_functions_test
@@ -0,0 +1,313 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Factorization of Integers into prime powers.
+// Small factors are removed by trial division. Each remaining composite is split in two by the
+// cheapest method likely to work, and both parts are factored in turn until every part is
+// prime:
+//   * Pollard's rho with Brent's cycle detection finds factors up to about 10^10 quickly.
+//   * Pollard's p - 1 finds any factor p where p - 1 has only small prime factors.
+//   * The quadratic sieve (siqs.rs) splits composites of 30 to 70 digits in time that depends
+//     only on the size of the composite: in a release build about 1.5 seconds at 50 digits,
+//     under 20 seconds at 60 and over two minutes at 70.
+//   * Lenstra's elliptic curve method (ecm.rs) finds factors of up to about 30 digits in a
+//     composite of any size, and is the fallback outside the range of the sieve.
+// Every method checks the time limit and cancel flag regularly so a caller can bound the work.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use super::{Integer, FactorError, Montgomery};
+use super::prime::primes_up_to;
+
+mod ecm;
+mod siqs;
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+const TRIAL_BOUND : u64 = 10_000;               // trial division covers primes below this
+const PRIME_ROUNDS : u32 = 8;                   // extra Miller-Rabin rounds for each prime found
+const RHO_ITERATIONS : u64 = 1 << 16;           // rho steps before moving on, for large numbers
+const PM1_BOUND : u64 = 50_000;                 // the smoothness bound of p - 1
+const SIQS_DIGITS : (usize, usize) = (30, 70);  // the sizes the quadratic sieve handles
+const SIQS_ECM_CURVES : u32 = 8;                // quick curves to try for small factors first
+
+/* ============================================================================================ */
+/*     Limits                                                                                   */
+/* ============================================================================================ */
+
+// Bounds on the work a factorization may do. The default has no bounds.
+#[derive(Clone, Default)]
+pub struct FactorLimits {
+    pub timeout : Option<Duration>,         // give up with TimedOut after this long
+    pub cancel : Option<Arc<AtomicBool>>,   // give up with Cancelled once this is set
+}
+
+// The limits of one factorization, with the timeout turned into a deadline.
+struct Deadline {
+    end : Option<Instant>,
+    cancel : Option<Arc<AtomicBool>>,
+}
+
+impl Deadline {
+    fn new(limits : &FactorLimits) -> Deadline {
+        Deadline {
+            end: limits.timeout.map(|timeout| Instant::now() + timeout),
+            cancel: limits.cancel.clone(),
+        }
+    } // End of new function
+
+    // returns an error once the factorization should stop.
+    fn check(&self) -> Result<(), FactorError> {
+        if self.cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+            return Err(FactorError::Cancelled);
+        }
+        match self.end {
+            Some(end) if Instant::now() >= end => Err(FactorError::TimedOut),
+            _ => Ok(()),
+        }
+    } // End of check function
+} // End of Deadline implementation
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns the prime factorization as (prime, exponent) pairs in increasing order.
+    // Negative numbers start with (-1, 1), 1 has no factors and 0 is returned as (0, 1).
+    // This can take a very long time for products of large primes, see factor_with_limits.
+    pub fn factor(&self) -> Vec<(Integer, u32)> {
+        self.factor_with_limits(&FactorLimits::default()).unwrap()
+    } // End of factor function
+
+    // returns the prime factorization like factor, or an error if the limits stop it first.
+    pub fn factor_with_limits(&self, limits : &FactorLimits) -> Result<Vec<(Integer, u32)>, FactorError> {
+        if self.is_zero() {
+            return Ok(vec![(Integer::from(0), 1)]);
+        }
+        let deadline = Deadline::new(limits);
+        let mut primes = Vec::new();
+        let mut n = self.abs();
+
+        for p in primes_up_to(TRIAL_BOUND) {
+            if n.value.len() == 1 && p * p > n.value[0] {
+                break;
+            }
+            let mut exponent = 0;
+            loop {
+                let (quotient, remainder) = n.div_rem(&Integer::from(p));
+                if !remainder.is_zero() {
+                    break;
+                }
+                n = quotient;
+                exponent += 1;
+            }
+            if exponent > 0 {
+                primes.push((Integer::from(p), exponent));
+            }
+        }
+
+        // split composites until only primes are left
+        let mut composites = vec![(n, 1)];
+        while let Some((n, multiplicity)) = composites.pop() {
+            deadline.check()?;
+            if n == Integer::from(1) {
+                continue;
+            }
+            if n < Integer::from(TRIAL_BOUND * TRIAL_BOUND) || n.is_probable_prime(PRIME_ROUNDS) {
+                primes.push((n, multiplicity));
+                continue;
+            }
+            if let Some((base, exponent)) = n.perfect_power() {
+                composites.push((base, multiplicity * exponent));
+                continue;
+            }
+            let factor = split(&n, &deadline)?;
+            composites.push((&n / &factor, multiplicity));
+            composites.push((factor, multiplicity));
+        }
+
+        // the same prime can be found more than once, so merge equal primes
+        primes.sort();
+        let mut factors : Vec<(Integer, u32)> = Vec::new();
+        if self.negative {
+            factors.push((Integer::from(-1), 1));
+        }
+        for (prime, exponent) in primes {
+            match factors.last_mut() {
+                Some(last) if last.0 == prime => last.1 += exponent,
+                _ => factors.push((prime, exponent)),
+            }
+        }
+        Ok(factors)
+    } // End of factor_with_limits function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns a factor strictly between 1 and n of an odd composite n with no small factors
+// that is not a perfect power.
+fn split(n : &Integer, deadline : &Deadline) -> Result<Integer, FactorError> {
+    // below 2^64 rho needs about n^(1/4) <= 2^16 steps, so it is the only method worth running
+    if n.bit_length() <= 64 {
+        let mut c = 1u32;
+        loop {
+            if let Some(factor) = brent_rho(n, c, u64::MAX, deadline)? {
+                return Ok(factor);
+            }
+            c += 1;
+        }
+    }
+    if let Some(factor) = brent_rho(n, 1, RHO_ITERATIONS, deadline)? {
+        return Ok(factor);
+    }
+    if let Some(factor) = pollard_pm1(n, PM1_BOUND, deadline)? {
+        return Ok(factor);
+    }
+
+    let digits = n.to_string().len();
+    if digits >= SIQS_DIGITS.0 && digits <= SIQS_DIGITS.1 {
+        // a few quick curves find small factors far sooner than the sieve
+        if let Some(factor) = ecm::ecm(n, Some(SIQS_ECM_CURVES), deadline)? {
+            return Ok(factor);
+        }
+        if let Some(factor) = siqs::siqs(n, deadline)? {
+            return Ok(factor);
+        }
+    }
+    ecm::ecm(n, None, deadline).map(|factor| factor.unwrap())
+} // End of split function
+
+// Pollard's rho with Brent's cycle detection on x -> x^2 + c mod n. Products of differences
+// are batched so that only one gcd is taken every BATCH steps.
+// Returns None if no factor turned up within the given number of steps.
+fn brent_rho(n : &Integer, c : u32, max_iterations : u64, deadline : &Deadline) -> Result<Option<Integer>, FactorError> {
+    const BATCH : u64 = 128;
+    let one = Integer::from(1);
+    let step = |x : &Integer| (x.square() + c) % n;
+    let (mut x, mut y, mut saved) = (Integer::from(2), Integer::from(2), Integer::from(2));
+    let (mut product, mut gcd) = (Integer::from(1), Integer::from(1));
+    let mut length = 1u64;
+    let mut iterations = 0u64;
+
+    while gcd == one {
+        x = y.clone();
+        for _ in 0..length {
+            y = step(&y);
+        }
+        let mut done = 0;
+        while done < length && gcd == one {
+            deadline.check()?;
+            saved = y.clone();
+            for _ in 0..BATCH.min(length - done) {
+                y = step(&y);
+                product = (product * (&x - &y).abs()) % n;
+            }
+            gcd = product.gcd(n);
+            done += BATCH;
+        }
+        iterations += length;
+        length *= 2;
+        if gcd == one && iterations >= max_iterations {
+            return Ok(None);
+        }
+    }
+
+    // the batch overshot the collision, so step through it one difference at a time
+    if gcd == *n {
+        loop {
+            saved = step(&saved);
+            gcd = (&x - &saved).gcd(n);
+            if gcd != one {
+                break;
+            }
+        }
+    }
+    Ok(if gcd == *n {None} else {Some(gcd)})
+} // End of brent_rho function
+
+// Pollard's p - 1 first stage: a = 2^E mod n where E is the product of every prime power up to
+// bound, so any prime p of n whose p - 1 divides E has a = 1 mod p and divides a - 1.
+fn pollard_pm1(n : &Integer, bound : u64, deadline : &Deadline) -> Result<Option<Integer>, FactorError> {
+    let context = Montgomery::new(n).unwrap();
+    let mut a = Integer::from(2);
+    for (i, p) in primes_up_to(bound).into_iter().enumerate() {
+        let mut power = p;
+        while power <= bound / p {
+            power *= p;
+        }
+        a = context.pow_mod(&a, &Integer::from(power)).unwrap();
+        if i % 1024 == 0 {
+            deadline.check()?;
+        }
+    }
+    let gcd = (a - 1u32).gcd(n);
+    Ok(if gcd == Integer::from(1) || gcd == *n {None} else {Some(gcd)})
+} // End of pollard_pm1 function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// returns the product of a factorization, checking each factor is prime and in order.
+#[cfg(test)]
+fn check_factorization(n : &Integer, factors : &[(Integer, u32)]) {
+    let mut product = Integer::from(1);
+    for (i, (prime, exponent)) in factors.iter().enumerate() {
+        assert!(*prime == Integer::from(-1) || prime.is_probable_prime(4), "\nEvaluated {} as a prime factor of {}\n", prime, n);
+        assert!(i == 0 || factors[i-1].0 < *prime, "\nEvaluated the factors of {} out of order: {:?}\n", n, factors);
+        product *= prime.pow_u32(*exponent);
+    }
+    assert!(product == *n, "\nEvaluated the factors of {} as {:?}\n", n, factors);
+} // End of check_factorization function
+
+// Tests factorizations found by trial division, rho and perfect powers, and the special cases.
+#[test]
+fn factor_small_test() {
+    for n in -200i64..3000 {
+        if n != 0 {
+            check_factorization(&Integer::from(n), &Integer::from(n).factor());
+        }
+    }
+    assert!(Integer::from(0).factor() == vec![(Integer::from(0), 1)], "\nEvaluated the factors of 0 incorrectly\n");
+    assert!(Integer::from(1).factor().is_empty(), "\nEvaluated 1 as having factors\n");
+
+    let p = Integer::from(1_000_000_007u64);
+    let q = Integer::from(4_294_967_291u64);
+    let numbers = [
+        &p * &q,
+        (&p * &q).pow_u32(3) * 12u32,
+        (Integer::from(1) << 64) + 1u32,
+        -(&p.pow_u32(2) * &Integer::from(18446744073709551557u64)),
+    ];
+    for n in numbers.iter() {
+        check_factorization(n, &n.factor());
+    }
+    let cube = (&p * &q).pow_u32(3) * 12u32;
+    assert!(cube.factor().len() == 4 && cube.factor()[3] == (q.clone(), 3), "\nEvaluated the factors of 12 (pq)^3 as {:?}\n", cube.factor());
+} // End of factor_small_test
+
+// Tests that p - 1 finds a factor with a smooth p - 1 and that the limits stop the work.
+#[test]
+fn factor_limits_test() {
+    // p - 1 = 2 3 5^2 7 11 13 17 19 23 29 31 while q - 1 has a 22 digit prime factor
+    let p = Integer::from(1_002_802_450_651u64);
+    let q = "10000000000000000000000013".parse::<Integer>().unwrap();
+    let n = &p * &q;
+    let deadline = Deadline::new(&FactorLimits::default());
+    let factor = pollard_pm1(&n, PM1_BOUND, &deadline).unwrap();
+    assert!(factor == Some(p.clone()), "\nEvaluated p - 1 on {} as finding {:?}\n", n, factor);
+    assert!(n.factor() == vec![(p, 1), (q, 1)], "\nEvaluated the factors of {} as {:?}\n", n, n.factor());
+
+    let cancel = Arc::new(AtomicBool::new(true));
+    let limits = FactorLimits {timeout: None, cancel: Some(cancel)};
+    assert!(n.factor_with_limits(&limits) == Err(FactorError::Cancelled), "\nEvaluated a cancelled factorization as finishing\n");
+    let limits = FactorLimits {timeout: Some(Duration::from_millis(0)), cancel: None};
+    assert!(n.factor_with_limits(&limits) == Err(FactorError::TimedOut), "\nEvaluated a timed out factorization as finishing\n");
+} // End of factor_limits_test
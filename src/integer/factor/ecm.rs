@@ -0,0 +1,208 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Lenstra's elliptic curve method.
+// A random curve mod n is also a curve mod each prime p of n, whose group order is a random
+// number near p. When that order has only small prime factors, multiplying a point by every
+// prime power up to B1 gives the identity mod p, which shows up as a Z coordinate divisible by p.
+// Stage 2 also catches orders with a single larger prime between B1 and B2.
+// Curves are in Montgomery form By^2 = x^3 + Ax^2 + x with Suyama's parametrization, so points
+// only need their X and Z coordinates and no inverses are taken after the curve is set up.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::{Integer, FactorError, Deadline, primes_up_to};
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+// (B1, curves) for each level, sized for factors of about 15, 20, 25, 30 and 35 digits
+const LEVELS : [(u64, u32); 5] = [(2_000, 25), (11_000, 90), (50_000, 300), (250_000, 700), (1_000_000, 1800)];
+const B2_RATIO : u64 = 50;      // B2 = B2_RATIO * B1
+const D : u64 = 210;            // the giant step of stage 2, 2 * 3 * 5 * 7
+
+/* ============================================================================================ */
+/*     Curve                                                                                    */
+/* ============================================================================================ */
+
+// A point in projective XZ coordinates.
+#[derive(Clone)]
+struct Point {
+    x : Integer,
+    z : Integer,
+}
+
+// A Montgomery curve mod n, held as (A + 2) / 4.
+struct Curve<'a> {
+    n : &'a Integer,
+    a24 : Integer,
+}
+
+impl<'a> Curve<'a> {
+    // returns the least non-negative residue of x mod n.
+    fn reduce(&self, x : Integer) -> Integer {
+        reduce(x, self.n)
+    } // End of reduce function
+
+    // returns 2P.
+    fn double(&self, p : &Point) -> Point {
+        let sum = (&p.x + &p.z).square();
+        let difference = (&p.x - &p.z).square();
+        let t = &sum - &difference;
+        Point {
+            x: self.reduce(&sum * &difference),
+            z: self.reduce(&t * &(&difference + &self.reduce(&self.a24 * &t))),
+        }
+    } // End of double function
+
+    // returns P + Q given P - Q.
+    fn add(&self, p : &Point, q : &Point, difference : &Point) -> Point {
+        let u = self.reduce(&(&p.x - &p.z) * &(&q.x + &q.z));
+        let v = self.reduce(&(&p.x + &p.z) * &(&q.x - &q.z));
+        Point {
+            x: self.reduce(&difference.z * &(&u + &v).square()),
+            z: self.reduce(&difference.x * &(&u - &v).square()),
+        }
+    } // End of add function
+
+    // returns kP with the Montgomery ladder, which keeps the pair (mP, (m+1)P) so every
+    // addition has the difference P.
+    fn multiply(&self, p : &Point, k : u64) -> Point {
+        if k == 1 {
+            return p.clone();
+        }
+        let (mut low, mut high) = (p.clone(), self.double(p));
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if (k >> i) & 1 == 1 {
+                low = self.add(&high, &low, p);
+                high = self.double(&high);
+            } else {
+                high = self.add(&low, &high, p);
+                low = self.double(&low);
+            }
+        }
+        low
+    } // End of multiply function
+} // End of Curve implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns a factor of n found with elliptic curves. Given a number of curves only that many
+// are tried at the first level, and None is returned if they all fail. Otherwise the levels
+// are worked through in turn and the last level repeats until a factor is found.
+pub fn ecm(n : &Integer, curves : Option<u32>, deadline : &Deadline) -> Result<Option<Integer>, FactorError> {
+    let mut sigma = 6u64;
+    let mut level = 0;
+    loop {
+        let (b1, count) = LEVELS[level];
+        let count = curves.unwrap_or(count);
+        let primes = primes_up_to(b1 * B2_RATIO);
+        for _ in 0..count {
+            if let Some(factor) = curve(n, sigma, b1, &primes, deadline)? {
+                return Ok(Some(factor));
+            }
+            sigma += 1;
+        }
+        if curves.is_some() {
+            return Ok(None);
+        }
+        level = (level + 1).min(LEVELS.len() - 1);
+    }
+} // End of ecm function
+
+// runs both stages on the curve given by sigma, with primes holding every prime up to B2.
+// Returns the factor found, or None if the curve found nothing or all of n at once.
+fn curve(n : &Integer, sigma : u64, b1 : u64, primes : &[u64], deadline : &Deadline) -> Result<Option<Integer>, FactorError> {
+    let one = Integer::from(1);
+    let found = |g : Integer| if g == one || g == *n {None} else {Some(g)};
+
+    // Suyama: u = sigma^2 - 5, v = 4 sigma, P = (u^3 : v^3) and
+    // (A + 2) / 4 = (v - u)^3 (3u + v) / (16 u^3 v)
+    let u = (Integer::from(sigma).square() - 5u32) % n;
+    let v = Integer::from(4 * sigma) % n;
+    let u3 = u.pow_u32(3) % n;
+    let numerator = reduce(&(&v - &u).pow_u32(3) * &(&(&u * 3u32) + &v), n);
+    let denominator = (&u3 * &v * 16u32) % n;
+    let inverse = match denominator.mod_inverse(n) {
+        Some(inverse) => inverse,
+        None => return Ok(found(denominator.gcd(n))),
+    };
+    let curve = Curve {n, a24: reduce(numerator * inverse, n)};
+    let mut point = Point {x: u3, z: v.pow_u32(3) % n};
+
+    // stage 1: multiply by the largest power of each prime up to B1
+    let stage1 = primes.iter().take_while(|&&p| p <= b1);
+    for (i, &p) in stage1.enumerate() {
+        let mut power = p;
+        while power <= b1 / p {
+            power *= p;
+        }
+        point = curve.multiply(&point, power);
+        if i % 1024 == 0 {
+            deadline.check()?;
+        }
+    }
+    let g = point.z.gcd(n);
+    if g != one {
+        return Ok(found(g));
+    }
+
+    // stage 2: each prime p in (B1, B2] is mD + j or mD - j for an odd j < D / 2, and
+    // pQ = O mod a prime of n makes X(mDQ) Z(jQ) - X(jQ) Z(mDQ) divisible by it
+    let double = curve.double(&point);
+    let mut small = vec![point.clone(); D as usize / 2 + 1];
+    small[3] = curve.add(&double, &point, &point);
+    for j in (5..=D as usize / 2).step_by(2) {
+        small[j] = curve.add(&small[j-2], &double, &small[j-4]);
+    }
+    let giant = curve.multiply(&point, D);
+    let mut m = (b1 + 1 + D / 2) / D;
+    let mut previous = curve.multiply(&point, (m - 1) * D);
+    let mut current = curve.multiply(&point, m * D);
+    let mut product = Integer::from(1);
+    for (i, &p) in primes.iter().filter(|&&p| p > b1).enumerate() {
+        let target = (p + D / 2) / D;
+        while m < target {
+            let next = curve.add(&current, &giant, &previous);
+            previous = std::mem::replace(&mut current, next);
+            m += 1;
+        }
+        let s = &small[p.abs_diff(m * D) as usize];
+        product = curve.reduce(&product * &(&(&current.x * &s.z) - &(&s.x * &current.z)));
+        if i % 1024 == 0 {
+            deadline.check()?;
+        }
+    }
+    Ok(found(product.gcd(n)))
+} // End of curve function
+
+// returns the least non-negative residue of x mod n.
+fn reduce(x : Integer, n : &Integer) -> Integer {
+    let remainder = x % n;
+    if remainder.negative {remainder + n} else {remainder}
+} // End of reduce function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that curves find a 10 digit factor of a 40 digit number and the ladder is consistent.
+#[test]
+fn ecm_test() {
+    let p = Integer::from(4_294_967_311u64);
+    let q = "1000000000000000000000000000057".parse::<Integer>().unwrap().next_prime();
+    let n = &p * &q;
+    let deadline = Deadline::new(&Default::default());
+    let factor = ecm(&n, None, &deadline).unwrap().unwrap();
+    assert!(factor == p || factor == q, "\nEvaluated ecm on {} as finding {}\n", n, factor);
+
+    // 5P computed by the ladder matches 2P + 3P with the difference P
+    let curve = Curve {n: &n, a24: Integer::from(12345)};
+    let point = Point {x: Integer::from(7), z: Integer::from(1)};
+    let five = curve.multiply(&point, 5);
+    let sum = curve.add(&curve.double(&point), &curve.multiply(&point, 3), &point);
+    assert!(curve.reduce(&five.x * &sum.z - &sum.x * &five.z).is_zero(), "\nEvaluated 5P inconsistently\n");
+} // End of ecm_test
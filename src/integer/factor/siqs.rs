@@ -0,0 +1,473 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// The self-initialising quadratic sieve.
+// Relations y^2 = q (mod kN) where q factors over a base of small primes are collected until
+// some subset of them multiplies to a square X^2 = Y^2 (mod N), and gcd(X - Y, N) is then a
+// factor about half the time. The y values come from polynomials (ax + b)^2 - kN = a g(x) with
+// a a product of factor base primes and b^2 = kN (mod a), so g(x) is about M sqrt(kN) over the
+// sieve interval [-M, M). Each a gives 2^(s-1) values of b = +-B_1 +-B_2 .. +-B_s from the s
+// square roots mod its primes. Taken in Gray code order consecutive values of b differ in the
+// sign of one B_l, so the roots of g mod each prime move by one precomputed step per polynomial
+// and only choosing a new a needs modular inverses.
+// Relations with one prime beyond the factor base are kept, and two with the same large prime
+// multiply to a full relation. The subsets are found by Gaussian elimination over GF(2).
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::collections::{HashMap, HashSet};
+use super::{Integer, FactorError, Deadline, primes_up_to};
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+// (digits of kN, factor base size, M) for the sieve, taking the first row large enough
+const PARAMETERS : [(usize, usize, i64); 7] = [
+    (30, 150, 16_384), (35, 250, 32_768), (40, 400, 32_768), (45, 600, 65_536), (50, 1_000, 65_536),
+    (60, 2_200, 65_536), (70, 4_500, 98_304),
+];
+// the multipliers tried by the Knuth-Schroeppel function
+const MULTIPLIERS : [u64; 19] = [1, 2, 3, 5, 6, 7, 10, 11, 13, 14, 15, 17, 19, 21, 22, 23, 26, 29, 30];
+const SMALL_SIEVE : u64 = 30;       // primes below this are not sieved, only trial divided
+const LARGE_PRIME_RATIO : u64 = 64; // large primes up to this times the largest base prime
+const EXTRA_RELATIONS : usize = 24; // relations beyond the factor base size, for dependencies
+const MAX_REPEATS : u32 = 1_000;    // values of a chosen again in a row before giving up
+
+/* ============================================================================================ */
+/*     Factor base                                                                              */
+/* ============================================================================================ */
+
+// A prime of the factor base with a square root of kN modulo it.
+struct Prime {
+    p : u64,
+    root : u64,     // a square root of kN mod p
+    log : u8,       // log2(p) rounded
+    sieve : bool,   // false for small primes and those dividing k, which are only trial divided
+}
+
+// A relation y^2 = (-1)^e0 * product of base primes * root^2 (mod N), with the primes held as
+// their matrix columns, column 0 being the sign and column i + 1 the ith base prime.
+struct Relation {
+    y : Integer,
+    columns : Vec<usize>,
+    root : Integer,
+}
+
+// A partial relation, whose product also has one large prime outside the factor base.
+struct Partial {
+    y : Integer,
+    columns : Vec<usize>,
+}
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns a factor of the odd composite n, which must not be a perfect power, or None if
+// every dependency gave a trivial factor or the values of a ran out.
+pub fn siqs(n : &Integer, deadline : &Deadline) -> Result<Option<Integer>, FactorError> {
+    let k = multiplier(n);
+    let kn = n * k;
+    let digits = kn.to_string().len();
+    let &(_, base_size, m) = PARAMETERS.iter().find(|row| row.0 >= digits).unwrap_or(&PARAMETERS[PARAMETERS.len() - 1]);
+
+    // the factor base, 2 and the primes where kN is a non-zero square
+    let mut base = vec![Prime {p: 2, root: 1, log: 1, sieve: false}];
+    for p in primes_up_to(base_size as u64 * 60).into_iter().skip(1) {
+        if base.len() == base_size {
+            break;
+        }
+        let residue = (&kn % p).to_u64_wrapping();
+        if residue == 0 {
+            if !k.is_multiple_of(p) {
+                return Ok(Some(Integer::from(p)));
+            }
+            base.push(Prime {p, root: 0, log: log2(p), sieve: false});
+        } else if pow_mod(residue, (p - 1) / 2, p) == 1 {
+            base.push(Prime {p, root: sqrt_mod(residue, p), log: log2(p), sieve: p >= SMALL_SIEVE});
+        }
+    }
+    // about half the primes are in the base, so the sieve above held plenty
+    let largest = base[base.len() - 1].p;
+    let large_bound = largest * LARGE_PRIME_RATIO;
+
+    // |g(x)| is at most about M sqrt(kN / 2), and a value is worth trial dividing if the
+    // sieve found all but a large prime and the small primes that are skipped
+    let size = (m as f64).log2() + kn.bit_length() as f64 / 2.0 - 0.5;
+    let threshold = (size - (large_bound as f64).log2() - 4.0).max(0.0) as u8;
+    // the sieve starts from this value so that every value reaching the threshold has its top
+    // bit set
+    let start = 128u8.saturating_sub(threshold);
+    let cutoff = start.saturating_add(threshold);
+
+    let target = (&kn * 2u32).sqrt().unwrap() / m;
+    let mut random = n.value[0] ^ 0x9e37_79b9_7f4a_7c15;
+    let mut used = HashSet::new();
+    let mut repeats = 0;
+    let mut relations = Vec::new();
+    let mut partials : HashMap<u64, Partial> = HashMap::new();
+    let mut sieve = vec![0u8; 2 * m as usize];
+
+    while relations.len() < base.len() + 1 + EXTRA_RELATIONS {
+        deadline.check()?;
+        let factors = choose_a(&base, &target, &mut random);
+        let a = factors.iter().fold(Integer::from(1), |a, &i| a * base[i].p);
+        if !used.insert(a.clone()) {
+            // a small base has few products near the target, give up once they keep repeating
+            repeats += 1;
+            if repeats == MAX_REPEATS {
+                return Ok(None);
+            }
+            continue;
+        }
+        repeats = 0;
+
+        // B_l = (a / q_l) * (root_l / (a / q_l) mod q_l) has B_l^2 = kN mod q_l and is 0 mod
+        // the other primes of a, so every sum of +-B_l is a b with b^2 = kN mod a
+        let b_parts : Vec<Integer> = factors.iter().map(|&i| {
+            let q = base[i].p;
+            let cofactor = &a / q;
+            let inverse = pow_mod((&cofactor % q).to_u64_wrapping(), q - 2, q);
+            let mut gamma = base[i].root * inverse % q;
+            if gamma > q / 2 {
+                gamma = q - gamma;
+            }
+            cofactor * gamma
+        }).collect();
+        let mut b = b_parts.iter().fold(Integer::from(0), |b, part| b + part);
+
+        // g(x) = 0 mod p when ax + b = +-root, and index i stands for x = i - M. Changing b by
+        // 2 B_l moves both roots by 2 B_l / a mod p, which is worked out once for each a.
+        let mut roots = vec![[u64::MAX; 2]; base.len()];
+        let mut steps = vec![vec![0u64; base.len()]; factors.len()];
+        for (j, prime) in base.iter().enumerate() {
+            let p = prime.p;
+            let residue = (&a % p).to_u64_wrapping();
+            if !prime.sieve || residue == 0 {
+                continue;
+            }
+            let a_inverse = pow_mod(residue, p - 2, p);
+            for (l, part) in b_parts.iter().enumerate() {
+                steps[l][j] = 2 * (part % p).to_u64_wrapping() % p * a_inverse % p;
+            }
+            let b_mod = (&b % p).to_u64_wrapping();
+            let offset = m as u64 % p;
+            for (k, root) in [prime.root, p - prime.root].iter().enumerate() {
+                let x = (root + p - b_mod) % p * a_inverse % p;
+                roots[j][k] = (x + offset) % p;
+            }
+        }
+
+        // the first B_l keeps its sign, since b and -b give the same relations, and the others
+        // change sign one at a time in Gray code order
+        let mut negative = vec![false; factors.len()];
+        for polynomial in 0..1u64 << (factors.len() - 1) {
+            deadline.check()?;
+            if polynomial > 0 {
+                let l = polynomial.trailing_zeros() as usize + 1;
+                let rising = negative[l];
+                b = if rising {b + &b_parts[l] * 2u32} else {b - &b_parts[l] * 2u32};
+                negative[l] = !rising;
+                for (j, prime) in base.iter().enumerate() {
+                    if roots[j][0] == u64::MAX {
+                        continue;
+                    }
+                    let (p, step) = (prime.p, steps[l][j]);
+                    for root in roots[j].iter_mut() {
+                        *root = if rising {*root + p - step} else {*root + step};
+                        if *root >= p {
+                            *root -= p;
+                        }
+                    }
+                }
+            }
+            let c = &(&b.square() - &kn) / &a;
+
+            sieve.fill(start);
+            for (j, prime) in base.iter().enumerate() {
+                if roots[j][0] == u64::MAX {
+                    continue;
+                }
+                for &root in roots[j].iter() {
+                    let mut i = root as usize;
+                    while i < sieve.len() {
+                        sieve[i] = sieve[i].saturating_add(prime.log);
+                        i += prime.p as usize;
+                    }
+                }
+            }
+
+            // skip eight values at a time while none has its top bit set
+            let candidates = sieve.chunks_exact(8).enumerate()
+                .filter(|&(_, chunk)| {
+                    let word = [chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7]];
+                    u64::from_ne_bytes(word) & 0x8080_8080_8080_8080 != 0
+                })
+                .flat_map(|(k, _)| 8 * k..8 * k + 8)
+                .filter(|&i| sieve[i] >= cutoff);
+            for i in candidates {
+                let x = i as i64 - m;
+                let g = &(&(&(&a * x) + &(&b * 2u32)) * x) + &c;
+                // a sieved prime divides g(x) exactly when i is on one of its roots
+                let (mut columns, cofactor) = trial_divide(&g, &base, |j| {
+                    roots[j][0] == u64::MAX || roots[j].contains(&(i as u64 % base[j].p))
+                });
+                let y = &(&a * x) + &b;
+                columns.extend(factors.iter().map(|&j| j + 1));
+                if cofactor == 1 {
+                    relations.push(Relation {y, columns, root: Integer::from(1)});
+                } else if cofactor < large_bound {
+                    // two relations with the same large prime L multiply to one with L^2
+                    match partials.get(&cofactor) {
+                        Some(partial) => {
+                            if partial.y != y {
+                                let mut combined = partial.columns.clone();
+                                combined.extend(columns);
+                                relations.push(Relation {y: (&partial.y * &y) % n, columns: combined, root: Integer::from(cofactor)});
+                            }
+                        }
+                        None => {
+                            partials.insert(cofactor, Partial {y, columns});
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    for dependency in dependencies(&relations, base.len() + 1) {
+        deadline.check()?;
+        if let Some(factor) = square_root(n, &base, &relations, &dependency) {
+            return Ok(Some(factor));
+        }
+    }
+    Ok(None)
+} // End of siqs function
+
+// returns the multiplier k that makes kN richest in small quadratic residues, by the
+// Knuth-Schroeppel function: the expected contribution of each small prime to log |g(x)|.
+fn multiplier(n : &Integer) -> u64 {
+    let primes = primes_up_to(1000);
+    let score = |k : u64| -> f64 {
+        let kn = n * k;
+        let mut score = -0.5 * (k as f64).ln();
+        score += match kn.value[0] & 7 {
+            1 => 2.0 * 2f64.ln(),
+            5 => 2f64.ln(),
+            3 | 7 => 0.5 * 2f64.ln(),
+            _ => 0.0,
+        };
+        for &p in primes.iter().skip(1) {
+            let residue = (&kn % p).to_u64_wrapping();
+            if residue == 0 {
+                score += (p as f64).ln() / p as f64;
+            } else if pow_mod(residue, (p - 1) / 2, p) == 1 {
+                score += 2.0 * (p as f64).ln() / (p - 1) as f64;
+            }
+        }
+        score
+    };
+    let scores : Vec<f64> = MULTIPLIERS.iter().map(|&k| score(k)).collect();
+    let best = (0..MULTIPLIERS.len()).max_by(|&i, &j| scores[i].total_cmp(&scores[j])).unwrap();
+    MULTIPLIERS[best]
+} // End of multiplier function
+
+// returns the indices of s base primes whose product is near the target. All but the last
+// are drawn at random from primes of about the right size and the last is chosen to get
+// closest to the target.
+fn choose_a(base : &[Prime], target : &Integer, random : &mut u64) -> Vec<usize> {
+    let log_target = target.bit_length() as f64 * 2f64.ln();
+    let largest = base[base.len() - 1].p as f64;
+    let s = ((log_target / (largest / 2.0).min(2000.0).ln()).ceil() as usize).max(2);
+    let size = (log_target / s as f64).exp();
+    let mut candidates : Vec<usize> = (0..base.len())
+        .filter(|&i| base[i].sieve && base[i].p as f64 >= size / 2.0 && base[i].p as f64 <= size * 2.0)
+        .collect();
+    if candidates.len() < s + 2 {
+        candidates = (0..base.len()).filter(|&i| base[i].sieve).collect();
+    }
+
+    let mut factors = Vec::new();
+    let mut log_product = 0.0;
+    while factors.len() < s - 1 {
+        // xorshift
+        *random ^= *random << 13;
+        *random ^= *random >> 7;
+        *random ^= *random << 17;
+        let i = candidates[(*random % candidates.len() as u64) as usize];
+        if !factors.contains(&i) {
+            factors.push(i);
+            log_product += (base[i].p as f64).ln();
+        }
+    }
+    let remaining = log_target - log_product;
+    let last = (0..base.len())
+        .filter(|&i| base[i].sieve && !factors.contains(&i))
+        .min_by(|&i, &j| {
+            let error = |i : usize| ((base[i].p as f64).ln() - remaining).abs();
+            error(i).total_cmp(&error(j))
+        }).unwrap();
+    factors.push(last);
+    factors
+} // End of choose_a function
+
+// returns the columns of the base primes dividing g, with multiplicity and the sign as column
+// 0, and the part of |g| left over. Only the primes for which may_divide holds are tried.
+fn trial_divide<F : Fn(usize) -> bool>(g : &Integer, base : &[Prime], may_divide : F) -> (Vec<usize>, u64) {
+    let mut columns = Vec::new();
+    if g.negative {
+        columns.push(0);
+    }
+    let mut g = g.abs();
+    for (j, prime) in base.iter().enumerate().filter(|&(j, _)| may_divide(j)) {
+        loop {
+            let (quotient, remainder) = g.div_rem(&Integer::from(prime.p));
+            if !remainder.is_zero() {
+                break;
+            }
+            g = quotient;
+            columns.push(j + 1);
+        }
+    }
+    let cofactor = if g.value.len() == 1 {g.value[0]} else {u64::MAX};
+    (columns, cofactor)
+} // End of trial_divide function
+
+// returns subsets of the relations whose products have every column to an even power, found
+// by Gaussian elimination on the exponent vectors mod 2 while tracking which relations were
+// added into each row.
+fn dependencies(relations : &[Relation], width : usize) -> Vec<Vec<usize>> {
+    let words = width.div_ceil(64);
+    let history_words = relations.len().div_ceil(64);
+    let mut rows : Vec<(Vec<u64>, Vec<u64>)> = relations.iter().enumerate().map(|(i, relation)| {
+        let mut row = vec![0u64; words];
+        for &column in relation.columns.iter() {
+            row[column / 64] ^= 1 << (column % 64);
+        }
+        let mut history = vec![0u64; history_words];
+        history[i / 64] = 1 << (i % 64);
+        (row, history)
+    }).collect();
+
+    let mut pivots = 0;
+    for column in 0..width {
+        let bit = |row : &[u64]| (row[column / 64] >> (column % 64)) & 1 == 1;
+        let pivot = match (pivots..rows.len()).find(|&i| bit(&rows[i].0)) {
+            Some(pivot) => pivot,
+            None => continue,
+        };
+        rows.swap(pivots, pivot);
+        let (done, rest) = rows.split_at_mut(pivots + 1);
+        let pivot_row = &done[pivots];
+        for row in rest.iter_mut().filter(|row| bit(&row.0)) {
+            for (word, pivot_word) in row.0.iter_mut().zip(pivot_row.0.iter()) {
+                *word ^= pivot_word;
+            }
+            for (word, pivot_word) in row.1.iter_mut().zip(pivot_row.1.iter()) {
+                *word ^= pivot_word;
+            }
+        }
+        pivots += 1;
+    }
+
+    // the rows below the pivots are all zero, so their histories are dependencies
+    rows[pivots..].iter().map(|(_, history)| {
+        (0..relations.len()).filter(|&i| (history[i / 64] >> (i % 64)) & 1 == 1).collect()
+    }).collect()
+} // End of dependencies function
+
+// returns gcd(X - Y, n) for the square X^2 = Y^2 (mod n) given by a dependency, or None if
+// the factor is trivial.
+fn square_root(n : &Integer, base : &[Prime], relations : &[Relation], dependency : &[usize]) -> Option<Integer> {
+    let mut exponents = vec![0u32; base.len() + 1];
+    let mut x = Integer::from(1);
+    let mut y = Integer::from(1);
+    for &i in dependency.iter() {
+        x = (&x * &relations[i].y) % n;
+        y = (&y * &relations[i].root) % n;
+        for &column in relations[i].columns.iter() {
+            exponents[column] += 1;
+        }
+    }
+    for (j, prime) in base.iter().enumerate() {
+        let exponent = exponents[j + 1];
+        if exponent > 0 {
+            y = (&y * &Integer::from(prime.p).mod_pow(&Integer::from(exponent / 2), n).unwrap()) % n;
+        }
+    }
+    let factor = (&x - &y).gcd(n);
+    if factor == Integer::from(1) || factor == *n {None} else {Some(factor)}
+} // End of square_root function
+
+// returns base ^ exponent mod p for p below 2^32.
+fn pow_mod(mut base : u64, mut exponent : u64, p : u64) -> u64 {
+    let mut result = 1 % p;
+    base %= p;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % p;
+        }
+        base = base * base % p;
+        exponent >>= 1;
+    }
+    result
+} // End of pow_mod function
+
+// returns a square root of the quadratic residue a mod the odd prime p below 2^32 by the
+// Tonelli-Shanks algorithm.
+fn sqrt_mod(a : u64, p : u64) -> u64 {
+    // p - 1 = q 2^s with q odd
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p).find(|&z| pow_mod(z, (p - 1) / 2, p) == p - 1).unwrap();
+    let (mut m, mut c, mut t, mut r) = (s, pow_mod(z, q, p), pow_mod(a, q, p), pow_mod(a, q.div_ceil(2), p));
+    while t != 1 {
+        // the least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut t2 = t;
+        while t2 != 1 {
+            t2 = t2 * t2 % p;
+            i += 1;
+        }
+        let b = pow_mod(c, 1 << (m - i - 1), p);
+        m = i;
+        c = b * b % p;
+        t = t * c % p;
+        r = r * b % p;
+    }
+    r
+} // End of sqrt_mod function
+
+// returns log2(p) rounded to the nearest integer.
+fn log2(p : u64) -> u8 {
+    (p as f64).log2().round() as u8
+} // End of log2 function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the modular square roots and that the sieve splits a 31 and a 42 digit semiprime.
+#[test]
+fn siqs_test() {
+    for &p in [3u64, 5, 13, 17, 97, 65537, 4_294_967_291].iter() {
+        for a in 1..200 {
+            if pow_mod(a, (p - 1) / 2, p) == 1 {
+                let root = sqrt_mod(a, p);
+                assert!(root * root % p == a % p, "\nEvaluated the square root of {} mod {} as {}\n", a, p, root);
+            }
+        }
+    }
+
+    let deadline = Deadline::new(&Default::default());
+    let semiprimes = [
+        (Integer::from(1_000_000_000_000_037u64), Integer::from(10_000_000_000_000_061u64)),
+        (Integer::from(100_000_000_000_000_000_039u128), Integer::from(1_000_000_000_000_000_000_117u128)),
+    ];
+    for (p, q) in semiprimes.iter() {
+        let n = p * q;
+        let factor = siqs(&n, &deadline).unwrap().unwrap();
+        assert!(factor == *p || factor == *q, "\nEvaluated the sieve on {} as finding {}\n", n, factor);
+    }
+} // End of siqs_test
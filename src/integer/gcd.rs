@@ -0,0 +1,245 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Greatest common divisors, Bezout coefficients, least common multiples and modular inverses.
+// Values that fit in 128 bits use Stein's binary GCD, which only needs shifts and subtraction.
+// Larger values use Lehmer's algorithm: the Euclidean quotients are predicted from the leading
+// 64 bits of both numbers, and a run of them is applied to the full numbers at once as a 2x2
+// matrix of small cofactors. This replaces most multi-limb divisions with a few multiplications
+// by single limb numbers.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::cmp::Ordering;
+use super::{Integer, cmp_limbs, significant};
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns the greatest common divisor of |self| and |other|, gcd(0, 0) is 0.
+    pub fn gcd(&self, other : &Integer) -> Integer {
+        if let (Some(a), Some(b)) = (to_u128(&self.value), to_u128(&other.value)) {
+            return Integer::from(binary_gcd(a, b));
+        }
+        lehmer(self.abs(), other.abs(), false).0
+    } // End of gcd function
+
+    // returns the least common multiple of |self| and |other|, which is 0 if either is 0.
+    pub fn lcm(&self, other : &Integer) -> Integer {
+        if self.is_zero() || other.is_zero() {
+            return Integer::from(0);
+        }
+        let gcd = self.gcd(other);
+        (&(&self.abs() / &gcd) * other).abs()
+    } // End of lcm function
+
+    // returns (g, x, y) where g = gcd(self, other) and self*x + other*y = g.
+    pub fn extended_gcd(&self, other : &Integer) -> (Integer, Integer, Integer) {
+        if other.is_zero() {
+            let x = Integer::from(if self.negative {-1} else if self.is_zero() {0} else {1});
+            return (self.abs(), x, Integer::from(0));
+        }
+
+        // the cofactor of |self| comes from Lehmer, the other follows from the Bezout identity
+        let (gcd, cofactor) = lehmer(self.abs(), other.abs(), true);
+        let mut x = cofactor.unwrap();
+        if self.negative {
+            x = -&x;
+        }
+        let y = &(&gcd - &(self * &x)) / other;
+        (gcd, x, y)
+    } // End of extended_gcd function
+
+    // returns the inverse of self modulo |modulus| in the range [0, |modulus|), or None if
+    // the two are not coprime or the modulus is zero.
+    pub fn mod_inverse(&self, modulus : &Integer) -> Option<Integer> {
+        if modulus.is_zero() {
+            return None;
+        }
+        let modulus = modulus.abs();
+        let (gcd, x, _) = self.extended_gcd(&modulus);
+        if significant(&gcd.value) != [1] {
+            return None;
+        }
+        let inverse = &x % &modulus;
+        Some(if inverse.negative {&inverse + &modulus} else {inverse})
+    } // End of mod_inverse function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// Stein's binary GCD, removing common factors of two and subtracting the smaller odd value.
+fn binary_gcd(mut u : u128, mut v : u128) -> u128 {
+    if u == 0 || v == 0 {
+        return u | v;
+    }
+    let shift = (u | v).trailing_zeros();
+    u >>= u.trailing_zeros();
+    loop {
+        v >>= v.trailing_zeros();
+        if u > v {
+            std::mem::swap(&mut u, &mut v);
+        }
+        v -= u;
+        if v == 0 {
+            return u << shift;
+        }
+    }
+} // End of binary_gcd function
+
+// Lehmer's GCD of two non-negative numbers. When cofactor is true also returns s such that
+// s*a = gcd (mod b), by applying every quotient step to the pair (s_a, s_b) as well.
+fn lehmer(mut a : Integer, mut b : Integer, cofactor : bool) -> (Integer, Option<Integer>) {
+    let (mut s_a, mut s_b) = (Integer::from(1), Integer::from(0));
+    if cmp_limbs(&a.value, &b.value) == Ordering::Less {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut s_a, &mut s_b);
+    }
+
+    while significant(&b.value).len() > 1 {
+        match lehmer_matrix(&a, &b) {
+            Some((m00, m01, m10, m11)) => {
+                let next_a = &a * m00 + &b * m01;
+                let next_b = &a * m10 + &b * m11;
+                a = next_a;
+                b = next_b;
+                if cofactor {
+                    let next_s_a = &s_a * m00 + &s_b * m01;
+                    let next_s_b = &s_a * m10 + &s_b * m11;
+                    s_a = next_s_a;
+                    s_b = next_s_b;
+                }
+            },
+            None => {
+                // the leading bits could not predict a quotient, so take one full Euclidean step
+                let (quotient, remainder) = a.div_rem(&b);
+                a = b;
+                b = remainder;
+                if cofactor {
+                    let next_s_b = &s_a - &(&quotient * &s_b);
+                    s_a = s_b;
+                    s_b = next_s_b;
+                }
+            },
+        }
+    }
+
+    // finish the single limb remainder with plain Euclidean steps
+    while !b.is_zero() {
+        if !cofactor {
+            let remainder = &a % &b;
+            return (Integer::from(binary_gcd(to_u128(&b.value).unwrap(), to_u128(&remainder.value).unwrap())), None);
+        }
+        let (quotient, remainder) = a.div_rem(&b);
+        a = b;
+        b = remainder;
+        let next_s_b = &s_a - &(&quotient * &s_b);
+        s_a = s_b;
+        s_b = next_s_b;
+    }
+    (a, if cofactor {Some(s_a)} else {None})
+} // End of lehmer function
+
+// Runs Euclid's algorithm on the leading 64 bits of a >= b for as long as the quotients are
+// guaranteed to match those of the full numbers, returning the accumulated cofactor matrix
+// (a, b) -> (m00*a + m01*b, m10*a + m11*b), or None if not even one quotient was certain.
+fn lehmer_matrix(a : &Integer, b : &Integer) -> Option<(i128, i128, i128, i128)> {
+    let shift = a.bit_length().saturating_sub(64);
+    let mut x = to_u128(&(a >> shift).value).unwrap() as i128;
+    let mut y = to_u128(&(b >> shift).value).unwrap() as i128;
+    let (mut m00, mut m01, mut m10, mut m11) = (1i128, 0i128, 0i128, 1i128);
+
+    // Knuth's Algorithm L: the quotient is certain when both bounds on it agree
+    while y + m10 > 0 && y + m11 > 0 {
+        let quotient = (x + m00) / (y + m10);
+        if quotient != (x + m01) / (y + m11) {
+            break;
+        }
+        let next = (m10, m00 - quotient * m10);
+        m00 = next.0;
+        m10 = next.1;
+        let next = (m11, m01 - quotient * m11);
+        m01 = next.0;
+        m11 = next.1;
+        let next = (y, x - quotient * y);
+        x = next.0;
+        y = next.1;
+    }
+    if m01 == 0 {
+        None
+    } else {
+        Some((m00, m01, m10, m11))
+    }
+} // End of lehmer_matrix function
+
+/* ============================================================================================ */
+/*     Helpers                                                                                  */
+/* ============================================================================================ */
+
+// returns the magnitude as a u128 if it fits in two limbs.
+fn to_u128(value : &[u64]) -> Option<u128> {
+    match significant(value) {
+        [low] => Some(*low as u128),
+        [low, high] => Some(((*high as u128) << 64) | *low as u128),
+        _ => None,
+    }
+} // End of to_u128 function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that the binary GCD matches Euclid's algorithm on small values.
+#[test]
+fn binary_gcd_test() {
+    for a in 0..60u128 {
+        for b in 0..60u128 {
+            let (mut x, mut y) = (a, b);
+            while y != 0 {
+                let t = x % y;
+                x = y;
+                y = t;
+            }
+            assert!(binary_gcd(a, b) == x, "\nEvaluated binary_gcd({}, {}) as {}\n", a, b, binary_gcd(a, b));
+        }
+    }
+} // End of binary_gcd_test
+
+// Tests that Lehmer's algorithm finds a known common factor of multi-limb values and that the
+// extended form returns Bezout coefficients.
+#[test]
+fn lehmer_gcd_test() {
+    let common = "1000000000000000000000000000057".parse::<Integer>().unwrap();
+    let a = &Integer::from(3).pow_u32(300) * &common;
+    let b = &Integer::from(7).pow_u32(250) * &common;
+    assert!(a.gcd(&b) == common, "\nEvaluated gcd(3^300 c, 7^250 c) as {}\n", a.gcd(&b));
+    assert!((-&a).gcd(&b) == common, "\nEvaluated gcd(-3^300 c, 7^250 c) as {}\n", (-&a).gcd(&b));
+
+    for (x, y) in [(&a, &b), (&b, &a), (&a, &(-&b)), (&(-&a), &common)].iter() {
+        let (gcd, s, t) = x.extended_gcd(y);
+        assert!(gcd == x.gcd(y), "\nEvaluated extended gcd of {} and {} as {}\n", x, y, gcd);
+        assert!(&(*x * &s) + &(*y * &t) == gcd, "\nEvaluated Bezout coefficients of {} and {} as {} and {}\n", x, y, s, t);
+    }
+} // End of lehmer_gcd_test
+
+// Tests lcm and mod_inverse, including the zero and non-coprime cases.
+#[test]
+fn lcm_mod_inverse_test() {
+    let lcm = Integer::from(-12).lcm(&Integer::from(18));
+    assert!(lcm == Integer::from(36), "\nEvaluated lcm(-12, 18) as {}\n", lcm);
+    assert!(Integer::from(0).lcm(&Integer::from(5)).is_zero(), "\nEvaluated lcm(0, 5) as nonzero\n");
+
+    let modulus = "170141183460469231731687303715884105727".parse::<Integer>().unwrap();
+    for value in [Integer::from(3), Integer::from(-10), &(&modulus * &modulus) + &Integer::from(2)].iter() {
+        let inverse = value.mod_inverse(&modulus).unwrap();
+        let product = &(value * &inverse) % &modulus;
+        assert!(product == Integer::from(1) || product == &Integer::from(1) - &modulus, "\nEvaluated the inverse of {} as {}\n", value, inverse);
+        assert!(!inverse.negative && inverse < modulus, "\nEvaluated the inverse of {} out of range as {}\n", value, inverse);
+    }
+    assert!(Integer::from(6).mod_inverse(&Integer::from(9)).is_none(), "\nEvaluated an inverse of 6 mod 9\n");
+    assert!(Integer::from(6).mod_inverse(&Integer::from(0)).is_none(), "\nEvaluated an inverse of 6 mod 0\n");
+} // End of lcm_mod_inverse_test
@@ -0,0 +1,122 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Fibonacci and Lucas numbers and the general Lucas sequences U_n(P, Q) and V_n(P, Q), defined by
+// U_0 = 0, U_1 = 1, V_0 = 2, V_1 = P and X_(n+1) = P X_n - Q X_(n-1). Fibonacci numbers are
+// U_n(1, -1) and Lucas numbers V_n(1, -1).
+// The nth terms come from doubling the pair (U_k, U_(k+1)) once per bit of n:
+//   U_2k = U_k (2 U_(k+1) - P U_k),    U_(2k+1) = U_(k+1)^2 - Q U_k^2
+// and adding one with U_(k+2) = P U_(k+1) - Q U_k when the bit is set. Most of the work is
+// squaring, and no step divides, so the same code works modulo any modulus.
+// V_n then follows from V_n = 2 U_(n+1) - P U_n.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::{Integer, ArithmeticError};
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns the nth Fibonacci number, F_0 = 0, F_1 = 1.
+    pub fn fibonacci(n : u64) -> Integer {
+        lucas_u(&Integer::from(n), &Integer::from(1), &Integer::from(-1), None).0
+    } // End of fibonacci function
+
+    // returns the nth Lucas number, L_0 = 2, L_1 = 1.
+    pub fn lucas(n : u64) -> Integer {
+        let (u, next) = lucas_u(&Integer::from(n), &Integer::from(1), &Integer::from(-1), None);
+        &(next << 1) - &u
+    } // End of lucas function
+
+    // returns (U_n(P, Q), V_n(P, Q)), reduced into [0, |modulus|) when a modulus is given.
+    // Returns an error for a negative n or a zero modulus.
+    pub fn lucas_sequence(n : &Integer, p : &Integer, q : &Integer, modulus : Option<&Integer>) -> Result<(Integer, Integer), ArithmeticError> {
+        if n.negative {
+            return Err(ArithmeticError::NegativeExponent);
+        }
+        let modulus = match modulus {
+            Some(modulus) if modulus.is_zero() => return Err(ArithmeticError::DivisionByZero),
+            Some(modulus) => Some(modulus.abs()),
+            None => None,
+        };
+        let reduce = |x : Integer| reduce(x, modulus.as_ref());
+        let (u, next) = lucas_u(n, p, q, modulus.as_ref());
+        let v = reduce(&(next << 1) - &(p * &u));
+        Ok((u, v))
+    } // End of lucas_sequence function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns x, or its least non-negative residue when there is a modulus.
+fn reduce(x : Integer, modulus : Option<&Integer>) -> Integer {
+    match modulus {
+        Some(modulus) => {
+            let remainder = x % modulus;
+            if remainder.negative {remainder + modulus} else {remainder}
+        }
+        None => x,
+    }
+} // End of reduce function
+
+// returns (U_n, U_(n+1)) for a non-negative n, reduced by the modulus if there is one.
+fn lucas_u(n : &Integer, p : &Integer, q : &Integer, modulus : Option<&Integer>) -> (Integer, Integer) {
+    let (mut u, mut next) = (Integer::from(0), reduce(Integer::from(1), modulus));
+    for i in (0..n.bit_length()).rev() {
+        let double = reduce(&u * &(&(&next << 1) - &(p * &u)), modulus);
+        let double_next = reduce(&next.square() - &(q * &u.square()), modulus);
+        if n.bit(i) {
+            next = reduce(&(p * &double_next) - &(q * &double), modulus);
+            u = double_next;
+        } else {
+            u = double;
+            next = double_next;
+        }
+    }
+    (u, next)
+} // End of lucas_u function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests Fibonacci and Lucas numbers and general sequences against their recurrences.
+#[test]
+fn lucas_sequences_test() {
+    let (mut f, mut l) = ((Integer::from(0), Integer::from(1)), (Integer::from(2), Integer::from(1)));
+    for n in 0..300u64 {
+        assert!(Integer::fibonacci(n) == f.0, "\nEvaluated F_{} as {}\n", n, Integer::fibonacci(n));
+        assert!(Integer::lucas(n) == l.0, "\nEvaluated L_{} as {}\n", n, Integer::lucas(n));
+        f = (f.1.clone(), &f.0 + &f.1);
+        l = (l.1.clone(), &l.0 + &l.1);
+    }
+    let f1000 = Integer::fibonacci(1000).to_string();
+    assert!(f1000.len() == 209 && f1000.starts_with("434665576869374564356885276750"), "\nEvaluated F_1000 as {}\n", f1000);
+
+    for &(p, q) in [(3i64, 2i64), (-2, 5), (4, -3), (0, 1), (2, 1)].iter() {
+        let (p_integer, q_integer) = (Integer::from(p), Integer::from(q));
+        let (mut u, mut v) = ((Integer::from(0), Integer::from(1)), (Integer::from(2), Integer::from(p)));
+        for n in 0..60 {
+            let index = Integer::from(n);
+            let exact = Integer::lucas_sequence(&index, &p_integer, &q_integer, None).unwrap();
+            assert!(exact == (u.0.clone(), v.0.clone()), "\nEvaluated U_{0}, V_{0} of ({1}, {2}) as {3:?}\n", n, p, q, exact);
+            let modulus = Integer::from(1000);
+            let reduced = Integer::lucas_sequence(&index, &p_integer, &q_integer, Some(&modulus)).unwrap();
+            let expected = (reduce(u.0.clone(), Some(&modulus)), reduce(v.0.clone(), Some(&modulus)));
+            assert!(reduced == expected, "\nEvaluated U_{0}, V_{0} of ({1}, {2}) mod 1000 as {3:?}\n", n, p, q, reduced);
+            u = (u.1.clone(), &(&u.1 * p) - &(&u.0 * q));
+            v = (v.1.clone(), &(&v.1 * p) - &(&v.0 * q));
+        }
+    }
+
+    // F_(p+1) = 0 mod p for the prime p = 2^127 - 1, which is 2 mod 5
+    let p = (Integer::from(1) << 127) - 1u32;
+    let (u, v) = Integer::lucas_sequence(&(&p + 1u32), &Integer::from(1), &Integer::from(-1), Some(&p)).unwrap();
+    assert!(u.is_zero() && v == &p - 2u32, "\nEvaluated F_(p+1), L_(p+1) mod 2^127 - 1 as {}, {}\n", u, v);
+    assert!(Integer::lucas_sequence(&Integer::from(-1), &p, &p, None) == Err(ArithmeticError::NegativeExponent), "\nEvaluated a negative index as not an error\n");
+    assert!(Integer::lucas_sequence(&p, &p, &p, Some(&Integer::from(0))) == Err(ArithmeticError::DivisionByZero), "\nEvaluated a zero modulus as not an error\n");
+} // End of lucas_sequences_test
@@ -0,0 +1,74 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Macros that fill in the owned and mixed operand forms of the Integer operators.
+// Each operator is written once for two references, &a op &b, and once in place, a op= &b.
+// These forward the other combinations to them, so an owned left operand has the result
+// written into its limbs and only two references compute into fresh limbs.
+
+/* ============================================================================================ */
+/*     Macros                                                                                   */
+/* ============================================================================================ */
+
+// Implements Integer op Integer, Integer op &Integer and &Integer op Integer for an operator
+// already implemented for two references and in place. The owned forms assign into the owned
+// operand, which for a commutative operator may be the right one.
+macro_rules! forward_binop {
+    ($imp:ident, $method:ident, $assign_imp:ident, $assign_method:ident) => {
+        forward_binop!(@owned $imp, $method, $assign_imp, $assign_method);
+
+        impl ::std::ops::$imp<Integer> for &Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn $method(self, num : Integer) -> Integer {
+                ::std::ops::$imp::$method(self, &num)
+            }
+        }
+    };
+    ($imp:ident, $method:ident, $assign_imp:ident, $assign_method:ident, commutative) => {
+        forward_binop!(@owned $imp, $method, $assign_imp, $assign_method);
+
+        impl ::std::ops::$imp<Integer> for &Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn $method(self, mut num : Integer) -> Integer {
+                ::std::ops::$assign_imp::$assign_method(&mut num, self);
+                num
+            }
+        }
+    };
+    (@owned $imp:ident, $method:ident, $assign_imp:ident, $assign_method:ident) => {
+        impl ::std::ops::$imp<Integer> for Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn $method(self, num : Integer) -> Integer {
+                ::std::ops::$imp::$method(self, &num)
+            }
+        }
+
+        impl<'b> ::std::ops::$imp<&'b Integer> for Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn $method(mut self, num : &'b Integer) -> Integer {
+                ::std::ops::$assign_imp::$assign_method(&mut self, num);
+                self
+            }
+        }
+    };
+}
+
+// Implements a op= b for an assignment operator already implemented for a op= &b.
+macro_rules! forward_assign {
+    ($imp:ident, $method:ident) => {
+        impl ::std::ops::$imp<Integer> for Integer {
+            #[inline]
+            fn $method(&mut self, num : Integer) {
+                ::std::ops::$imp::$method(self, &num)
+            }
+        }
+    };
+}
@@ -0,0 +1,276 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Modular multiplication and exponentiation over the u64 limb vectors used by Integer.
+// An odd modulus uses Montgomery form, where x is stored as x*R mod m with R = 2^(64n) for an
+// n limb modulus, so every product is reduced with multiplications and shifts instead of a long
+// division. Montgomery form needs R to be invertible mod m, so even moduli fall back to Barrett
+// reduction, which replaces the division with a multiplication by a precomputed 2^(128n) / m.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::cmp::Ordering;
+use super::{Integer, ArithmeticError, cmp_limbs, significant, sliding_window_pow, sub_limbs, trim, div, mul};
+
+/* ============================================================================================ */
+/*     Reducer                                                                                  */
+/* ============================================================================================ */
+
+// The operations exponentiation needs from a modular representation.
+trait Reducer {
+    // moves a residue below the modulus into the representation.
+    fn encode(&self, value: &[u64]) -> Vec<u64>;
+    // moves a value in the representation back to a plain residue.
+    fn decode(&self, value: &[u64]) -> Vec<u64>;
+    // multiplies two values in the representation.
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64>;
+}
+
+// raises a residue below the modulus to the power held in the exponent limbs.
+fn pow<R: Reducer>(reducer: &R, base: &[u64], exponent: &[u64], modulus: &[u64]) -> Vec<u64> {
+    let one = if modulus == [1] {vec![0]} else {vec![1]};
+    let result = sliding_window_pow(
+        reducer.encode(base),
+        exponent,
+        reducer.encode(&one),
+        |x| reducer.mul(x, x),
+        |x, y| reducer.mul(x, y),
+    );
+    reducer.decode(&result)
+} // End of pow function
+
+// returns the least non-negative residue of the Integer modulo the magnitude m.
+fn residue(value: &Integer, modulus: &[u64]) -> Vec<u64> {
+    let (_, remainder) = div::div_rem(&value.value, modulus);
+    if value.negative && significant(&remainder) != [0] {
+        sub_limbs(modulus, &remainder)
+    } else {
+        remainder
+    }
+} // End of residue function
+
+/* ============================================================================================ */
+/*     Montgomery                                                                               */
+/* ============================================================================================ */
+
+// A reusable context for arithmetic modulo a fixed odd modulus in Montgomery form.
+// Building the context costs a division, after which every multiplication and exponentiation
+// with the same modulus avoids long division entirely.
+pub struct Montgomery {
+    modulus : Vec<u64>,     // the odd modulus m, n limbs
+    m_inv : u64,            // -m^-1 mod 2^64
+    r2 : Vec<u64>,          // R^2 mod m, used to move values into Montgomery form
+}
+
+impl Montgomery {
+    // creates a context for the modulus |m|, or None if the modulus is even or zero.
+    pub fn new(modulus : &Integer) -> Option<Montgomery> {
+        let modulus = significant(&modulus.value).to_vec();
+        if modulus[0] & 1 == 0 {
+            return None;
+        }
+
+        // Newton's iteration doubles the correct low bits of the inverse each step
+        let mut inv : u64 = modulus[0];
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+        }
+        let mut r_squared = vec![0u64; 2 * modulus.len() + 1];
+        r_squared[2 * modulus.len()] = 1;
+        let (_, r2) = div::div_rem(&r_squared, &modulus);
+        Some(Montgomery {
+            m_inv: inv.wrapping_neg(),
+            r2,
+            modulus,
+        })
+    } // End of new function
+
+    // returns the modulus of the context.
+    pub fn modulus(&self) -> Integer {
+        Integer::from_parts(self.modulus.clone(), false)
+    } // End of modulus function
+
+    // returns a * b mod m as the least non-negative residue.
+    pub fn mul_mod(&self, a : &Integer, b : &Integer) -> Integer {
+        let a = self.encode(&residue(a, &self.modulus));
+        let b = self.encode(&residue(b, &self.modulus));
+        Integer::from_parts(self.decode(&self.mul(&a, &b)), false)
+    } // End of mul_mod function
+
+    // returns base ^ exponent mod m as the least non-negative residue.
+    // Negative exponents are rejected since they need a modular inverse.
+    pub fn pow_mod(&self, base : &Integer, exponent : &Integer) -> Result<Integer, ArithmeticError> {
+        if exponent.negative {
+            return Err(ArithmeticError::NegativeExponent);
+        }
+        let base = residue(base, &self.modulus);
+        Ok(Integer::from_parts(pow(self, &base, &exponent.value, &self.modulus), false))
+    } // End of pow_mod function
+
+    // Montgomery reduction of a * b, returning a * b / R mod m padded to n limbs.
+    // Coarsely integrated operand scanning: each limb of a is multiplied in and one limb of
+    // the running total is cleared by adding a multiple of m, then shifted out.
+    fn redc_mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = self.modulus.len();
+        let m = &self.modulus;
+        let mut t = vec![0u64; n + 2];
+        for i in 0..n {
+            let a_i = if i < a.len() {a[i] as u128} else {0};
+            let mut carry : u128 = 0;
+            for j in 0..n {
+                let b_j = if j < b.len() {b[j] as u128} else {0};
+                let sum = t[j] as u128 + a_i * b_j + carry;
+                t[j] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n] = sum as u64;
+            t[n+1] = (sum >> 64) as u64;
+
+            // add q * m so the lowest limb becomes zero, then drop it
+            let q = t[0].wrapping_mul(self.m_inv) as u128;
+            let mut carry = (t[0] as u128 + q * m[0] as u128) >> 64;
+            for j in 1..n {
+                let sum = t[j] as u128 + q * m[j] as u128 + carry;
+                t[j-1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n-1] = sum as u64;
+            t[n] = t[n+1] + (sum >> 64) as u64;
+            t[n+1] = 0;
+        }
+        t.truncate(n + 1);
+        if cmp_limbs(&t, m) != Ordering::Less {
+            t = sub_limbs(&t, m);
+        }
+        t.resize(n, 0);
+        t
+    } // End of redc_mul function
+} // End of Montgomery implementation
+
+impl Reducer for Montgomery {
+    fn encode(&self, value: &[u64]) -> Vec<u64> {
+        self.redc_mul(value, &self.r2)
+    } // End of encode function
+
+    fn decode(&self, value: &[u64]) -> Vec<u64> {
+        let mut plain = self.redc_mul(value, &[1]);
+        trim(&mut plain);
+        plain
+    } // End of decode function
+
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        self.redc_mul(a, b)
+    } // End of mul function
+} // End of Reducer implementation
+
+/* ============================================================================================ */
+/*     Barrett                                                                                  */
+/* ============================================================================================ */
+
+// Arithmetic modulo any modulus, reducing products with Barrett's method.
+// With k the limb count of m and mu = floor(2^(128k) / m), the quotient of x < m^2 by m is
+// estimated as ((x >> 64(k-1)) * mu) >> 64(k+1), which is at most two below the true quotient.
+struct Barrett {
+    modulus : Vec<u64>,     // the modulus m, k limbs
+    mu : Vec<u64>,          // floor(2^(128k) / m)
+}
+
+impl Barrett {
+    // creates a context for the modulus |m|, or None if the modulus is zero.
+    fn new(modulus : &Integer) -> Option<Barrett> {
+        let modulus = significant(&modulus.value).to_vec();
+        if modulus == [0] {
+            return None;
+        }
+        let mut power = vec![0u64; 2 * modulus.len() + 1];
+        power[2 * modulus.len()] = 1;
+        let (mu, _) = div::div_rem(&power, &modulus);
+        Some(Barrett { modulus, mu })
+    } // End of new function
+
+    // returns base ^ exponent mod m as the least non-negative residue.
+    fn pow_mod(&self, base : &Integer, exponent : &Integer) -> Result<Integer, ArithmeticError> {
+        if exponent.negative {
+            return Err(ArithmeticError::NegativeExponent);
+        }
+        let base = residue(base, &self.modulus);
+        Ok(Integer::from_parts(pow(self, &base, &exponent.value, &self.modulus), false))
+    } // End of pow_mod function
+
+    // reduces a value below m^2 modulo m.
+    fn reduce(&self, x: &[u64]) -> Vec<u64> {
+        let k = self.modulus.len();
+        let x = significant(x);
+        if x.len() < k {
+            return x.to_vec();
+        }
+        let estimate = mul::mul(&x[k-1..], &self.mu);
+        let quotient = if estimate.len() > k + 1 {&estimate[k+1..]} else {&[0][..]};
+        let mut remainder = sub_limbs(x, &mul::mul(quotient, &self.modulus));
+        while cmp_limbs(&remainder, &self.modulus) != Ordering::Less {
+            remainder = sub_limbs(&remainder, &self.modulus);
+        }
+        remainder
+    } // End of reduce function
+} // End of Barrett implementation
+
+impl Reducer for Barrett {
+    fn encode(&self, value: &[u64]) -> Vec<u64> {
+        value.to_vec()
+    } // End of encode function
+
+    fn decode(&self, value: &[u64]) -> Vec<u64> {
+        value.to_vec()
+    } // End of decode function
+
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        self.reduce(&mul::mul(a, b))
+    } // End of mul function
+} // End of Reducer implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns base ^ exponent mod |m| using Barrett reduction, which works for any modulus.
+pub fn barrett_pow_mod(base: &Integer, exponent: &Integer, modulus: &Integer) -> Result<Integer, ArithmeticError> {
+    match Barrett::new(modulus) {
+        Some(context) => context.pow_mod(base, exponent),
+        None => Err(ArithmeticError::DivisionByZero),
+    }
+} // End of mod_pow function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that Montgomery and Barrett exponentiation agree with repeated multiply and divide
+// for single and multi-limb moduli.
+#[test]
+fn modular_pow_agrees_test() {
+    let moduli = [
+        Integer::from(97),
+        Integer::from(1_000_000),
+        Integer::from(u64::MAX),
+        "340282366920938463463374607431768211507".parse::<Integer>().unwrap(),
+        "340282366920938463463374607431768211456".parse::<Integer>().unwrap(),
+        "123456789012345678901234567890123456789012345678901234567891".parse::<Integer>().unwrap(),
+    ];
+    let base = "98765432109876543210987654321".parse::<Integer>().unwrap();
+    for modulus in moduli.iter() {
+        let mut expected = Integer::from(1);
+        for exponent in 0..40u64 {
+            let exponent_integer = Integer::from(exponent);
+            let barrett = Barrett::new(modulus).unwrap().pow_mod(&base, &exponent_integer).unwrap();
+            assert!(barrett == &expected % modulus, "\nbarrett {} ^ {} mod {} = {}\n", base, exponent, modulus, barrett);
+            if let Some(montgomery) = Montgomery::new(modulus) {
+                let result = montgomery.pow_mod(&base, &exponent_integer).unwrap();
+                assert!(result == &expected % modulus, "\nmontgomery {} ^ {} mod {} = {}\n", base, exponent, modulus, result);
+            }
+            expected = &(&expected * &base) % modulus;
+        }
+    }
+} // End of modular_pow_agrees_test
@@ -0,0 +1,399 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Multiplication over the u64 limb vectors used by Integer.
+// Inputs and outputs are magnitudes stored least significant limb first, signs are handled by
+// the Mul operator in the parent module.
+// The algorithm is chosen by the limb count of the smaller operand:
+//   schoolbook O(n^2)          below the karatsuba threshold
+//   Karatsuba O(n^1.585)       below the toom3 threshold
+//   Toom-Cook 3 O(n^1.465)     below the ntt threshold
+//   NTT O(n log n)             above that
+// The thresholds default to values that suit a typical 64bit machine and can be tuned at runtime
+// with Integer::set_mul_thresholds.
+// Operands of very different sizes are cut into pieces the size of the smaller operand first,
+// and squaring has its own path that shares the work of the two identical operands.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::cmp::Ordering;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use super::{add_limbs, cmp_limbs, significant, sub_limbs, trim, div, ntt};
+
+/* ============================================================================================ */
+/*     Thresholds                                                                               */
+/* ============================================================================================ */
+// the smallest operand, in limbs, that is multiplied with Karatsuba instead of schoolbook
+static KARATSUBA_THRESHOLD : AtomicUsize = AtomicUsize::new(32);
+
+// the smallest operand, in limbs, that is multiplied with Toom-Cook 3 instead of Karatsuba
+static TOOM3_THRESHOLD : AtomicUsize = AtomicUsize::new(128);
+
+// the smallest operand, in limbs, that is multiplied with the NTT instead of Toom-Cook 3
+static NTT_THRESHOLD : AtomicUsize = AtomicUsize::new(12288);
+
+// The operand sizes, in limbs, at which multiplication switches to a faster algorithm.
+// Each threshold applies to the smaller operand, squaring uses the same thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MulThresholds {
+    pub karatsuba : usize,  // schoolbook below, Karatsuba at or above
+    pub toom3 : usize,      // Karatsuba below, Toom-Cook 3 at or above
+    pub ntt : usize,        // Toom-Cook 3 below, NTT at or above
+}
+
+// returns the thresholds currently in use.
+pub fn thresholds() -> MulThresholds {
+    MulThresholds {
+        karatsuba: KARATSUBA_THRESHOLD.load(AtomicOrdering::Relaxed),
+        toom3: TOOM3_THRESHOLD.load(AtomicOrdering::Relaxed),
+        ntt: NTT_THRESHOLD.load(AtomicOrdering::Relaxed),
+    }
+} // End of thresholds function
+
+// replaces the thresholds used by every later multiplication.
+// Karatsuba and Toom-Cook 3 split operands into pieces, so their thresholds are raised to at
+// least 2 and 3 limbs to guarantee every piece is smaller than the operand.
+pub fn set_thresholds(thresholds: MulThresholds) {
+    KARATSUBA_THRESHOLD.store(thresholds.karatsuba.max(2), AtomicOrdering::Relaxed);
+    TOOM3_THRESHOLD.store(thresholds.toom3.max(3), AtomicOrdering::Relaxed);
+    NTT_THRESHOLD.store(thresholds.ntt.max(1), AtomicOrdering::Relaxed);
+} // End of set_thresholds function
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// Multiplies the magnitudes a and b, returning the product.
+pub fn mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.as_ptr() == b.as_ptr() && a.len() == b.len() {
+        return square(a);
+    }
+    let (a, b) = (significant(a), significant(b));
+    let (a, b) = if a.len() >= b.len() {(a, b)} else {(b, a)};
+    // the transform handles unbalanced operands directly, its size follows the product
+    if b.len() >= NTT_THRESHOLD.load(AtomicOrdering::Relaxed) {
+        return ntt::mul(a, Some(b));
+    }
+    let mut product = mul_unbalanced(a, b);
+    trim(&mut product);
+    product
+} // End of mul function
+
+// Squares the magnitude a, returning the product.
+pub fn square(a: &[u64]) -> Vec<u64> {
+    let a = significant(a);
+    let thresholds = thresholds();
+    let mut product = if a.len() < thresholds.karatsuba {
+        schoolbook_square(a)
+    } else if a.len() < thresholds.toom3 {
+        karatsuba(a, None)
+    } else if a.len() < thresholds.ntt {
+        toom3(a, None)
+    } else {
+        ntt::mul(a, None)
+    };
+    trim(&mut product);
+    product
+} // End of square function
+
+// Multiplies the magnitude in place by a single limb and adds a single limb to it.
+pub fn mul_add_limb(value: &mut Vec<u64>, multiplier: u64, addend: u64) {
+    let mut carry = addend as u128;
+    for limb in value.iter_mut() {
+        let product = (*limb as u128) * (multiplier as u128) + carry;
+        *limb = product as u64;
+        carry = product >> 64;
+    }
+    if carry > 0 {
+        value.push(carry as u64);
+    }
+    trim(value);
+} // End of mul_add_limb function
+
+// Multiplies a by b where a has at least as many limbs as b.
+// When a is more than twice the size of b it is cut into pieces the size of b, so every
+// recursive multiplication sees roughly balanced operands.
+fn mul_unbalanced(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if b.len() < KARATSUBA_THRESHOLD.load(AtomicOrdering::Relaxed) {
+        let mut product = vec![0u64; a.len() + b.len()];
+        schoolbook(a, b, &mut product);
+        return product;
+    }
+    if a.len() < 2 * b.len() {
+        return mul_balanced(a, b);
+    }
+
+    let mut product = vec![0u64; a.len() + b.len() + 1];
+    for (i, piece) in a.chunks(b.len()).enumerate() {
+        let piece = significant(piece);
+        let partial = if piece.len() >= b.len() {mul_balanced(piece, b)} else {mul_unbalanced(b, piece)};
+        add_at(&mut product, &partial, i * b.len());
+    }
+    product
+} // End of mul_unbalanced function
+
+// Multiplies two operands whose sizes are within a factor of two of each other.
+fn mul_balanced(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let min = a.len().min(b.len());
+    let thresholds = thresholds();
+    if min < thresholds.karatsuba {
+        let mut product = vec![0u64; a.len() + b.len()];
+        schoolbook(a, b, &mut product);
+        product
+    } else if min < thresholds.toom3 {
+        karatsuba(a, Some(b))
+    } else if min < thresholds.ntt {
+        toom3(a, Some(b))
+    } else {
+        ntt::mul(a, Some(b))
+    }
+} // End of mul_balanced function
+
+// Multiplies x by y, or squares x when y is None, picking the algorithm for the operand sizes.
+fn mul_or_square(x: &[u64], y: Option<&[u64]>) -> Vec<u64> {
+    match y {
+        Some(y) => mul(x, y),
+        None => square(x),
+    }
+} // End of mul_or_square function
+
+// Schoolbook O(n*m) multiplication, accumulating a * b into out.
+// out must be zeroed and hold at least a.len() + b.len() limbs.
+fn schoolbook(a: &[u64], b: &[u64], out: &mut [u64]) {
+    for (i, a_limb) in a.iter().enumerate() {
+        if *a_limb == 0 {
+            continue;
+        }
+        // a 64bit * 64bit product plus two 64bit values always fits in 128bits
+        let mut carry : u128 = 0;
+        for (j, b_limb) in b.iter().enumerate() {
+            let product = (*a_limb as u128) * (*b_limb as u128) + out[i+j] as u128 + carry;
+            out[i+j] = product as u64;
+            carry = product >> 64;
+        }
+        out[i+b.len()] = carry as u64;
+    }
+} // End of schoolbook function
+
+// Schoolbook squaring, each cross product a[i] * a[j] is computed once and doubled.
+fn schoolbook_square(a: &[u64]) -> Vec<u64> {
+    let mut out = vec![0u64; 2 * a.len()];
+    for i in 0..a.len() {
+        let mut carry : u128 = 0;
+        for j in i+1..a.len() {
+            let product = (a[i] as u128) * (a[j] as u128) + out[i+j] as u128 + carry;
+            out[i+j] = product as u64;
+            carry = product >> 64;
+        }
+        out[i+a.len()] = carry as u64;
+    }
+
+    // double the cross products and add the squares on the diagonal
+    let mut shifted_out = 0;
+    for limb in out.iter_mut() {
+        let next = *limb >> 63;
+        *limb = (*limb << 1) | shifted_out;
+        shifted_out = next;
+    }
+    let mut carry : u128 = 0;
+    for i in 0..a.len() {
+        let square = (a[i] as u128) * (a[i] as u128);
+        let low = out[2*i] as u128 + (square as u64) as u128 + carry;
+        out[2*i] = low as u64;
+        let high = out[2*i+1] as u128 + (square >> 64) + (low >> 64);
+        out[2*i+1] = high as u64;
+        carry = high >> 64;
+    }
+    out
+} // End of schoolbook_square function
+
+// Karatsuba multiplication of a by b, or the square of a when b is None.
+// Splitting both operands at m limbs, a = a1*B^m + a0 and b = b1*B^m + b0, gives
+//   a*b = z2*B^2m + (z1 - z2 - z0)*B^m + z0
+// where z0 = a0*b0, z2 = a1*b1 and z1 = (a0 + a1)(b0 + b1), three half sized products.
+fn karatsuba(a: &[u64], b: Option<&[u64]>) -> Vec<u64> {
+    let len = a.len().max(b.map_or(0, |b| b.len()));
+    let m = len.div_ceil(2);
+    let (a0, a1) = split(a, m);
+    let b_parts = b.map(|b| split(b, m));
+
+    let (z0, z1, z2) = match b_parts {
+        Some((b0, b1)) => (mul(a0, b0), mul(&add_limbs(a0, a1), &add_limbs(b0, b1)), mul(a1, b1)),
+        None => (square(a0), square(&add_limbs(a0, a1)), square(a1)),
+    };
+    let middle = sub_limbs(&sub_limbs(&z1, &z0), &z2);
+
+    let mut product = vec![0u64; 2 * len + 1];
+    add_at(&mut product, &z0, 0);
+    add_at(&mut product, &middle, m);
+    add_at(&mut product, &z2, 2 * m);
+    product
+} // End of karatsuba function
+
+// Toom-Cook 3 way multiplication of a by b, or the square of a when b is None.
+// Both operands are split into three parts and treated as polynomials in B^k, which are
+// evaluated at 0, 1, -1, -2 and infinity. The five point products determine the degree four
+// product polynomial, which is interpolated with Bodrato's sequence of exact divisions.
+fn toom3(a: &[u64], b: Option<&[u64]>) -> Vec<u64> {
+    let len = a.len().max(b.map_or(0, |b| b.len()));
+    let k = len.div_ceil(3);
+    let a_points = toom3_evaluate(a, k);
+    let b_points = b.map(|b| toom3_evaluate(b, k));
+
+    // the pointwise products r(0), r(1), r(-1), r(-2), r(inf)
+    let mut r = Vec::with_capacity(5);
+    for i in 0..5 {
+        let (x, x_negative) = &a_points[i];
+        let product = match b_points {
+            Some(ref b_points) => {
+                let (y, y_negative) = &b_points[i];
+                (mul_or_square(x, Some(y)), *x_negative != *y_negative)
+            },
+            None => (mul_or_square(x, None), false),
+        };
+        r.push(product);
+    }
+    let (r0, r1, rm1, rm2, rinf) = (&r[0], &r[1], &r[2], &r[3], &r[4]);
+
+    // interpolate the coefficients c0 .. c4 of the product polynomial
+    let c3 = signed_div_small(&signed_sub(rm2, r1), 3);
+    let c1 = signed_half(&signed_sub(r1, rm1));
+    let c2 = signed_sub(rm1, r0);
+    let c3 = signed_add(&signed_half(&signed_sub(&c2, &c3)), &signed_add(rinf, rinf));
+    let c2 = signed_sub(&signed_add(&c2, &c1), rinf);
+    let c1 = signed_sub(&c1, &c3);
+
+    // every coefficient of a product of non-negative numbers is non-negative
+    debug_assert!(!c1.1 && !c2.1 && !c3.1, "toom3 interpolation produced a negative coefficient");
+    let mut product = vec![0u64; 2 * len + 2];
+    add_at(&mut product, &r0.0, 0);
+    add_at(&mut product, &c1.0, k);
+    add_at(&mut product, &c2.0, 2 * k);
+    add_at(&mut product, &c3.0, 3 * k);
+    add_at(&mut product, &rinf.0, 4 * k);
+    product
+} // End of toom3 function
+
+// Splits x = x2*B^2k + x1*B^k + x0 and evaluates it at 0, 1, -1, -2 and infinity.
+fn toom3_evaluate(x: &[u64], k: usize) -> Vec<(Vec<u64>, bool)> {
+    let (x0, rest) = split(x, k);
+    let (x1, x2) = split(rest, k);
+    let x0 = (x0.to_vec(), false);
+    let x1 = (x1.to_vec(), false);
+    let x2 = (x2.to_vec(), false);
+
+    let even = signed_add(&x0, &x2);
+    let at_one = signed_add(&even, &x1);
+    let at_minus_one = signed_sub(&even, &x1);
+    // x(-2) = 2 * (x(-1) + x2) - x0
+    let doubled = signed_add(&at_minus_one, &x2);
+    let at_minus_two = signed_sub(&signed_add(&doubled, &doubled), &x0);
+    vec![x0, at_one, at_minus_one, at_minus_two, x2]
+} // End of toom3_evaluate function
+
+/* ============================================================================================ */
+/*     Helpers                                                                                  */
+/* ============================================================================================ */
+
+// Splits x into its low m limbs and the remaining high limbs, either part may be [0].
+fn split(x: &[u64], m: usize) -> (&[u64], &[u64]) {
+    if x.len() <= m {
+        (significant(x), &[0])
+    } else {
+        (significant(&x[..m]), significant(&x[m..]))
+    }
+} // End of split function
+
+// Adds value into out starting at limb offset, out must be large enough to hold the sum.
+fn add_at(out: &mut [u64], value: &[u64], offset: usize) {
+    let value = significant(value);
+    let mut carry = false;
+    let mut i = 0;
+    while i < value.len() || carry {
+        let addend = if i < value.len() {value[i]} else {0};
+        let (sum, overflow1) = out[offset+i].overflowing_add(addend);
+        let (sum, overflow2) = sum.overflowing_add(carry as u64);
+        out[offset+i] = sum;
+        carry = overflow1 || overflow2;
+        i += 1;
+    }
+} // End of add_at function
+
+// Adds two signed magnitudes stored as (magnitude, negative).
+fn signed_add(x: &(Vec<u64>, bool), y: &(Vec<u64>, bool)) -> (Vec<u64>, bool) {
+    if x.1 == y.1 {
+        return (add_limbs(&x.0, &y.0), x.1);
+    }
+    match cmp_limbs(&x.0, &y.0) {
+        Ordering::Less => (sub_limbs(&y.0, &x.0), y.1),
+        Ordering::Equal => (vec![0], false),
+        Ordering::Greater => (sub_limbs(&x.0, &y.0), x.1),
+    }
+} // End of signed_add function
+
+// Subtracts two signed magnitudes stored as (magnitude, negative).
+fn signed_sub(x: &(Vec<u64>, bool), y: &(Vec<u64>, bool)) -> (Vec<u64>, bool) {
+    let negated = (y.0.clone(), !y.1);
+    signed_add(x, &negated)
+} // End of signed_sub function
+
+// Divides a signed magnitude by a small divisor that is known to divide it exactly.
+fn signed_div_small(x: &(Vec<u64>, bool), divisor: u64) -> (Vec<u64>, bool) {
+    let (quotient, remainder) = div::div_rem_limb(&x.0, divisor);
+    debug_assert!(remainder == 0, "toom3 interpolation division was not exact");
+    (quotient, x.1)
+} // End of signed_div_small function
+
+// Halves a signed magnitude that is known to be even.
+fn signed_half(x: &(Vec<u64>, bool)) -> (Vec<u64>, bool) {
+    let mut half = x.0.clone();
+    for i in 0..half.len() {
+        let high = if i + 1 < half.len() {half[i+1] << 63} else {0};
+        half[i] = (half[i] >> 1) | high;
+    }
+    trim(&mut half);
+    (half, x.1)
+} // End of signed_half function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that Karatsuba, Toom-Cook 3, the unbalanced split and squaring all agree with schoolbook
+// multiplication for operands on both sides of each threshold.
+#[test]
+fn mul_algorithms_agree_test() {
+    // a simple xorshift generator so the operands are dense and repeatable
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut random_limbs = |len: usize| -> Vec<u64> {
+        (0..len).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        }).collect()
+    };
+
+    let sizes = [(1, 1), (31, 33), (40, 40), (40, 100), (127, 129), (200, 200), (300, 150), (500, 90), (64, 1000)];
+    for &(a_len, b_len) in sizes.iter() {
+        let a = random_limbs(a_len);
+        let b = random_limbs(b_len);
+        let mut expected = vec![0u64; a_len + b_len];
+        schoolbook(&a, &b, &mut expected);
+        trim(&mut expected);
+        assert!(mul(&a, &b) == expected, "\nproduct of {} by {} limbs did not match schoolbook\n", a_len, b_len);
+
+        let mut expected_square = vec![0u64; 2 * a_len];
+        schoolbook(&a, &a, &mut expected_square);
+        trim(&mut expected_square);
+        assert!(square(&a) == expected_square, "\nsquare of {} limbs did not match schoolbook\n", a_len);
+    }
+
+    // all ones operands maximise the carries in every algorithm
+    let ones = vec![u64::MAX; 400];
+    let mut expected = vec![0u64; 800];
+    schoolbook(&ones, &ones, &mut expected);
+    assert!(mul(&ones, &ones.clone()) == expected, "\nproduct of all ones operands did not match schoolbook\n");
+    assert!(square(&ones) == expected, "\nsquare of all ones operand did not match schoolbook\n");
+} // End of mul_algorithms_agree_test
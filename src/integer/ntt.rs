@@ -0,0 +1,290 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Multiplication of very large magnitudes with a number theoretic transform (NTT).
+// The limbs of each operand are treated as the coefficients of a polynomial, and the product
+// polynomial is found by transforming, multiplying pointwise and transforming back, O(n log n).
+// Unlike a floating point FFT every step is exact: the convolution is computed modulo three
+// primes of the form k*2^40 + 1 and the true coefficients, which are below n * 2^128, are
+// recovered with the Chinese Remainder Theorem since the primes multiply to more than 2^186.
+// Arithmetic modulo each prime is done in Montgomery form so no step needs a 128bit division.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::trim;
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+// the three primes and a primitive root of each, every prime - 1 is divisible by 2^40
+const PRIMES : [(u64, u64); 3] = [
+    (0x7FFF_FE00_0000_0001, 7),
+    (0x7FFF_EF00_0000_0001, 5),
+    (0x7FFF_E900_0000_0001, 7),
+];
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// Multiplies the magnitudes a and b, or squares a when b is None, returning the product.
+pub fn mul(a: &[u64], b: Option<&[u64]>) -> Vec<u64> {
+    let b_len = b.map_or(a.len(), |b| b.len());
+    let n = (a.len() + b_len).next_power_of_two();
+
+    // the convolution modulo each prime, in natural order
+    let mut residues = Vec::with_capacity(3);
+    for &(prime, generator) in PRIMES.iter() {
+        let field = Field::new(prime);
+        let roots = field.roots(generator, n);
+        let mut fa = field.transform_input(a, n);
+        field.forward(&mut fa, &roots.0);
+        match b {
+            Some(b) => {
+                let mut fb = field.transform_input(b, n);
+                field.forward(&mut fb, &roots.0);
+                for (x, y) in fa.iter_mut().zip(fb.iter()) {
+                    *x = field.mul(*x, *y);
+                }
+            },
+            None => {
+                for x in fa.iter_mut() {
+                    *x = field.mul(*x, *x);
+                }
+            },
+        }
+        field.inverse(&mut fa, &roots.1);
+        residues.push((field, fa));
+    }
+
+    // recombine each coefficient and add it into the product at its limb position
+    let garner = Garner::new(&residues[0].0, &residues[1].0, &residues[2].0);
+    let mut product = vec![0u64; a.len() + b_len + 3];
+    for i in 0..(a.len() + b_len - 1) {
+        let coefficient = garner.combine(residues[0].1[i], residues[1].1[i], residues[2].1[i]);
+        let mut carry = false;
+        let mut j = 0;
+        while j < 3 || carry {
+            let addend = if j < 3 {coefficient[j]} else {0};
+            let (sum, overflow1) = product[i+j].overflowing_add(addend);
+            let (sum, overflow2) = sum.overflowing_add(carry as u64);
+            product[i+j] = sum;
+            carry = overflow1 || overflow2;
+            j += 1;
+        }
+    }
+    trim(&mut product);
+    product
+} // End of mul function
+
+/* ============================================================================================ */
+/*     Field                                                                                    */
+/* ============================================================================================ */
+
+// Arithmetic modulo a prime below 2^63 with values kept in Montgomery form, x * 2^64 mod p.
+struct Field {
+    prime : u64,        // the modulus
+    prime_inv : u64,    // -prime^-1 mod 2^64, used by the Montgomery reduction
+    r2 : u64,           // 2^128 mod prime, used to move values into Montgomery form
+}
+
+impl Field {
+    fn new(prime: u64) -> Field {
+        // Newton's iteration doubles the correct low bits of the inverse each step
+        let mut inv : u64 = prime;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(prime.wrapping_mul(inv)));
+        }
+        let r = ((1u128 << 64) % prime as u128) as u64;
+        Field {
+            prime,
+            prime_inv: inv.wrapping_neg(),
+            r2: ((r as u128 * r as u128) % prime as u128) as u64,
+        }
+    } // End of new function
+
+    // returns a * b / 2^64 mod prime, which is the Montgomery product when both are in form.
+    // a may be any u64 as long as b is below the prime.
+    #[inline]
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        let t = a as u128 * b as u128;
+        let m = (t as u64).wrapping_mul(self.prime_inv);
+        let reduced = ((t + m as u128 * self.prime as u128) >> 64) as u64;
+        if reduced >= self.prime {reduced - self.prime} else {reduced}
+    } // End of mul function
+
+    #[inline]
+    fn add(&self, a: u64, b: u64) -> u64 {
+        let sum = a + b;
+        if sum >= self.prime {sum - self.prime} else {sum}
+    } // End of add function
+
+    #[inline]
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b {a - b} else {a + self.prime - b}
+    } // End of sub function
+
+    // moves a plain value below 2^64 into Montgomery form.
+    #[inline]
+    fn to_form(&self, a: u64) -> u64 {
+        self.mul(a, self.r2)
+    } // End of to_form function
+
+    // returns base^exp with base and result in Montgomery form.
+    fn pow(&self, base: u64, mut exp: u64) -> u64 {
+        let (mut result, mut base) = (self.to_form(1), base);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    } // End of pow function
+
+    // returns the powers w^0 .. w^(n/2 - 1) of a primitive nth root of unity w and of its inverse.
+    fn roots(&self, generator: u64, n: usize) -> (Vec<u64>, Vec<u64>) {
+        let w = self.pow(self.to_form(generator), (self.prime - 1) / n as u64);
+        let w_inv = self.pow(w, self.prime - 2);
+        let (mut roots, mut inverse_roots) = (Vec::with_capacity(n / 2), Vec::with_capacity(n / 2));
+        let (mut current, mut current_inv) = (self.to_form(1), self.to_form(1));
+        for _ in 0..(n / 2).max(1) {
+            roots.push(current);
+            inverse_roots.push(current_inv);
+            current = self.mul(current, w);
+            current_inv = self.mul(current_inv, w_inv);
+        }
+        (roots, inverse_roots)
+    } // End of roots function
+
+    // copies the limbs into Montgomery form, padded with zeros to n values.
+    fn transform_input(&self, limbs: &[u64], n: usize) -> Vec<u64> {
+        let mut values = Vec::with_capacity(n);
+        values.extend(limbs.iter().map(|limb| self.to_form(*limb)));
+        values.resize(n, 0);
+        values
+    } // End of transform_input function
+
+    // Gentleman-Sande decimation in frequency, natural order in and bit reversed order out.
+    fn forward(&self, values: &mut [u64], roots: &[u64]) {
+        let n = values.len();
+        let mut len = n;
+        while len >= 2 {
+            let half = len / 2;
+            let stride = n / len;
+            for start in (0..n).step_by(len) {
+                for j in 0..half {
+                    let (u, v) = (values[start+j], values[start+j+half]);
+                    values[start+j] = self.add(u, v);
+                    values[start+j+half] = self.mul(self.sub(u, v), roots[j * stride]);
+                }
+            }
+            len = half;
+        }
+    } // End of forward function
+
+    // Cooley-Tukey decimation in time, bit reversed order in and natural order out, scaled by 1/n
+    // and taken out of Montgomery form.
+    fn inverse(&self, values: &mut [u64], inverse_roots: &[u64]) {
+        let n = values.len();
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let stride = n / len;
+            for start in (0..n).step_by(len) {
+                for j in 0..half {
+                    let u = values[start+j];
+                    let v = self.mul(values[start+j+half], inverse_roots[j * stride]);
+                    values[start+j] = self.add(u, v);
+                    values[start+j+half] = self.sub(u, v);
+                }
+            }
+            len *= 2;
+        }
+
+        // multiplying by the plain value 1/n divides by n and leaves Montgomery form in one step
+        let n_inv = self.pow(self.to_form(n as u64), self.prime - 2);
+        let n_inv_plain = self.mul(n_inv, 1);
+        for value in values.iter_mut() {
+            *value = self.mul(*value, n_inv_plain);
+        }
+    } // End of inverse function
+} // End of Field implementation
+
+/* ============================================================================================ */
+/*     Garner                                                                                   */
+/* ============================================================================================ */
+
+// Recombines residues modulo the three primes p1 > p2 > p3 into a value below p1*p2*p3 with
+// Garner's algorithm, x = r1 + p1*s + p1*p2*t where s < p2 and t < p3.
+struct Garner<'a> {
+    fields : [&'a Field; 3],
+    inv_p1_mod_p2 : u64,        // p1^-1 mod p2, in Montgomery form
+    inv_p1_mod_p3 : u64,        // p1^-1 mod p3, in Montgomery form
+    inv_p2_mod_p3 : u64,        // p2^-1 mod p3, in Montgomery form
+    p1_p2 : u128,               // p1 * p2
+}
+
+impl<'a> Garner<'a> {
+    fn new(f1: &'a Field, f2: &'a Field, f3: &'a Field) -> Garner<'a> {
+        let inverse = |field: &Field, value: u64| field.pow(field.to_form(value), field.prime - 2);
+        Garner {
+            fields: [f1, f2, f3],
+            inv_p1_mod_p2: inverse(f2, f1.prime),
+            inv_p1_mod_p3: inverse(f3, f1.prime),
+            inv_p2_mod_p3: inverse(f3, f2.prime),
+            p1_p2: f1.prime as u128 * f2.prime as u128,
+        }
+    } // End of new function
+
+    // returns the three limbs of the value with the given plain residues.
+    fn combine(&self, r1: u64, r2: u64, r3: u64) -> [u64; 3] {
+        let [f1, f2, f3] = self.fields;
+        // the primes are within a factor of two, so one subtraction reduces between them
+        let reduce = |value: u64, field: &Field| if value >= field.prime {value - field.prime} else {value};
+
+        // a plain value times an inverse in Montgomery form gives a plain product
+        let s = f2.mul(f2.sub(r2, reduce(r1, f2)), self.inv_p1_mod_p2);
+        let t = f3.mul(f3.sub(r3, reduce(r1, f3)), self.inv_p1_mod_p3);
+        let t = f3.mul(f3.sub(t, reduce(s, f3)), self.inv_p2_mod_p3);
+
+        // r1 + p1*s fits in 127 bits, p1*p2*t needs three limbs
+        let low = r1 as u128 + f1.prime as u128 * s as u128;
+        let t_low = (self.p1_p2 as u64) as u128 * t as u128;
+        let t_high = (self.p1_p2 >> 64) * t as u128;
+        let limb0 = (low as u64) as u128 + (t_low as u64) as u128;
+        let limb1 = (low >> 64) + (t_low >> 64) + (t_high as u64) as u128 + (limb0 >> 64);
+        let limb2 = (t_high >> 64) + (limb1 >> 64);
+        [limb0 as u64, limb1 as u64, limb2 as u64]
+    } // End of combine function
+} // End of Garner implementation
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that the transform agrees with the Toom-Cook and schoolbook paths, including all ones
+// operands whose convolution coefficients are as large as possible.
+#[test]
+fn ntt_matches_mul_test() {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut random_limbs = |len: usize| -> Vec<u64> {
+        (0..len).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        }).collect()
+    };
+
+    for &(a_len, b_len) in [(1, 1), (3, 700), (300, 300), (1000, 999)].iter() {
+        let a = random_limbs(a_len);
+        let b = random_limbs(b_len);
+        assert!(mul(&a, Some(&b)) == super::mul::mul(&a, &b), "\nntt product of {} by {} limbs did not match\n", a_len, b_len);
+        assert!(mul(&a, None) == super::mul::square(&a), "\nntt square of {} limbs did not match\n", a_len);
+    }
+    let ones = vec![u64::MAX; 1200];
+    assert!(mul(&ones, None) == super::mul::mul(&ones, &ones.clone()), "\nntt square of all ones did not match\n");
+} // End of ntt_matches_mul_test
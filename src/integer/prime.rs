@@ -0,0 +1,303 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Primality testing and prime search.
+// Candidates are first trial divided by the primes below 256. Values below 2^64 then get a
+// Miller-Rabin test with the first twelve primes as witnesses, which is known to be exact for
+// every n below 3.3 * 10^24. Larger values get the Baillie-PSW test, a strong base 2 Miller-Rabin
+// test followed by a strong Lucas test with Selfridge's parameters, which has no known
+// counterexample, and optionally more Miller-Rabin rounds with further bases.
+// Modular exponentiation uses a Montgomery context built once per candidate.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::{Integer, Montgomery};
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+// the primes below 256, used for trial division
+const SMALL_PRIMES : [u64; 54] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+    97, 101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191,
+    193, 197, 199, 211, 223, 227, 229, 233, 239, 241, 251,
+];
+
+// witnesses that make Miller-Rabin exact below 3.3 * 10^24, and so for every u64
+const U64_WITNESSES : [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns true if the number is probably prime. Values below 2^64 are answered exactly.
+    // Larger values pass the Baillie-PSW test and then rounds more Miller-Rabin tests with
+    // pseudo random bases, each of which a composite passes with probability below 1/4.
+    // Negative numbers, 0 and 1 are not prime.
+    pub fn is_probable_prime(&self, rounds : u32) -> bool {
+        if self.negative {
+            return false;
+        }
+        for &p in SMALL_PRIMES.iter() {
+            if self.value == [p] {
+                return true;
+            }
+            if (self % p).is_zero() {
+                return false;
+            }
+        }
+        if self.value.len() == 1 {
+            // no factor below 256, so every n < 256^2 is prime
+            return self.value[0] >= 2 && (self.value[0] < 256 * 256 || is_prime_u64(self.value[0]));
+        }
+
+        let context = Montgomery::new(self).unwrap();
+        if !miller_rabin(&context, self, &Integer::from(2)) || !strong_lucas(self) {
+            return false;
+        }
+
+        // further bases from a generator seeded by the number, so answers are repeatable
+        let mut state = self.value[0] | 1;
+        let range = self - 3u32;
+        (0..rounds).all(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let base = Integer::from(state) % &range + 2u32;
+            miller_rabin(&context, self, &base)
+        })
+    } // End of is_probable_prime function
+
+    // returns the smallest prime greater than the number.
+    pub fn next_prime(&self) -> Integer {
+        if *self < Integer::from(2) {
+            return Integer::from(2);
+        }
+        // step through odd candidates, starting at the first odd number above self
+        let mut candidate = self + if self.value[0] & 1 == 0 {1u32} else {2u32};
+        while !candidate.is_probable_prime(0) {
+            candidate += 2u32;
+        }
+        candidate
+    } // End of next_prime function
+
+    // returns the largest prime less than the number, or None if there is none.
+    pub fn prev_prime(&self) -> Option<Integer> {
+        if *self <= Integer::from(2) {
+            return None;
+        }
+        if *self == Integer::from(3) {
+            return Some(Integer::from(2));
+        }
+        let mut candidate = self - if self.value[0] & 1 == 0 {1u32} else {2u32};
+        while !candidate.is_probable_prime(0) {
+            candidate -= 2u32;
+        }
+        Some(candidate)
+    } // End of prev_prime function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns every prime up to limit with a sieve of Eratosthenes over the odd numbers.
+pub fn primes_up_to(limit : u64) -> Vec<u64> {
+    if limit < 2 {
+        return Vec::new();
+    }
+    // bit i of the sieve stands for the odd number 2i + 1
+    let size = (limit as usize).div_ceil(2) + 1;
+    let mut composite = vec![0u64; size / 64 + 1];
+    let mut primes = vec![2];
+    for i in 1..size {
+        let p = 2 * i as u64 + 1;
+        if p > limit {
+            break;
+        }
+        if (composite[i / 64] >> (i % 64)) & 1 == 1 {
+            continue;
+        }
+        primes.push(p);
+        let mut j = (p * p / 2) as usize;
+        while j < size {
+            composite[j / 64] |= 1 << (j % 64);
+            j += p as usize;
+        }
+    }
+    primes
+} // End of primes_up_to function
+
+// Deterministic Miller-Rabin for an odd n below 2^64 with no factor below 256.
+fn is_prime_u64(n : u64) -> bool {
+    let mul_mod = |a : u64, b : u64| (a as u128 * b as u128 % n as u128) as u64;
+    let pow_mod = |mut base : u64, mut exponent : u64| {
+        let mut result = 1;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = mul_mod(result, base);
+            }
+            base = mul_mod(base, base);
+            exponent >>= 1;
+        }
+        result
+    };
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    U64_WITNESSES.iter().all(|&a| {
+        let mut x = pow_mod(a % n, d);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+} // End of is_prime_u64 function
+
+// The strong probable prime test to the given base for an odd n > 3, with n - 1 = d * 2^s.
+// A prime n has either a^d = 1 or a^(d 2^r) = -1 mod n for some r < s.
+fn miller_rabin(context : &Montgomery, n : &Integer, base : &Integer) -> bool {
+    let n_minus_one = n - 1u32;
+    let s = n_minus_one.trailing_zeros().unwrap();
+    let d = &n_minus_one >> s;
+    let mut x = context.pow_mod(base, &d).unwrap();
+    if x == Integer::from(1) || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = context.mul_mod(&x, &x);
+        if x == n_minus_one {
+            return true;
+        }
+    }
+    false
+} // End of miller_rabin function
+
+// The strong Lucas probable prime test for an odd n > 3, with Selfridge's method A:
+// D is the first of 5, -7, 9, -11, .. with Jacobi symbol (D/n) = -1, P = 1 and Q = (1 - D)/4.
+// With n + 1 = d * 2^s a prime n has U_d = 0 or V_(d 2^r) = 0 mod n for some r < s.
+fn strong_lucas(n : &Integer) -> bool {
+    // no such D exists for a square, which is composite anyway
+    if n.is_perfect_square() {
+        return false;
+    }
+    let mut d = 5i64;
+    loop {
+        match Integer::from(d).jacobi(n).unwrap() {
+            -1 => break,
+            0 if Integer::from(d.abs()) != *n => return false,
+            _ => d = if d > 0 {-d - 2} else {-d + 2},
+        }
+    }
+    let q = (1 - d) / 4;
+
+    let n_plus_one = n + 1u32;
+    let s = n_plus_one.trailing_zeros().unwrap();
+    let (u, mut v, mut q_k) = lucas_uv(&n_plus_one >> s, n, d, q);
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        // V_2k = V_k^2 - 2 Q^k
+        v = modulo(&(&v.square() - &(&q_k * 2u32)), n);
+        if v.is_zero() {
+            return true;
+        }
+        q_k = modulo(&q_k.square(), n);
+    }
+    false
+} // End of strong_lucas function
+
+// returns (U_k, V_k, Q^k) mod n for the Lucas sequences with P = 1 and the given D and Q,
+// working down the bits of k with the doubling and add one formulas.
+fn lucas_uv(k : Integer, n : &Integer, d : i64, q : i64) -> (Integer, Integer, Integer) {
+    let half = |x : Integer| -> Integer {
+        // x / 2 mod n for an odd n, adding n first when x is odd
+        if x.value[0] & 1 == 1 {(x + n) >> 1} else {x >> 1}
+    };
+    let q_integer = modulo(&Integer::from(q), n);
+    let (mut u, mut v, mut q_k) = (Integer::from(1), Integer::from(1), q_integer.clone());
+    for i in (0..k.bit_length() - 1).rev() {
+        // U_2k = U_k V_k, V_2k = V_k^2 - 2 Q^k
+        u = modulo(&(&u * &v), n);
+        v = modulo(&(&v.square() - &(&q_k * 2u32)), n);
+        q_k = modulo(&q_k.square(), n);
+        if k.bit(i) {
+            // U_(k+1) = (P U_k + V_k) / 2, V_(k+1) = (D U_k + P V_k) / 2
+            let next_u = half(&u + &v);
+            v = half(modulo(&(&u * d + &v), n));
+            u = modulo(&next_u, n);
+            q_k = modulo(&(&q_k * &q_integer), n);
+        }
+    }
+    (u, v, q_k)
+} // End of lucas_uv function
+
+// returns the least non-negative residue of x modulo the positive n.
+fn modulo(x : &Integer, n : &Integer) -> Integer {
+    let remainder = x % n;
+    if remainder.negative {remainder + n} else {remainder}
+} // End of modulo function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the primality of every number below 20000 against a sieve, along with pseudoprimes
+// that fool weaker tests.
+#[test]
+fn small_primes_test() {
+    let mut sieve = vec![true; 20000];
+    sieve[0] = false;
+    sieve[1] = false;
+    for i in 2..sieve.len() {
+        if sieve[i] {
+            for j in (i * i..sieve.len()).step_by(i) {
+                sieve[j] = false;
+            }
+        }
+    }
+    for (n, &prime) in sieve.iter().enumerate() {
+        let integer = Integer::from(n as u64);
+        assert!(integer.is_probable_prime(0) == prime, "\nEvaluated {} as prime: {}\n", n, !prime);
+    }
+    let primes : Vec<u64> = (0..sieve.len() as u64).filter(|&n| sieve[n as usize]).collect();
+    assert!(primes_up_to(sieve.len() as u64 - 1) == primes && primes_up_to(2) == vec![2], "\nEvaluated the primes below 20000 incorrectly\n");
+    assert!(!Integer::from(-7).is_probable_prime(5), "\nEvaluated -7 as prime\n");
+
+    // Carmichael numbers, strong pseudoprimes to base 2 and to the first seven prime bases
+    for &n in [561u64, 41041, 2047, 3215031751, 341550071728321, 3825123056546413051].iter() {
+        assert!(!Integer::from(n).is_probable_prime(0), "\nEvaluated the pseudoprime {} as prime\n", n);
+    }
+    assert!(Integer::from(18446744073709551557u64).is_probable_prime(0), "\nEvaluated 2^64 - 59 as composite\n");
+} // End of small_primes_test
+
+// Tests the strong Lucas test on its known pseudoprimes and BPSW on multi-limb values.
+#[test]
+fn large_primes_test() {
+    for &n in [5459u64, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309, 58519].iter() {
+        assert!(strong_lucas(&Integer::from(n)), "\nEvaluated the strong Lucas pseudoprime {} as composite\n", n);
+    }
+    let one = Integer::from(1);
+    let mersenne = |p : u64| (&one << p) - 1u32;
+    assert!(mersenne(127).is_probable_prime(10), "\nEvaluated 2^127 - 1 as composite\n");
+    assert!(mersenne(521).is_probable_prime(2), "\nEvaluated 2^521 - 1 as composite\n");
+    assert!(!mersenne(523).is_probable_prime(2), "\nEvaluated 2^523 - 1 as prime\n");
+    assert!(!(&mersenne(89) * &mersenne(107)).is_probable_prime(2), "\nEvaluated (2^89 - 1)(2^107 - 1) as prime\n");
+    assert!(!((&one << 128) + 1u32).is_probable_prime(2), "\nEvaluated 2^128 + 1 as prime\n");
+
+    // the primes around 2^64 and 2^128
+    assert!((&one << 64).next_prime() == (&one << 64) + 13u32, "\nEvaluated the prime after 2^64 as {}\n", (&one << 64).next_prime());
+    assert!((&one << 64).prev_prime() == Some((&one << 64) - 59u32), "\nEvaluated the prime before 2^64 incorrectly\n");
+    assert!((&one << 128).prev_prime() == Some((&one << 128) - 159u32), "\nEvaluated the prime before 2^128 incorrectly\n");
+    assert!(Integer::from(-5).next_prime() == Integer::from(2), "\nEvaluated the prime after -5 incorrectly\n");
+    assert!(Integer::from(2).prev_prime().is_none() && Integer::from(4).prev_prime() == Some(Integer::from(3)), "\nEvaluated prev_prime near 2 incorrectly\n");
+    assert!(Integer::from(89).next_prime() == Integer::from(97), "\nEvaluated the prime after 89 incorrectly\n");
+} // End of large_primes_test
@@ -0,0 +1,382 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Conversions from Rust's primitive integers and arithmetic between them and Integer.
+// A primitive operand is split into at most two limbs on the stack and its sign, so adding,
+// multiplying or dividing by one never builds a second Integer. Operands that fit in one limb
+// take the single limb paths, which work on the Integer's limbs in place.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::ops::{Add, Sub, Mul, Div, Rem, AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
+use super::{Integer, significant, div, mul};
+
+/* ============================================================================================ */
+/*     Parts                                                                                    */
+/* ============================================================================================ */
+
+// Splits a primitive into its magnitude and whether it is negative.
+trait Parts {
+    fn parts(self) -> (u128, bool);
+}
+
+impl Parts for u32 {
+    #[inline]
+    fn parts(self) -> (u128, bool) {(self as u128, false)}
+}
+
+impl Parts for u64 {
+    #[inline]
+    fn parts(self) -> (u128, bool) {(self as u128, false)}
+}
+
+impl Parts for u128 {
+    #[inline]
+    fn parts(self) -> (u128, bool) {(self, false)}
+}
+
+impl Parts for i32 {
+    #[inline]
+    fn parts(self) -> (u128, bool) {(self.unsigned_abs() as u128, self < 0)}
+}
+
+impl Parts for i64 {
+    #[inline]
+    fn parts(self) -> (u128, bool) {(self.unsigned_abs() as u128, self < 0)}
+}
+
+impl Parts for i128 {
+    #[inline]
+    fn parts(self) -> (u128, bool) {(self.unsigned_abs(), self < 0)}
+}
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns the limbs of a magnitude, zero in the high limb when it fits in one.
+#[inline]
+fn limbs(magnitude : u128) -> [u64; 2] {
+    [magnitude as u64, (magnitude >> 64) as u64]
+} // End of limbs function
+
+// returns integer + (-1)^negative * magnitude, adding into the integer's limbs.
+fn add_small(mut integer : Integer, magnitude : u128, negative : bool) -> Integer {
+    integer.add_signed(&limbs(magnitude), negative);
+    integer
+} // End of add_small function
+
+// returns integer * (-1)^negative * magnitude, multiplying the integer's limbs in place when the
+// magnitude fits in a single limb.
+fn mul_small(mut integer : Integer, magnitude : u128, negative : bool) -> Integer {
+    let negative = integer.negative != negative;
+    if magnitude <= u64::MAX as u128 {
+        mul::mul_add_limb(&mut integer.value, magnitude as u64, 0);
+        Integer::from_parts(integer.value, negative)
+    } else {
+        Integer::from_parts(mul::mul(&integer.value, &limbs(magnitude)), negative)
+    }
+} // End of mul_small function
+
+// returns the truncated quotient and remainder of integer / ((-1)^negative * magnitude).
+// Panics if the magnitude is zero.
+fn div_rem_small(integer : &Integer, magnitude : u128, negative : bool) -> (Integer, Integer) {
+    assert!(magnitude != 0, "attempt to divide by zero");
+    let (quotient, remainder) = if magnitude <= u64::MAX as u128 {
+        let (quotient, remainder) = div::div_rem_limb(&integer.value, magnitude as u64);
+        (quotient, vec![remainder])
+    } else {
+        div::div_rem(&integer.value, significant(&limbs(magnitude)))
+    };
+    (Integer::from_parts(quotient, integer.negative != negative),
+     Integer::from_parts(remainder, integer.negative))
+} // End of div_rem_small function
+
+/* ============================================================================================ */
+/*     Macros                                                                                   */
+/* ============================================================================================ */
+
+// Implements From and the arithmetic operators for each primitive.
+macro_rules! primitive_impls {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Integer {
+            #[inline]
+            fn from(init : $t) -> Integer {
+                let (magnitude, negative) = init.parts();
+                Integer::from_parts(limbs(magnitude).to_vec(), negative)
+            }
+        }
+
+        impl Add<$t> for Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn add(self, num : $t) -> Integer {
+                let (magnitude, negative) = num.parts();
+                add_small(self, magnitude, negative)
+            }
+        }
+
+        impl Sub<$t> for Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn sub(self, num : $t) -> Integer {
+                let (magnitude, negative) = num.parts();
+                add_small(self, magnitude, !negative)
+            }
+        }
+
+        impl Mul<$t> for Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn mul(self, num : $t) -> Integer {
+                let (magnitude, negative) = num.parts();
+                mul_small(self, magnitude, negative)
+            }
+        }
+
+        impl Div<$t> for Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn div(self, num : $t) -> Integer {
+                &self / num
+            }
+        }
+
+        impl Rem<$t> for Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn rem(self, num : $t) -> Integer {
+                &self % num
+            }
+        }
+
+        impl Add<$t> for &Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn add(self, num : $t) -> Integer {
+                self.clone() + num
+            }
+        }
+
+        impl Sub<$t> for &Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn sub(self, num : $t) -> Integer {
+                self.clone() - num
+            }
+        }
+
+        impl Mul<$t> for &Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn mul(self, num : $t) -> Integer {
+                self.clone() * num
+            }
+        }
+
+        impl Div<$t> for &Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn div(self, num : $t) -> Integer {
+                let (magnitude, negative) = num.parts();
+                div_rem_small(self, magnitude, negative).0
+            }
+        }
+
+        impl Rem<$t> for &Integer {
+            type Output = Integer;
+
+            #[inline]
+            fn rem(self, num : $t) -> Integer {
+                let (magnitude, negative) = num.parts();
+                div_rem_small(self, magnitude, negative).1
+            }
+        }
+
+        impl Add<Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn add(self, num : Integer) -> Integer {
+                num + self
+            }
+        }
+
+        impl<'b> Add<&'b Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn add(self, num : &'b Integer) -> Integer {
+                num + self
+            }
+        }
+
+        impl Sub<Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn sub(self, num : Integer) -> Integer {
+                -num + self
+            }
+        }
+
+        impl<'b> Sub<&'b Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn sub(self, num : &'b Integer) -> Integer {
+                -num + self
+            }
+        }
+
+        impl Mul<Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn mul(self, num : Integer) -> Integer {
+                num * self
+            }
+        }
+
+        impl<'b> Mul<&'b Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn mul(self, num : &'b Integer) -> Integer {
+                num * self
+            }
+        }
+
+        // a primitive divided by an Integer has a primitive sized result, so these simply
+        // convert the primitive
+        impl Div<Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn div(self, num : Integer) -> Integer {
+                &Integer::from(self) / &num
+            }
+        }
+
+        impl<'b> Div<&'b Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn div(self, num : &'b Integer) -> Integer {
+                &Integer::from(self) / num
+            }
+        }
+
+        impl Rem<Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn rem(self, num : Integer) -> Integer {
+                &Integer::from(self) % &num
+            }
+        }
+
+        impl<'b> Rem<&'b Integer> for $t {
+            type Output = Integer;
+
+            #[inline]
+            fn rem(self, num : &'b Integer) -> Integer {
+                &Integer::from(self) % num
+            }
+        }
+
+        impl AddAssign<$t> for Integer {
+            #[inline]
+            fn add_assign(&mut self, num : $t) {
+                let (magnitude, negative) = num.parts();
+                self.add_signed(&limbs(magnitude), negative);
+            }
+        }
+
+        impl SubAssign<$t> for Integer {
+            #[inline]
+            fn sub_assign(&mut self, num : $t) {
+                let (magnitude, negative) = num.parts();
+                self.add_signed(&limbs(magnitude), !negative);
+            }
+        }
+
+        impl MulAssign<$t> for Integer {
+            #[inline]
+            fn mul_assign(&mut self, num : $t) {
+                let (magnitude, negative) = num.parts();
+                let integer = ::std::mem::take(self);
+                *self = mul_small(integer, magnitude, negative);
+            }
+        }
+
+        impl DivAssign<$t> for Integer {
+            #[inline]
+            fn div_assign(&mut self, num : $t) {
+                *self = &*self / num;
+            }
+        }
+
+        impl RemAssign<$t> for Integer {
+            #[inline]
+            fn rem_assign(&mut self, num : $t) {
+                *self = &*self % num;
+            }
+        }
+    )*};
+}
+
+primitive_impls!(u32, u64, u128, i32, i64, i128);
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that every operator between Integer and a primitive agrees with i128 arithmetic,
+// for primitives on either side and values on both sides of the one limb boundary.
+#[test]
+fn primitive_ops_match_i128_test() {
+    let values = [0i128, 1, -1, 7, -7, u64::MAX as i128, -(u64::MAX as i128), 1 << 64, -(1 << 70) - 3, i64::MIN as i128];
+    for &a in values.iter() {
+        let integer = Integer::from(a);
+        for &b in values.iter() {
+            let check = |result : Integer, expected : i128, op : &str| {
+                assert!(result == Integer::from(expected), "\nEvaluated {} {} {} as {}\n", a, op, b, result);
+            };
+            check(&integer + b, a + b, "+");
+            check(&integer - b, a - b, "-");
+            check(b - &integer, b - a, "r-");
+            check(integer.clone() + b, a + b, "owned +");
+            if let Some(product) = a.checked_mul(b) {
+                check(&integer * b, product, "*");
+                check(b * integer.clone(), product, "r*");
+            }
+            if b != 0 {
+                check(&integer / b, a / b, "/");
+                check(&integer % b, a % b, "%");
+            }
+            if a != 0 {
+                check(1_000_000i64 / &integer, 1_000_000 / a, "r/");
+                check(-1000i32 % integer.clone(), -1000 % a, "r%");
+            }
+
+            let mut assigned = integer.clone();
+            assigned += b;
+            assigned -= 3u32;
+            assigned *= -2i32;
+            check(assigned, (a + b - 3) * -2, "assign");
+        }
+    }
+    assert!(Integer::from(u128::MAX) == &(Integer::from(1) << 128) - 1, "\nEvaluated u128::MAX as {}\n", Integer::from(u128::MAX));
+    assert!(Integer::from(i128::MIN) == -(Integer::from(1) << 127), "\nEvaluated i128::MIN as {}\n", Integer::from(i128::MIN));
+} // End of primitive_ops_match_i128_test
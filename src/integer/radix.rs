@@ -0,0 +1,282 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Conversion between the u64 limb vectors used by Integer and digit strings in radix 2 to 36.
+// Power of two radices map directly onto the bits of the limbs. Other radices work in chunks of
+// the largest power of the radix that fits in a limb, for example 10^19 for decimal. Small values
+// are converted by repeatedly dividing by the chunk. Large values are split in half by a
+// precomputed power chunk^(2^k) and each half is converted recursively, so the expensive
+// divisions are done on balanced operands. Each power keeps its reciprocal, which turns those
+// divisions into multiplications.
+// Parsing works the other way: the chunks are joined in pairs, then pairs of pairs, multiplying
+// the higher half by chunk^(2^k), so large strings also take a few multiplications of their
+// full size rather than one pass over the value per chunk.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::cmp::Ordering;
+use super::{add_assign_limbs, cmp_limbs, significant, div, mul};
+use super::div::Reciprocal;
+use super::{ParseIntegerError, ParseErrorKind};
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+// the digits used for every radix, letters are written in lower case
+const DIGITS : &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+// values with at most this many limbs, or strings with at most this many chunks, are converted
+// one chunk at a time
+const DIVIDE_AND_CONQUER_THRESHOLD : usize = 32;
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// Converts the magnitude into its digits in the radix, without sign or leading zeros.
+// Panics if the radix is not between 2 and 36.
+pub fn to_radix(value: &[u64], radix: u32) -> String {
+    assert!((2..=36).contains(&radix), "radix must be between 2 and 36, was {}", radix);
+    let value = significant(value);
+    if radix.is_power_of_two() {
+        return to_power_of_two_radix(value, radix.trailing_zeros());
+    }
+
+    let chunk = Chunk::new(radix);
+    let mut digits = Vec::with_capacity(value.len() * 64);
+    if value.len() <= DIVIDE_AND_CONQUER_THRESHOLD {
+        write_chunks(value, &chunk, 0, &mut digits);
+    } else {
+        // powers[k] = chunk^(2^k), stop once the next power could not split the value
+        let mut powers = vec![vec![chunk.value]];
+        while powers[powers.len()-1].len() * 2 <= value.len() {
+            let last = &powers[powers.len()-1];
+            let square = mul::mul(last, last);
+            powers.push(square);
+        }
+        let reciprocals : Vec<Reciprocal> = powers.iter().map(|power| Reciprocal::new(power)).collect();
+        write_divide_and_conquer(value, &chunk, &powers, &reciprocals, powers.len() - 1, 0, &mut digits);
+    }
+    String::from_utf8(digits).unwrap()
+} // End of to_radix function
+
+// Parses a string of digits in the radix and underscores into a magnitude.
+// The string must start with a digit, any sign has already been removed by the caller.
+// Panics if the radix is not between 2 and 36.
+pub fn from_radix(digits: &str, radix: u32) -> Result<Vec<u64>, ParseIntegerError> {
+    assert!((2..=36).contains(&radix), "radix must be between 2 and 36, was {}", radix);
+    match digits.chars().next() {
+        None => return Err(ParseIntegerError { kind: ParseErrorKind::Empty }),
+        Some('_') => return Err(ParseIntegerError { kind: ParseErrorKind::InvalidDigit }),
+        _ => {}
+    }
+
+    // accumulate a chunk worth of digits in a u64, the last chunk may be short
+    let chunk = Chunk::new(radix);
+    let mut chunks = Vec::with_capacity(digits.len() / chunk.digits + 1);
+    let (mut current, mut current_len) = (0u64, 0usize);
+    for c in digits.chars() {
+        if c == '_' {
+            continue;
+        }
+        let digit = match c.to_digit(radix) {
+            Some(digit) => digit as u64,
+            None => return Err(ParseIntegerError { kind: ParseErrorKind::InvalidDigit }),
+        };
+        current = current * radix as u64 + digit;
+        current_len += 1;
+        if current_len == chunk.digits {
+            chunks.push(current);
+            current = 0;
+            current_len = 0;
+        }
+    }
+    let mut value = join_chunks(&chunks, &chunk);
+    if current_len > 0 {
+        mul::mul_add_limb(&mut value, (radix as u64).pow(current_len as u32), current);
+    }
+    Ok(value)
+} // End of from_radix function
+
+// Joins whole chunks, most significant first, into a magnitude.
+fn join_chunks(chunks: &[u64], chunk: &Chunk) -> Vec<u64> {
+    if chunks.len() <= DIVIDE_AND_CONQUER_THRESHOLD {
+        let mut value = vec![0u64];
+        for current in chunks {
+            mul::mul_add_limb(&mut value, chunk.value, *current);
+        }
+        return value;
+    }
+
+    // powers[k] = chunk^(2^k), the last power is the weight of the lowest 2^k chunks
+    let mut powers = vec![vec![chunk.value]];
+    while 2 << (powers.len() - 1) < chunks.len() {
+        let last = &powers[powers.len()-1];
+        let square = mul::mul(last, last);
+        powers.push(square);
+    }
+    join_divide_and_conquer(chunks, chunk, &powers)
+} // End of join_chunks function
+
+// Joins the chunks as high * chunk^(2^k) + low, where low is the lowest 2^k chunks and high is
+// no longer than low.
+fn join_divide_and_conquer(chunks: &[u64], chunk: &Chunk, powers: &[Vec<u64>]) -> Vec<u64> {
+    if chunks.len() <= DIVIDE_AND_CONQUER_THRESHOLD {
+        return join_chunks(chunks, chunk);
+    }
+    let mut k = powers.len() - 1;
+    while 1 << k >= chunks.len() {
+        k -= 1;
+    }
+    let (high, low) = chunks.split_at(chunks.len() - (1 << k));
+    let mut value = mul::mul(&join_divide_and_conquer(high, chunk, powers), &powers[k]);
+    add_assign_limbs(&mut value, &join_divide_and_conquer(low, chunk, powers));
+    value
+} // End of join_divide_and_conquer function
+
+// Writes the digits of a power of two radix straight from the bits, most significant first.
+fn to_power_of_two_radix(value: &[u64], bits: u32) -> String {
+    let total_bits = value.len() * 64 - value[value.len()-1].leading_zeros() as usize;
+    let bits = bits as usize;
+    let digit_count = total_bits.div_ceil(bits).max(1);
+    let mask = (1u64 << bits) - 1;
+
+    let mut digits = Vec::with_capacity(digit_count);
+    for i in (0..digit_count).rev() {
+        // a digit may straddle two limbs when the radix is 8 or 32
+        let position = i * bits;
+        let (limb, offset) = (position / 64, position % 64);
+        let mut digit = value[limb] >> offset;
+        if offset + bits > 64 && limb + 1 < value.len() {
+            digit |= value[limb+1] << (64 - offset);
+        }
+        digits.push(DIGITS[(digit & mask) as usize]);
+    }
+    String::from_utf8(digits).unwrap()
+} // End of to_power_of_two_radix function
+
+// Writes the value split at powers[k] or lower, left padding the output with zeros to pad digits.
+fn write_divide_and_conquer(value: &[u64], chunk: &Chunk, powers: &[Vec<u64>], reciprocals: &[Reciprocal], mut k: usize, pad: usize, digits: &mut Vec<u8>) {
+    if value.len() <= DIVIDE_AND_CONQUER_THRESHOLD {
+        write_chunks(value, chunk, pad, digits);
+        return;
+    }
+    while k > 0 && cmp_limbs(&powers[k], value) == Ordering::Greater {
+        k -= 1;
+    }
+
+    // the low half always has exactly digits * 2^k digits, the high half takes whatever padding is left
+    let (high, low) = reciprocals[k].div_rem(value);
+    let low_digits = chunk.digits << k;
+    write_divide_and_conquer(&high, chunk, powers, reciprocals, k, pad.saturating_sub(low_digits), digits);
+    write_divide_and_conquer(&low, chunk, powers, reciprocals, k, low_digits, digits);
+} // End of write_divide_and_conquer function
+
+// Writes the value by dividing off one chunk at a time, left padding with zeros to pad digits.
+fn write_chunks(value: &[u64], chunk: &Chunk, pad: usize, digits: &mut Vec<u8>) {
+    let mut chunks = Vec::with_capacity(value.len() + 1);
+    let mut rest = significant(value).to_vec();
+    loop {
+        let (quotient, remainder) = div::div_rem_limb(&rest, chunk.value);
+        chunks.push(remainder);
+        if quotient == [0] {
+            break;
+        }
+        rest = quotient;
+    }
+
+    // every chunk but the most significant is written with its leading zeros
+    let mut text = Vec::with_capacity(chunks.len() * chunk.digits);
+    for (i, current) in chunks.iter().rev().enumerate() {
+        let start = text.len();
+        let mut current = *current;
+        loop {
+            text.push(DIGITS[(current % chunk.radix) as usize]);
+            current /= chunk.radix;
+            if current == 0 && (i == 0 || text.len() - start == chunk.digits) {
+                break;
+            }
+        }
+        text[start..].reverse();
+    }
+    for _ in text.len()..pad {
+        digits.push(b'0');
+    }
+    digits.extend_from_slice(&text);
+} // End of write_chunks function
+
+/* ============================================================================================ */
+/*     Chunk                                                                                    */
+/* ============================================================================================ */
+
+// The largest power of a radix that fits in a single limb.
+struct Chunk {
+    radix : u64,    // the radix the digits are written in
+    digits : usize, // the number of digits in one chunk
+    value : u64,    // radix^digits
+}
+
+impl Chunk {
+    fn new(radix: u32) -> Chunk {
+        let radix = radix as u64;
+        let (mut digits, mut value) = (1, radix);
+        while let Some(next) = value.checked_mul(radix) {
+            value = next;
+            digits += 1;
+        }
+        Chunk { radix, digits, value }
+    } // End of new function
+} // End of Chunk implementation
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that the divide and conquer conversions agree with converting one chunk at a time, in
+// both directions, for values well above the threshold.
+#[test]
+fn divide_and_conquer_test() {
+    let mut state : u64 = 0x9E37_79B9_7F4A_7C15;
+    let value : Vec<u64> = (0..700).map(|_| {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        state
+    }).collect();
+    for &radix in [3, 10, 36].iter() {
+        let chunk = Chunk::new(radix);
+        let mut expected = Vec::new();
+        write_chunks(&value, &chunk, 0, &mut expected);
+        let digits = to_radix(&value, radix);
+        assert!(digits.as_bytes() == &expected[..], "\nEvaluated the radix {} digits of a 700 limb value wrongly\n", radix);
+        let parsed = from_radix(&digits, radix).unwrap();
+        assert!(parsed == value, "\nEvaluated the radix {} round trip of a 700 limb value wrongly\n", radix);
+    }
+} // End of divide_and_conquer_test
+
+// Times decimal conversion in both directions at 1024 and 8192 limbs. With subquadratic
+// multiplication eight times the limbs takes less than 48 times as long, where a quadratic
+// conversion takes 64 times. Wall clock ratios depend on the machine and its load, so this is a
+// benchmark: run it with cargo test --release -- --ignored.
+#[test]
+#[ignore]
+fn conversion_scaling_test() {
+    use std::time::{Duration, Instant};
+    let time = |limbs: usize| -> (Duration, Duration) {
+        let mut value = vec![0u64; limbs];
+        value[limbs-1] = 1 << 63;
+        let (mut write, mut parse) = (Duration::MAX, Duration::MAX);
+        for _ in 0..3 {
+            let start = Instant::now();
+            let digits = to_radix(&value, 10);
+            write = write.min(start.elapsed());
+            let start = Instant::now();
+            let parsed = from_radix(&digits, 10).unwrap();
+            parse = parse.min(start.elapsed());
+            assert!(parsed == value, "\nEvaluated the round trip of 2^{} wrongly\n", limbs * 64 - 1);
+        }
+        (write, parse)
+    };
+    let (small, large) = (time(1024), time(8192));
+    assert!(large.0 < small.0 * 48, "\nEvaluated the decimal digits of 8x the limbs in {:?} against {:?}\n", large.0, small.0);
+    assert!(large.1 < small.1 * 48, "\nEvaluated the parse of 8x the digits in {:?} against {:?}\n", large.1, small.1);
+} // End of conversion_scaling_test
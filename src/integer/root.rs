@@ -0,0 +1,189 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// Exact integer roots and perfect power detection.
+// The floor of the kth root of n is found with Newton's iteration x = ((k-1)x + n / x^(k-1)) / k
+// in Integer arithmetic. Started from any value at or above the root the iterates decrease
+// strictly until they reach it, so the first step that fails to decrease marks the answer.
+// The start is taken from a float root of the leading 64 bits, which is already correct to
+// about 50 bits, so each of the few remaining steps doubles the number of correct bits.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::{Integer, ArithmeticError};
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns the floor of the square root, or an error if the number is negative.
+    pub fn sqrt(&self) -> Result<Integer, ArithmeticError> {
+        self.nth_root(2)
+    } // End of sqrt function
+
+    // returns (s, r) where s is the floor of the square root and r = self - s^2 is the
+    // remainder, or an error if the number is negative.
+    pub fn sqrt_rem(&self) -> Result<(Integer, Integer), ArithmeticError> {
+        let root = self.sqrt()?;
+        let remainder = self - &root.square();
+        Ok((root, remainder))
+    } // End of sqrt_rem function
+
+    // returns the kth root rounded toward zero, so negative numbers have odd roots such as
+    // nth_root(-30, 3) = -3. Even roots of negative numbers and the zeroth root are errors.
+    pub fn nth_root(&self, k : u32) -> Result<Integer, ArithmeticError> {
+        if k == 0 {
+            return Err(ArithmeticError::ZeroRoot);
+        }
+        if self.negative && k.is_multiple_of(2) {
+            return Err(ArithmeticError::NegativeRoot);
+        }
+        let root = root(&self.abs(), k);
+        Ok(if self.negative {-root} else {root})
+    } // End of nth_root function
+
+    // returns true if the number is the square of an Integer.
+    // Most non-squares are rejected by their residues before any root is taken.
+    pub fn is_perfect_square(&self) -> bool {
+        if self.negative {
+            return false;
+        }
+        // bit r is set when r is a square mod 64
+        if (0x0202_0212_0203_0213u64 >> (self.value[0] & 63)) & 1 == 0 {
+            return false;
+        }
+        let residue = (self % 45_045u64).to_u64_wrapping();
+        if !is_square_mod(residue % 63, 63) || !is_square_mod(residue % 65, 65) || !is_square_mod(residue % 11, 11) {
+            return false;
+        }
+        root(self, 2).square() == *self
+    } // End of is_perfect_square function
+
+    // returns true if the number is a^k for some Integer a and k >= 2, counting 0, 1 and -1.
+    pub fn is_perfect_power(&self) -> bool {
+        self.perfect_power().is_some()
+    } // End of is_perfect_power function
+
+    // returns (a, k) with self = a^k and k >= 2 as large as possible, or None if the number
+    // is not a perfect power. Negative numbers only have odd exponents, and 0, 1 and -1, which
+    // are powers of themselves for every exponent, return k = 2, 2 and 3.
+    pub fn perfect_power(&self) -> Option<(Integer, u32)> {
+        if self.value == [0] || self.value == [1] {
+            return Some((self.clone(), if self.negative {3} else {2}));
+        }
+        let magnitude = self.abs();
+
+        // any exponent has a prime factor p, and a^k = (a^(k/p))^p
+        for p in 2..=magnitude.bit_length() as u32 {
+            if (self.negative && p == 2) || !is_small_prime(p) {
+                continue;
+            }
+            if p == 2 && !magnitude.is_perfect_square() {
+                continue;
+            }
+            let root = root(&magnitude, p);
+            if root.pow_u32(p) == magnitude {
+                let base = if self.negative {-root} else {root};
+                return Some(match base.perfect_power() {
+                    Some((base, exponent)) => (base, exponent * p),
+                    None => (base, p),
+                });
+            }
+        }
+        None
+    } // End of perfect_power function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns the floor of the kth root of a non-negative number, k >= 1.
+fn root(n : &Integer, k : u32) -> Integer {
+    let bits = n.bit_length();
+    if k == 1 || bits <= 1 {
+        return n.clone();
+    }
+    if bits <= k as u64 {
+        // n < 2^k, so the root is 1
+        return Integer::from(1);
+    }
+
+    // n >> shift has at most 64 bits and shift is a multiple of k, so the kth root of
+    // (n >> shift) + 1, rounded up, times 2^(shift/k) is at or above the root of n
+    let shift = bits.saturating_sub(64).div_ceil(k as u64) * k as u64;
+    let top = (n >> shift).to_u64_wrapping();
+    let estimate = (top as f64 + 1.0).powf(1.0 / k as f64) as u64 + 2;
+    let mut x = &Integer::from(estimate) << (shift / k as u64);
+
+    loop {
+        let next = (&x * (k - 1) + n / &x.pow_u32(k - 1)) / k;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+} // End of root function
+
+// returns true if the residue is a square modulo m.
+fn is_square_mod(residue : u64, m : u64) -> bool {
+    (0..m).any(|x| x * x % m == residue)
+} // End of is_square_mod function
+
+// returns true if the small exponent is prime.
+fn is_small_prime(n : u32) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+} // End of is_small_prime function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests that roots match a brute force search on small values, including negative odd roots.
+#[test]
+fn small_roots_test() {
+    for n in -300i64..3000 {
+        let integer = Integer::from(n);
+        for k in 1..6u32 {
+            if n < 0 && k % 2 == 0 {
+                assert!(integer.nth_root(k) == Err(ArithmeticError::NegativeRoot), "\nEvaluated root {} of {} as not an error\n", k, n);
+                continue;
+            }
+            let magnitude = (0i64..).take_while(|r| r.pow(k) <= n.abs()).last().unwrap();
+            let expected = if n < 0 {-magnitude} else {magnitude};
+            assert!(integer.nth_root(k) == Ok(Integer::from(expected)), "\nEvaluated root {} of {} as {:?}\n", k, n, integer.nth_root(k));
+        }
+
+        let is_square = n >= 0 && (0i64..).take_while(|r| r * r <= n).any(|r| r * r == n);
+        assert!(integer.is_perfect_square() == is_square, "\nEvaluated {} as a square: {}\n", n, integer.is_perfect_square());
+        let is_power = n.abs() <= 1 || (2..12u32).any(|k| (n >= 0 || k % 2 == 1) && (0i64..).take_while(|r| r.pow(k) <= n.abs()).any(|r| r.pow(k) == n.abs()));
+        assert!(integer.is_perfect_power() == is_power, "\nEvaluated {} as a perfect power: {}\n", n, integer.is_perfect_power());
+    }
+    assert!(Integer::from(8).nth_root(0) == Err(ArithmeticError::ZeroRoot), "\nEvaluated the zeroth root of 8 as not an error\n");
+} // End of small_roots_test
+
+// Tests exact roots, remainders and perfect powers of multi-limb values.
+#[test]
+fn large_roots_test() {
+    let base = "123456789012345678901234567890123".parse::<Integer>().unwrap();
+    for k in 2..8u32 {
+        let power = base.pow_u32(k);
+        assert!(power.nth_root(k) == Ok(base.clone()), "\nEvaluated root {} of base^{} incorrectly\n", k, k);
+        assert!((&power - 1u32).nth_root(k) == Ok(&base - 1u32), "\nEvaluated root {} of base^{} - 1 incorrectly\n", k, k);
+        assert!((&power + 1u32).nth_root(k) == Ok(base.clone()), "\nEvaluated root {} of base^{} + 1 incorrectly\n", k, k);
+    }
+
+    let n = Integer::from(10).pow_u32(101) + 12345u32;
+    let (root, remainder) = n.sqrt_rem().unwrap();
+    assert!(&root.square() + &remainder == n && remainder <= &root * 2u32, "\nEvaluated sqrt_rem of 10^101 + 12345 as {}, {}\n", root, remainder);
+    assert!(Integer::from(-4).sqrt_rem().is_err(), "\nEvaluated sqrt_rem of -4 as not an error\n");
+
+    let sixth = Integer::from(6).pow_u32(2).pow_u32(3).pow_u32(5);
+    assert!(sixth.perfect_power() == Some((Integer::from(6), 30)), "\nEvaluated 6^30 as {:?}\n", sixth.perfect_power());
+    let negative = -base.pow_u32(15);
+    assert!(negative.perfect_power() == Some((-&base, 15)), "\nEvaluated -base^15 as {:?}\n", negative.perfect_power());
+    assert!(!(-base.pow_u32(4)).is_perfect_power(), "\nEvaluated -base^4 as a perfect power\n");
+    assert!(!(&base.pow_u32(6) * 2u32).is_perfect_power(), "\nEvaluated 2 base^6 as a perfect power\n");
+    assert!(!(&base.square() + 1u32).is_perfect_square(), "\nEvaluated base^2 + 1 as a square\n");
+} // End of large_roots_test
@@ -0,0 +1,139 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// The Legendre, Jacobi and Kronecker symbols, each 1, -1 or 0.
+// The Legendre symbol (a/p) for an odd prime p tells whether a is a square mod p. The Jacobi
+// symbol extends it multiplicatively to odd positive n, and the Kronecker symbol to every n by
+// defining (a/2), (a/-1) and (a/0). All three are computed with the binary Jacobi algorithm,
+// which uses reciprocity to swap the arguments and so never needs the factors of n.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use super::{Integer, ArithmeticError};
+
+/* ============================================================================================ */
+/*     Methods                                                                                  */
+/* ============================================================================================ */
+impl Integer {
+    // returns the Jacobi symbol (self/n), or an error unless n is odd and positive.
+    pub fn jacobi(&self, n : &Integer) -> Result<i32, ArithmeticError> {
+        if n.negative || n.value[0] & 1 == 0 {
+            return Err(ArithmeticError::InvalidModulus);
+        }
+        Ok(jacobi(self, n))
+    } // End of jacobi function
+
+    // returns the Legendre symbol (self/p): 0 if p divides self, 1 if self is a non-zero square
+    // mod p and -1 otherwise. Returns an error unless p is an odd prime.
+    pub fn legendre(&self, p : &Integer) -> Result<i32, ArithmeticError> {
+        if p.negative || p.value[0] & 1 == 0 {
+            return Err(ArithmeticError::InvalidModulus);
+        }
+        if !p.is_probable_prime(0) {
+            return Err(ArithmeticError::CompositeModulus);
+        }
+        Ok(jacobi(self, p))
+    } // End of legendre function
+
+    // returns the Kronecker symbol (self/n), which is defined for every n.
+    pub fn kronecker(&self, n : &Integer) -> i32 {
+        if n.is_zero() {
+            return if self.abs() == Integer::from(1) {1} else {0};
+        }
+        // (a/-1) = -1 for negative a
+        let mut result = if n.negative && self.negative {-1} else {1};
+        let twos = n.trailing_zeros().unwrap();
+        if twos > 0 {
+            // (a/2) is 0 for even a, 1 for a = +-1 mod 8 and -1 for a = +-3 mod 8
+            match self.value[0] & 7 {
+                0 | 2 | 4 | 6 => return 0,
+                3 | 5 if twos % 2 == 1 => result = -result,
+                _ => {}
+            }
+        }
+        result * jacobi(self, &(&n.abs() >> twos))
+    } // End of kronecker function
+} // End of Integer Implementation
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// returns the Jacobi symbol (a/n) for an odd positive n.
+fn jacobi(a : &Integer, n : &Integer) -> i32 {
+    let mut a = a % n;
+    if a.negative {
+        a += n;
+    }
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        // (2/n) = -1 exactly when n = 3 or 5 mod 8
+        let twos = a.trailing_zeros().unwrap();
+        a = &a >> twos;
+        if twos % 2 == 1 && matches!(n.value[0] & 7, 3 | 5) {
+            result = -result;
+        }
+        // quadratic reciprocity flips the sign when both are 3 mod 4
+        std::mem::swap(&mut a, &mut n);
+        if a.value[0] & 3 == 3 && n.value[0] & 3 == 3 {
+            result = -result;
+        }
+        a = &a % &n;
+    }
+    if n == Integer::from(1) {result} else {0}
+} // End of jacobi function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the symbols against Euler's criterion and the definitions on small values, with
+// negative arguments on both sides.
+#[test]
+fn symbols_test() {
+    // Euler's criterion a^((p-1)/2) mod p gives the Legendre symbol
+    let legendre = |a : i64, p : i64| -> i32 {
+        let power = (0..(p - 1) / 2).fold(1, |x, _| x * a.rem_euclid(p) % p);
+        if power == p - 1 {-1} else {power as i32}
+    };
+    let odd_primes = [3i64, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for a in -60i64..60 {
+        let a_integer = Integer::from(a);
+        for &p in odd_primes.iter() {
+            let expected = legendre(a, p);
+            assert!(a_integer.legendre(&Integer::from(p)) == Ok(expected), "\nEvaluated ({}/{}) as {:?}\n", a, p, a_integer.legendre(&Integer::from(p)));
+        }
+        // the Jacobi symbol is multiplicative in n, and the Kronecker symbol extends it
+        for n in -40i64..40 {
+            let mut expected = if n < 0 && a < 0 {-1} else {1};
+            let mut m = n.abs();
+            if m == 0 {
+                expected = if a.abs() == 1 {1} else {0};
+            }
+            while m > 0 && m % 2 == 0 {
+                expected *= match a.rem_euclid(8) {1 | 7 => 1, 3 | 5 => -1, _ => 0};
+                m /= 2;
+            }
+            for &p in odd_primes.iter() {
+                while m > 1 && m % p == 0 {
+                    expected *= legendre(a, p);
+                    m /= p;
+                }
+            }
+            let n_integer = Integer::from(n);
+            assert!(a_integer.kronecker(&n_integer) == expected, "\nEvaluated the Kronecker symbol ({}/{}) as {}\n", a, n, a_integer.kronecker(&n_integer));
+            if n > 0 && n % 2 == 1 {
+                assert!(a_integer.jacobi(&n_integer) == Ok(expected), "\nEvaluated the Jacobi symbol ({}/{}) as {:?}\n", a, n, a_integer.jacobi(&n_integer));
+            } else {
+                assert!(a_integer.jacobi(&n_integer) == Err(ArithmeticError::InvalidModulus), "\nEvaluated the Jacobi symbol ({}/{}) as not an error\n", a, n);
+            }
+        }
+    }
+    assert!(Integer::from(2).legendre(&Integer::from(15)) == Err(ArithmeticError::CompositeModulus), "\nEvaluated (2/15) as a Legendre symbol\n");
+
+    // 2 is a square mod the prime 2^127 - 1, which is 7 mod 8, and 3 is not
+    let p = (Integer::from(1) << 127) - 1u32;
+    assert!(Integer::from(2).legendre(&p) == Ok(1) && Integer::from(3).legendre(&p) == Ok(-1), "\nEvaluated the symbols mod 2^127 - 1 incorrectly\n");
+} // End of symbols_test
@@ -0,0 +1,308 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// This Module splits a line of calculator input into tokens.
+// Each token records the columns it covers so that syntax errors, whether found here or by the
+// parser, can point at the exact place in the line they came from. Columns count characters
+// from 0, and whitespace only separates tokens.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::fmt;
+use std::error::Error;
+use std::iter::Peekable;
+use std::str::Chars;
+use integer::Integer;
+
+/* ============================================================================================ */
+/*     Tokens                                                                                   */
+/* ============================================================================================ */
+
+// The columns [start, end) of the input that a token or expression covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start : usize,
+    pub end : usize,
+}
+
+impl Span {
+    // returns the span covering both spans and everything between them.
+    pub fn to(self, other : Span) -> Span {
+        Span {start: self.start.min(other.start), end: self.end.max(other.end)}
+    } // End of to function
+
+    // returns the source line with a row of carets under the columns of the span.
+    pub fn highlight(self, source : &str) -> String {
+        let width = (self.end - self.start).max(1);
+        format!("{}\n{}{}", source, " ".repeat(self.start), "^".repeat(width))
+    } // End of highlight function
+} // End of Span implementation
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Number(Integer),    // a decimal literal, which may separate digits with underscores
+    Identifier(String), // a name of letters, digits and underscores, not starting with a digit
+    If,                 // the keyword if
+    Then,               // the keyword then
+    Else,               // the keyword else
+    Plus,               // +
+    Minus,              // -
+    Star,               // *
+    Slash,              // /
+    Percent,            // %
+    Caret,              // ^
+    LeftParen,          // (
+    RightParen,         // )
+    Comma,              // ,
+    Less,               // <
+    LessEqual,          // <=
+    Greater,            // >
+    GreaterEqual,       // >=
+    EqualEqual,         // ==
+    NotEqual,           // !=
+    Equals,             // =
+    PlusEquals,         // +=
+    MinusEquals,        // -=
+    StarEquals,         // *=
+    SlashEquals,        // /=
+    PercentEquals,      // %=
+    CaretEquals,        // ^=
+    End,                // the end of the input
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind : TokenKind,
+    pub span : Span,
+}
+
+/* ============================================================================================ */
+/*     Lexer                                                                                    */
+/* ============================================================================================ */
+
+// Produces the tokens of a line one at a time, ending with a single End token.
+pub struct Lexer<'a> {
+    chars : Peekable<Chars<'a>>,
+    column : usize,     // the column of the next character
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(source : &'a str) -> Lexer<'a> {
+        Lexer {chars: source.chars().peekable(), column: 0}
+    } // End of new function
+
+    // returns the next token, or an error at the first character that can not start one.
+    pub fn next_token(&mut self) -> Result<Token, SyntaxError> {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.bump();
+        }
+        let start = self.column;
+        let c = match self.bump() {
+            Some(c) => c,
+            None => return Ok(Token {kind: TokenKind::End, span: Span {start, end: start}}),
+        };
+        let kind = match c {
+            '+' => self.operator(TokenKind::Plus, TokenKind::PlusEquals),
+            '-' => self.operator(TokenKind::Minus, TokenKind::MinusEquals),
+            '*' => self.operator(TokenKind::Star, TokenKind::StarEquals),
+            '/' => self.operator(TokenKind::Slash, TokenKind::SlashEquals),
+            '%' => self.operator(TokenKind::Percent, TokenKind::PercentEquals),
+            '^' => self.operator(TokenKind::Caret, TokenKind::CaretEquals),
+            '(' => TokenKind::LeftParen,
+            ')' => TokenKind::RightParen,
+            ',' => TokenKind::Comma,
+            '<' => self.operator(TokenKind::Less, TokenKind::LessEqual),
+            '>' => self.operator(TokenKind::Greater, TokenKind::GreaterEqual),
+            '=' => self.operator(TokenKind::Equals, TokenKind::EqualEqual),
+            '!' if self.chars.peek() == Some(&'=') => {
+                self.bump();
+                TokenKind::NotEqual
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(&c) = self.chars.peek() {
+                    if !c.is_alphanumeric() && c != '_' {
+                        break;
+                    }
+                    name.push(c);
+                    self.bump();
+                }
+                match name.as_str() {
+                    "if" => TokenKind::If,
+                    "then" => TokenKind::Then,
+                    "else" => TokenKind::Else,
+                    _ => TokenKind::Identifier(name),
+                }
+            }
+            '0'..='9' => {
+                let mut digits = c.to_string();
+                while let Some(&c) = self.chars.peek() {
+                    if !c.is_ascii_digit() && c != '_' {
+                        break;
+                    }
+                    digits.push(c);
+                    self.bump();
+                }
+                let span = Span {start, end: self.column};
+                // a letter straight after the digits is a typo rather than a new token
+                if let Some(&c) = self.chars.peek() {
+                    if c.is_alphanumeric() {
+                        return Err(SyntaxError::new(SyntaxErrorKind::InvalidNumber, span.to(Span {start: self.column, end: self.column + 1})));
+                    }
+                }
+                match digits.parse() {
+                    Ok(number) => TokenKind::Number(number),
+                    Err(_) => return Err(SyntaxError::new(SyntaxErrorKind::InvalidNumber, span)),
+                }
+            }
+            c => return Err(SyntaxError::new(SyntaxErrorKind::UnexpectedCharacter(c), Span {start, end: start + 1})),
+        };
+        Ok(Token {kind, span: Span {start, end: self.column}})
+    } // End of next_token function
+
+    // returns the second kind if the character is followed by =, as in += or <=, otherwise the first.
+    fn operator(&mut self, single : TokenKind, with_equals : TokenKind) -> TokenKind {
+        if self.chars.peek() == Some(&'=') {
+            self.bump();
+            return with_equals;
+        }
+        single
+    } // End of operator function
+
+    // consumes the next character.
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c.is_some() {
+            self.column += 1;
+        }
+        c
+    } // End of bump function
+} // End of Lexer implementation
+
+// returns every token of the source, ending with End.
+pub fn tokenize(source : &str) -> Result<Vec<Token>, SyntaxError> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+    loop {
+        let token = lexer.next_token()?;
+        let end = token.kind == TokenKind::End;
+        tokens.push(token);
+        if end {
+            return Ok(tokens);
+        }
+    }
+} // End of tokenize function
+
+/* ============================================================================================ */
+/*     Errors                                                                                   */
+/* ============================================================================================ */
+
+// The reasons a line can fail to tokenize or parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxErrorKind {
+    UnexpectedCharacter(char),  // a character that does not start any token
+    InvalidNumber,              // digits run into letters, as in 12ab
+    ExpectedExpression,         // an operator or the end of the line where a value should be
+    UnclosedParen,              // a ( with no matching )
+    UnexpectedToken,            // a token after a complete expression, such as an extra )
+    InvalidAssignment,          // an assignment to something other than a variable, as in 1 = 2
+    InvalidParameter,           // a parameter of a definition that is not a name, as in f(1) = 2
+    DuplicateParameter,         // a parameter named twice, as in f(x, x) = x
+    ExpectedThen,               // an if condition not followed by then
+    ExpectedElse,               // an if branch not followed by else
+    TooDeep,                    // an expression nested more than the parser allows
+}
+
+// A syntax error and the columns it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    kind : SyntaxErrorKind,
+    span : Span,
+}
+
+impl SyntaxError {
+    pub fn new(kind : SyntaxErrorKind, span : Span) -> SyntaxError {
+        SyntaxError {kind, span}
+    } // End of new function
+
+    // returns the reason for the error.
+    pub fn kind(&self) -> &SyntaxErrorKind {
+        &self.kind
+    } // End of kind function
+
+    // returns the columns the error points at.
+    pub fn span(&self) -> Span {
+        self.span
+    } // End of span function
+} // End of SyntaxError implementation
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "syntax error at column {}: ", self.span.start + 1)?;
+        match self.kind {
+            SyntaxErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character '{}'", c),
+            SyntaxErrorKind::InvalidNumber => write!(f, "invalid number"),
+            SyntaxErrorKind::ExpectedExpression => write!(f, "expected an expression"),
+            SyntaxErrorKind::UnclosedParen => write!(f, "unclosed parenthesis"),
+            SyntaxErrorKind::UnexpectedToken => write!(f, "unexpected token after expression"),
+            SyntaxErrorKind::InvalidAssignment => write!(f, "can only assign to a variable"),
+            SyntaxErrorKind::InvalidParameter => write!(f, "a parameter must be a name"),
+            SyntaxErrorKind::DuplicateParameter => write!(f, "duplicate parameter name"),
+            SyntaxErrorKind::ExpectedThen => write!(f, "expected then"),
+            SyntaxErrorKind::ExpectedElse => write!(f, "expected else"),
+            SyntaxErrorKind::TooDeep => write!(f, "expression nested too deeply"),
+        }
+    } // End of fmt function
+} // End of fmt::Display implementation
+
+impl Error for SyntaxError {}
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the kinds and columns of the tokens of a line, and the errors for bad characters.
+#[test]
+fn tokenize_test() {
+    let tokens = tokenize(" 12 +(3_000*-4) ^ 5 % 6/7").unwrap();
+    let kinds : Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+    let number = |n : i64| TokenKind::Number(Integer::from(n));
+    let expected = vec![
+        number(12), TokenKind::Plus, TokenKind::LeftParen, number(3000), TokenKind::Star, TokenKind::Minus,
+        number(4), TokenKind::RightParen, TokenKind::Caret, number(5), TokenKind::Percent, number(6),
+        TokenKind::Slash, number(7), TokenKind::End,
+    ];
+    assert!(kinds == expected, "\nEvaluated the tokens as {:?}\n", kinds);
+    assert!(tokens[0].span == Span {start: 1, end: 3} && tokens[3].span == Span {start: 6, end: 11}, "\nEvaluated the spans as {:?}\n", tokens);
+    assert!(tokens[14].span == Span {start: 25, end: 25}, "\nEvaluated the end span as {:?}\n", tokens[14].span);
+
+    let tokens = tokenize("x_1 += _ ^= 2 = y").unwrap();
+    let kinds : Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+    let name = |name : &str| TokenKind::Identifier(name.to_string());
+    let expected = vec![
+        name("x_1"), TokenKind::PlusEquals, name("_"), TokenKind::CaretEquals, number(2), TokenKind::Equals, name("y"), TokenKind::End,
+    ];
+    assert!(kinds == expected, "\nEvaluated the tokens as {:?}\n", kinds);
+    assert!(tokens[1].span == Span {start: 4, end: 6}, "\nEvaluated the span of += as {:?}\n", tokens[1].span);
+
+    let tokens = tokenize("if a<=b then f(a, b) else a != b == (c >= 0) > 1 < 2").unwrap();
+    let kinds : Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+    let expected = vec![
+        TokenKind::If, name("a"), TokenKind::LessEqual, name("b"), TokenKind::Then, name("f"), TokenKind::LeftParen, name("a"),
+        TokenKind::Comma, name("b"), TokenKind::RightParen, TokenKind::Else, name("a"), TokenKind::NotEqual, name("b"),
+        TokenKind::EqualEqual, TokenKind::LeftParen, name("c"), TokenKind::GreaterEqual, number(0), TokenKind::RightParen,
+        TokenKind::Greater, number(1), TokenKind::Less, number(2), TokenKind::End,
+    ];
+    assert!(kinds == expected, "\nEvaluated the tokens as {:?}\n", kinds);
+
+    let error = tokenize("1 + $").unwrap_err();
+    assert!(*error.kind() == SyntaxErrorKind::UnexpectedCharacter('$') && error.span() == Span {start: 4, end: 5}, "\nEvaluated the error for $ as {:?}\n", error);
+    assert!(error.to_string() == "syntax error at column 5: unexpected character '$'", "\nEvaluated the message as {}\n", error);
+    assert!(error.span().highlight("1 + $") == "1 + $\n    ^", "\nEvaluated the highlight as {:?}\n", error.span().highlight("1 + $"));
+    let error = tokenize("1 ! 2").unwrap_err();
+    assert!(*error.kind() == SyntaxErrorKind::UnexpectedCharacter('!'), "\nEvaluated the error for ! as {:?}\n", error);
+    let error = tokenize("2 * 12ab").unwrap_err();
+    assert!(*error.kind() == SyntaxErrorKind::InvalidNumber && error.span() == Span {start: 4, end: 7}, "\nEvaluated the error for 12ab as {:?}\n", error);
+    assert!(tokenize("").unwrap().len() == 1, "\nEvaluated the empty line as having tokens\n");
+} // End of tokenize_test
@@ -0,0 +1,7 @@
+pub mod cli;
+pub mod editor;
+pub mod eval;
+pub mod integer;
+pub mod lexer;
+pub mod parser;
+pub mod repl;
@@ -1,5 +1,22 @@
-mod integer;
+extern crate calc;
 
-fn main() {
+use std::env;
+use std::process;
+use std::thread;
+
+use calc::cli;
+use calc::eval::STACK_SIZE;
 
+fn main() {
+    let args : Vec<String> = env::args().skip(1).collect();
+    // run on a thread with room for deeply recursive functions
+    let status = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || cli::run(&args))
+        .map(|handle| handle.join().unwrap_or(cli::EXIT_FAILURE))
+        .unwrap_or_else(|error| {
+            eprintln!("calc: {}", error);
+            cli::EXIT_FAILURE
+        });
+    process::exit(status);
 }
@@ -0,0 +1,439 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// This Module parses a line of calculator input into an expression tree.
+// The parser is a Pratt parser: each binary operator has a left and a right binding power, and
+// an operand keeps absorbing operators whose left power is above the power it was called with.
+// Giving ^ a right power below its left power makes it right associative, and giving unary
+// minus a power between * and ^ makes -2^2 = -(2^2) while -2*3 = (-2)*3. Comparisons bind
+// more loosely than any arithmetic, and if-then-else is an operand whose else branch runs to
+// the end of the enclosing expression.
+// Assignments and definitions are statements of their own: the line is parsed as an expression,
+// and if = follows a call such as f(x, y) it is a definition with the rest of the line as the
+// body. After a variable any assignment operator assigns the rest of the line, so x = y = 1
+// assigns right to left.
+// Every node records the columns it came from so later errors can point back into the line.
+// Parsing and evaluating both recurse once per level of the tree, so the depth of the tree is
+// limited: every parenthesis, negation, argument and branch adds a level, as does every operator
+// in a chain like 1+1+...+1, which builds a tree as deep as it is long.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::fmt;
+use std::rc::Rc;
+use integer::Integer;
+use lexer::{Lexer, Token, TokenKind, Span, SyntaxError, SyntaxErrorKind};
+
+/* ============================================================================================ */
+/*     Expressions                                                                              */
+/* ============================================================================================ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+}
+
+impl BinaryOp {
+    // returns the operator a token stands for, if any.
+    fn from_token(kind : &TokenKind) -> Option<BinaryOp> {
+        match *kind {
+            TokenKind::Plus => Some(BinaryOp::Add),
+            TokenKind::Minus => Some(BinaryOp::Sub),
+            TokenKind::Star => Some(BinaryOp::Mul),
+            TokenKind::Slash => Some(BinaryOp::Div),
+            TokenKind::Percent => Some(BinaryOp::Rem),
+            TokenKind::Caret => Some(BinaryOp::Pow),
+            TokenKind::Less => Some(BinaryOp::Less),
+            TokenKind::LessEqual => Some(BinaryOp::LessEqual),
+            TokenKind::Greater => Some(BinaryOp::Greater),
+            TokenKind::GreaterEqual => Some(BinaryOp::GreaterEqual),
+            TokenKind::EqualEqual => Some(BinaryOp::Equal),
+            TokenKind::NotEqual => Some(BinaryOp::NotEqual),
+            _ => None,
+        }
+    } // End of from_token function
+
+    // returns the operator of a compound assignment token, such as Add for +=, if any.
+    fn from_assignment_token(kind : &TokenKind) -> Option<BinaryOp> {
+        match *kind {
+            TokenKind::PlusEquals => Some(BinaryOp::Add),
+            TokenKind::MinusEquals => Some(BinaryOp::Sub),
+            TokenKind::StarEquals => Some(BinaryOp::Mul),
+            TokenKind::SlashEquals => Some(BinaryOp::Div),
+            TokenKind::PercentEquals => Some(BinaryOp::Rem),
+            TokenKind::CaretEquals => Some(BinaryOp::Pow),
+            _ => None,
+        }
+    } // End of from_assignment_token function
+
+    // returns the (left, right) binding powers of the operator.
+    fn binding_power(self) -> (u8, u8) {
+        match self {
+            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual | BinaryOp::Equal | BinaryOp::NotEqual => (5, 6),
+            BinaryOp::Add | BinaryOp::Sub => (10, 11),
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => (20, 21),
+            BinaryOp::Pow => (41, 40),
+        }
+    } // End of binding_power function
+
+    // returns the symbol of the operator.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Pow => "^",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::Equal => "==",
+            BinaryOp::NotEqual => "!=",
+        }
+    } // End of symbol function
+} // End of BinaryOp implementation
+
+// the binding power of unary minus, above * and below ^
+const NEGATE_POWER : u8 = 30;
+
+// the deepest expression parsed
+const MAX_DEPTH : usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprKind {
+    Number(Integer),
+    Variable(String),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    If(Box<Expr>, Box<Expr>, Box<Expr>),            // if condition then value else value
+    Assign(String, Option<BinaryOp>, Box<Expr>),    // x = value, or x op= value with the op
+    Define(String, Rc<Function>),                   // f(x, y) = body
+}
+
+// The parameters and body of a user-defined function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub params : Vec<String>,
+    pub body : Expr,
+}
+
+// An expression and the columns of the input it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expr {
+    pub kind : ExprKind,
+    pub span : Span,
+}
+
+// Writes the expression with every operation in parentheses, so the structure is explicit.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ExprKind::Number(ref number) => write!(f, "{}", number),
+            ExprKind::Variable(ref name) => write!(f, "{}", name),
+            ExprKind::Negate(ref operand) => write!(f, "(-{})", operand),
+            ExprKind::Binary(op, ref left, ref right) => write!(f, "({} {} {})", left, op.symbol(), right),
+            ExprKind::Call(ref name, ref args) => {
+                let args : Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            ExprKind::If(ref condition, ref then, ref otherwise) => write!(f, "(if {} then {} else {})", condition, then, otherwise),
+            ExprKind::Assign(ref name, op, ref value) => write!(f, "({} {}= {})", name, op.map_or("", |op| op.symbol()), value),
+            ExprKind::Define(ref name, ref function) => write!(f, "({}({}) = {})", name, function.params.join(", "), function.body),
+        }
+    } // End of fmt function
+} // End of fmt::Display implementation
+
+/* ============================================================================================ */
+/*     Parser                                                                                   */
+/* ============================================================================================ */
+
+// Parses a token stream with one token of lookahead.
+pub struct Parser<'a> {
+    lexer : Lexer<'a>,
+    current : Token,
+    depth : usize,      // the levels of the tree above the expression being parsed
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source : &'a str) -> Result<Parser<'a>, SyntaxError> {
+        let mut lexer = Lexer::new(source);
+        let current = lexer.next_token()?;
+        Ok(Parser {lexer, current, depth: 0})
+    } // End of new function
+
+    // parses the whole input as one expression, assignment or definition.
+    pub fn parse(&mut self) -> Result<Expr, SyntaxError> {
+        let target = self.expression(0)?;
+        let expr = match target.kind {
+            ExprKind::Call(..) if self.current.kind == TokenKind::Equals => self.definition(target)?,
+            _ => self.assignment(target)?,
+        };
+        if self.current.kind != TokenKind::End {
+            return Err(SyntaxError::new(SyntaxErrorKind::UnexpectedToken, self.current.span));
+        }
+        Ok(expr)
+    } // End of parse function
+
+    // parses the value assigned to the expression if an assignment operator follows it.
+    fn assignment(&mut self, target : Expr) -> Result<Expr, SyntaxError> {
+        let op = match self.current.kind {
+            TokenKind::Equals => None,
+            ref kind => match BinaryOp::from_assignment_token(kind) {
+                Some(op) => Some(op),
+                None => return Ok(target),
+            },
+        };
+        let name = match target.kind {
+            ExprKind::Variable(name) => name,
+            _ => return Err(SyntaxError::new(SyntaxErrorKind::InvalidAssignment, target.span)),
+        };
+        let token = self.advance()?;
+        self.descend(token.span)?;
+        let value = self.expression(0)?;
+        let value = self.assignment(value)?;
+        let span = target.span.to(value.span);
+        Ok(Expr {kind: ExprKind::Assign(name, op, Box::new(value)), span})
+    } // End of assignment function
+
+    // parses the body of a function after the call that names it and its parameters.
+    fn definition(&mut self, target : Expr) -> Result<Expr, SyntaxError> {
+        let (name, args) = match target.kind {
+            ExprKind::Call(name, args) => (name, args),
+            _ => return Err(SyntaxError::new(SyntaxErrorKind::InvalidAssignment, target.span)),
+        };
+        let mut params : Vec<String> = Vec::new();
+        for arg in args {
+            match arg.kind {
+                ExprKind::Variable(ref param) if params.contains(param) => {
+                    return Err(SyntaxError::new(SyntaxErrorKind::DuplicateParameter, arg.span));
+                }
+                ExprKind::Variable(param) => params.push(param),
+                _ => return Err(SyntaxError::new(SyntaxErrorKind::InvalidParameter, arg.span)),
+            }
+        }
+        self.advance()?;
+        let body = self.expression(0)?;
+        let span = target.span.to(body.span);
+        Ok(Expr {kind: ExprKind::Define(name, Rc::new(Function {params, body})), span})
+    } // End of definition function
+
+    // parses an operand followed by every operator that binds more tightly than min_power.
+    fn expression(&mut self, min_power : u8) -> Result<Expr, SyntaxError> {
+        let depth = self.depth;
+        self.descend(self.current.span)?;
+        let mut left = self.operand()?;
+        while let Some(op) = BinaryOp::from_token(&self.current.kind) {
+            let (left_power, right_power) = op.binding_power();
+            if left_power < min_power {
+                break;
+            }
+            // each operator puts everything parsed so far one level further down
+            let token = self.advance()?;
+            self.descend(token.span)?;
+            let right = self.expression(right_power)?;
+            let span = left.span.to(right.span);
+            left = Expr {kind: ExprKind::Binary(op, Box::new(left), Box::new(right)), span};
+        }
+        self.depth = depth;
+        Ok(left)
+    } // End of expression function
+
+    // parses a number, a variable, a call, a negated operand, a conditional or a parenthesized
+    // expression.
+    fn operand(&mut self) -> Result<Expr, SyntaxError> {
+        let token = self.advance()?;
+        match token.kind {
+            TokenKind::Number(number) => Ok(Expr {kind: ExprKind::Number(number), span: token.span}),
+            TokenKind::Identifier(name) => {
+                if self.current.kind != TokenKind::LeftParen {
+                    return Ok(Expr {kind: ExprKind::Variable(name), span: token.span});
+                }
+                let open = self.advance()?;
+                let mut args = Vec::new();
+                if self.current.kind != TokenKind::RightParen {
+                    loop {
+                        args.push(self.expression(0)?);
+                        if self.current.kind != TokenKind::Comma {
+                            break;
+                        }
+                        self.advance()?;
+                    }
+                }
+                let close = self.close(open.span)?;
+                Ok(Expr {kind: ExprKind::Call(name, args), span: token.span.to(close.span)})
+            }
+            TokenKind::If => {
+                let condition = self.expression(0)?;
+                self.expect(TokenKind::Then, SyntaxErrorKind::ExpectedThen)?;
+                let then = self.expression(0)?;
+                self.expect(TokenKind::Else, SyntaxErrorKind::ExpectedElse)?;
+                let otherwise = self.expression(0)?;
+                let span = token.span.to(otherwise.span);
+                Ok(Expr {kind: ExprKind::If(Box::new(condition), Box::new(then), Box::new(otherwise)), span})
+            }
+            TokenKind::Minus => {
+                let operand = self.expression(NEGATE_POWER)?;
+                let span = token.span.to(operand.span);
+                Ok(Expr {kind: ExprKind::Negate(Box::new(operand)), span})
+            }
+            TokenKind::LeftParen => {
+                let inner = self.expression(0)?;
+                let close = self.close(token.span)?;
+                Ok(Expr {kind: inner.kind, span: token.span.to(close.span)})
+            }
+            _ => Err(SyntaxError::new(SyntaxErrorKind::ExpectedExpression, token.span)),
+        }
+    } // End of operand function
+
+    // adds a level to the tree, or returns an error at the span if it is too deep.
+    fn descend(&mut self, span : Span) -> Result<(), SyntaxError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(SyntaxError::new(SyntaxErrorKind::TooDeep, span));
+        }
+        Ok(())
+    } // End of descend function
+
+    // consumes the ) matching the ( at the span.
+    fn close(&mut self, open : Span) -> Result<Token, SyntaxError> {
+        if self.current.kind != TokenKind::RightParen {
+            // point at the ( when the line ran out, otherwise at what is there instead
+            let span = if self.current.kind == TokenKind::End {open} else {self.current.span};
+            return Err(SyntaxError::new(SyntaxErrorKind::UnclosedParen, span));
+        }
+        self.advance()
+    } // End of close function
+
+    // consumes a token of the kind, or returns the error at the token found instead.
+    fn expect(&mut self, kind : TokenKind, error : SyntaxErrorKind) -> Result<Token, SyntaxError> {
+        if self.current.kind != kind {
+            return Err(SyntaxError::new(error, self.current.span));
+        }
+        self.advance()
+    } // End of expect function
+
+    // moves to the next token, returning the current one.
+    fn advance(&mut self) -> Result<Token, SyntaxError> {
+        let next = self.lexer.next_token()?;
+        Ok(::std::mem::replace(&mut self.current, next))
+    } // End of advance function
+} // End of Parser implementation
+
+// parses a line of input into an expression.
+pub fn parse(source : &str) -> Result<Expr, SyntaxError> {
+    Parser::new(source)?.parse()
+} // End of parse function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests precedence, associativity, unary minus and parentheses through the parenthesized form.
+#[test]
+fn precedence_test() {
+    let cases = [
+        ("1 + 2 * 3", "(1 + (2 * 3))"),
+        ("1 - 2 - 3", "((1 - 2) - 3)"),
+        ("2 ^ 3 ^ 2", "(2 ^ (3 ^ 2))"),
+        ("-2 ^ 2", "(-(2 ^ 2))"),
+        ("-2 * 3", "((-2) * 3)"),
+        ("2 ^ -3", "(2 ^ (-3))"),
+        ("--4 % 3 / 2", "(((-(-4)) % 3) / 2)"),
+        ("(1 + 2) * 3", "((1 + 2) * 3)"),
+        ("2 * (3 - (4 + 5)) ^ 2", "(2 * ((3 - (4 + 5)) ^ 2))"),
+        ("12345678901234567890123", "12345678901234567890123"),
+        ("x = 2 ^ 64", "(x = (2 ^ 64))"),
+        ("x = y = -ans", "(x = (y = (-ans)))"),
+        ("total += _ * 2", "(total += (_ * 2))"),
+        ("n ^= 2", "(n ^= 2)"),
+        ("1 + 2 < 3 * 4 == 1", "(((1 + 2) < (3 * 4)) == 1)"),
+        ("f(x, y) = x ^ 2 + y", "(f(x, y) = ((x ^ 2) + y))"),
+        ("fact(n) = if n <= 1 then 1 else n * fact(n - 1)", "(fact(n) = (if (n <= 1) then 1 else (n * fact((n - 1)))))"),
+        ("2 * if a then b else c + 1", "(2 * (if a then b else (c + 1)))"),
+        ("now() + g(-1, h(2))", "(now() + g((-1), h(2)))"),
+    ];
+    for &(source, expected) in cases.iter() {
+        let expr = parse(source).unwrap();
+        assert!(expr.to_string() == expected, "\nEvaluated {} as {}\n", source, expr);
+    }
+    let expr = parse(" (1 + 2) * 3").unwrap();
+    assert!(expr.span == Span {start: 1, end: 12}, "\nEvaluated the span of the product as {:?}\n", expr.span);
+    match expr.kind {
+        ExprKind::Binary(BinaryOp::Mul, ref left, _) => assert!(left.span == Span {start: 1, end: 8}, "\nEvaluated the span of the sum as {:?}\n", left.span),
+        _ => panic!("\nEvaluated {} as not a product\n", expr),
+    }
+} // End of precedence_test
+
+// Tests the kind and column of each syntax error.
+#[test]
+fn syntax_error_test() {
+    let cases = [
+        ("", SyntaxErrorKind::ExpectedExpression, 0, 0),
+        ("1 +", SyntaxErrorKind::ExpectedExpression, 3, 3),
+        ("1 + * 2", SyntaxErrorKind::ExpectedExpression, 4, 5),
+        ("(1 + 2", SyntaxErrorKind::UnclosedParen, 0, 1),
+        ("(1 + 2 3", SyntaxErrorKind::UnclosedParen, 7, 8),
+        ("1 + 2)", SyntaxErrorKind::UnexpectedToken, 5, 6),
+        ("1 2", SyntaxErrorKind::UnexpectedToken, 2, 3),
+        ("4 # 2", SyntaxErrorKind::UnexpectedCharacter('#'), 2, 3),
+        ("()", SyntaxErrorKind::ExpectedExpression, 1, 2),
+        ("1 + x = 2", SyntaxErrorKind::InvalidAssignment, 0, 5),
+        ("x = ", SyntaxErrorKind::ExpectedExpression, 4, 4),
+        ("x = 1 = 2", SyntaxErrorKind::InvalidAssignment, 4, 5),
+        ("2 * x += 1", SyntaxErrorKind::InvalidAssignment, 0, 5),
+        ("f(x, 1) = x", SyntaxErrorKind::InvalidParameter, 5, 6),
+        ("f(x, x) = x", SyntaxErrorKind::DuplicateParameter, 5, 6),
+        ("f(x) += 1", SyntaxErrorKind::InvalidAssignment, 0, 4),
+        ("y = f(x) = 1", SyntaxErrorKind::InvalidAssignment, 4, 8),
+        ("f(1, 2", SyntaxErrorKind::UnclosedParen, 1, 2),
+        ("f(1 2)", SyntaxErrorKind::UnclosedParen, 4, 5),
+        ("f(1,)", SyntaxErrorKind::ExpectedExpression, 4, 5),
+        ("if 1 else 2", SyntaxErrorKind::ExpectedThen, 5, 9),
+        ("if 1 then 2", SyntaxErrorKind::ExpectedElse, 11, 11),
+    ];
+    for &(source, ref kind, start, end) in cases.iter() {
+        let error = parse(source).unwrap_err();
+        assert!(error.kind() == kind && error.span() == Span {start, end}, "\nEvaluated the error of {:?} as {:?}\n", source, error);
+    }
+} // End of syntax_error_test
+
+// Tests that deeply nested expressions parse up to the limit and are errors beyond it.
+#[test]
+fn depth_limit_test() {
+    // parsing to the limit takes several kilobytes a level in a debug build, far more stack than
+    // a test thread has
+    ::std::thread::Builder::new().stack_size(256 << 20).spawn(|| {
+        let nested = |depth : usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH - 1)).is_ok(), "\nEvaluated {} parentheses as too deep\n", MAX_DEPTH - 1);
+        let error = parse(&nested(MAX_DEPTH)).unwrap_err();
+        assert!(*error.kind() == SyntaxErrorKind::TooDeep && error.span() == Span {start: MAX_DEPTH, end: MAX_DEPTH + 1}, "\nEvaluated {} parentheses as {:?}\n", MAX_DEPTH, error);
+
+        let sources = [
+            "-".repeat(100_000) + "1",
+            "1".to_string() + &"+1".repeat(300_000),
+            "2".to_string() + &"^2".repeat(MAX_DEPTH),
+            "x = ".repeat(MAX_DEPTH) + "1",
+            "f(".repeat(MAX_DEPTH + 1) + &")".repeat(MAX_DEPTH + 1),
+        ];
+        for source in sources.iter() {
+            let error = parse(source).unwrap_err();
+            assert!(*error.kind() == SyntaxErrorKind::TooDeep, "\nEvaluated the error of a line of {} characters as {:?}\n", source.len(), error);
+        }
+        assert!(parse(&("1".to_string() + &"+1".repeat(MAX_DEPTH - 2))).is_ok(), "\nEvaluated a sum of {} terms as too deep\n", MAX_DEPTH - 1);
+    }).unwrap().join().unwrap();
+} // End of depth_limit_test
@@ -0,0 +1,189 @@
+/* ============================================================================================ */
+/*     Description                                                                              */
+/* ============================================================================================ */
+// This Module runs the interactive calculator: it reads a line, evaluates it and prints the
+// result until the input ends. Lines starting with : are commands to the calculator itself
+// rather than expressions. The session's variables and functions are kept in an Environment for
+// as long as it runs.
+// An error is shown as a row of carets under the part of the line it refers to, which is still
+// on the screen above, followed by the message.
+
+/* ============================================================================================ */
+/*     Modules                                                                                  */
+/* ============================================================================================ */
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use editor::{Editor, History, Input};
+use eval::{evaluate, Environment};
+use lexer::Span;
+
+/* ============================================================================================ */
+/*     Constants                                                                                */
+/* ============================================================================================ */
+const PROMPT : &str = "> ";
+const HISTORY_FILE : &str = ".calc_history";     // kept in the home directory
+
+const HELP : &str = "\
+Enter an expression to evaluate it with integers of any size.
+
+Operators, from loosest to tightest:
+  a + b, a - b      addition and subtraction
+  a * b, a / b      multiplication and division, rounding toward zero
+  a % b             remainder, with the sign of a
+  -a                negation
+  a ^ b             power, grouping from the right so 2^3^2 = 2^9
+  (a)               grouping
+Comparisons <, <=, >, >=, == and != bind more loosely than arithmetic and give 1 or 0.
+Digits may be separated with underscores, as in 1_000_000.
+
+Variables:
+  x = a             set x to a, which is also the value of the line
+  x += a            update x, likewise with -=, *=, /=, %= and ^=
+  ans, _            the value of the last line
+
+Functions:
+  f(x, y) = a       define f, whose body a sees x, y and the variables
+  f(a, b)           call f
+  if c then a else b    a if c is not 0, otherwise b
+  fib(n), lucas(n)  the nth Fibonacci and Lucas numbers
+
+Keys:
+  Left, Right       move the cursor, as do Ctrl-B and Ctrl-F
+  Home, End         go to the start or end of the line, as do Ctrl-A and Ctrl-E
+  Up, Down          step through the history, as do Ctrl-P and Ctrl-N
+  Ctrl-U            clear the line
+  Ctrl-C            abandon the line
+  Ctrl-D            exit on an empty line
+
+Commands:
+  :help             show this help
+  :vars             list the variables and functions
+  :clear            forget the variables, functions and the last value
+  :quit, :q         exit";
+
+/* ============================================================================================ */
+/*     Functions                                                                                */
+/* ============================================================================================ */
+
+// What to do after a line has been read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Print(String),  // print the text and read another line
+    Nothing,        // read another line
+    Quit,           // stop
+}
+
+// returns the response to a line read after a prompt of the given width.
+pub fn respond(line : &str, env : &mut Environment, prompt_width : usize) -> Response {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return Response::Nothing;
+    }
+    if trimmed.starts_with(':') {
+        return match trimmed {
+            ":help" | ":h" => Response::Print(HELP.to_string()),
+            ":quit" | ":q" => Response::Quit,
+            ":vars" => Response::Print(list_variables(env)),
+            ":clear" => {
+                env.clear();
+                Response::Nothing
+            }
+            _ => Response::Print(format!("unknown command {}, enter :help for the commands", trimmed)),
+        };
+    }
+    match evaluate(line, env) {
+        Ok(Some(value)) => Response::Print(value.to_string()),
+        Ok(None) => Response::Nothing,
+        Err(error) => Response::Print(format!("{}\n{}", pointer(error.span(), prompt_width), error)),
+    }
+} // End of respond function
+
+// returns a line for each variable, function and the last value, or a note that there are none.
+fn list_variables(env : &Environment) -> String {
+    let mut lines : Vec<String> = env.variables().map(|(name, value)| format!("{} = {}", name, value)).collect();
+    lines.extend(env.functions().map(|(name, function)| format!("{}({}) = {}", name, function.params.join(", "), function.body)));
+    if let Some(value) = env.last() {
+        lines.push(format!("ans = {}", value));
+    }
+    if lines.is_empty() {
+        return "no variables".to_string();
+    }
+    lines.join("\n")
+} // End of list_variables function
+
+// returns a row of carets under the span, shifted past the prompt.
+fn pointer(span : Span, prompt_width : usize) -> String {
+    let width = (span.end - span.start).max(1);
+    format!("{}{}", " ".repeat(prompt_width + span.start), "^".repeat(width))
+} // End of pointer function
+
+// returns the path of the history file, if there is a home directory.
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").filter(|home| !home.is_empty()).map(|home| PathBuf::from(home).join(HISTORY_FILE))
+} // End of history_path function
+
+// runs the read-eval-print loop until the input ends or :quit is entered.
+pub fn run() -> io::Result<()> {
+    let history = match history_path() {
+        Some(path) => History::load(path),
+        None => History::new(),
+    };
+    let mut editor = Editor::new(history);
+    let mut env = Environment::new();
+    loop {
+        let line = match editor.read_line(PROMPT)? {
+            Input::Line(line) => line,
+            Input::Interrupted => continue,
+            Input::Eof => return Ok(()),
+        };
+        match respond(&line, &mut env, PROMPT.chars().count()) {
+            Response::Print(text) => println!("{}", text),
+            Response::Nothing => {}
+            Response::Quit => return Ok(()),
+        }
+    }
+} // End of run function
+
+/* ============================================================================================ */
+/*      Test cases                                                                              */
+/* ============================================================================================ */
+
+// Tests the responses to expressions, errors and commands.
+#[test]
+fn respond_test() {
+    let mut env = Environment::new();
+    let cases = [
+        ("2 ^ 64", Response::Print("18446744073709551616".to_string())),
+        ("   ", Response::Nothing),
+        (":q", Response::Quit),
+        (" :quit ", Response::Quit),
+        ("1 +", Response::Print("     ^\nsyntax error at column 4: expected an expression".to_string())),
+        ("7 % (3 - 3)", Response::Print("  ^^^^^^^^^^^\nerror at column 1: attempt to divide by zero".to_string())),
+        (":frobnicate", Response::Print("unknown command :frobnicate, enter :help for the commands".to_string())),
+    ];
+    for &(line, ref expected) in cases.iter() {
+        let response = respond(line, &mut env, 2);
+        assert!(response == *expected, "\nEvaluated the response to {:?} as {:?}\n", line, response);
+    }
+    assert!(respond(":help", &mut env, 2) == Response::Print(HELP.to_string()), "\nEvaluated :help as not the help\n");
+
+    let mut env = Environment::new();
+    let session = [
+        (":vars", "no variables"),
+        ("b = 3", "3"),
+        ("a = b ^ 2", "9"),
+        (":vars", "a = 9\nb = 3\nans = 9"),
+        ("a -= ans * 2", "-9"),
+        ("sq(x) = x * x", ""),
+        (":vars", "a = -9\nb = 3\nsq(x) = (x * x)\nans = -9"),
+        ("sq(a, b)", "  ^^^^^^^^\nerror at column 1: sq takes 1 argument but was given 2"),
+        ("c", "  ^\nerror at column 1: undefined variable c"),
+    ];
+    for &(line, expected) in session.iter() {
+        let response = respond(line, &mut env, 2);
+        let expected = if expected.is_empty() {Response::Nothing} else {Response::Print(expected.to_string())};
+        assert!(response == expected, "\nEvaluated the response to {:?} as {:?}\n", line, response);
+    }
+    assert!(respond(":clear", &mut env, 2) == Response::Nothing && env.get("a").is_none(), "\nEvaluated :clear as keeping the variables\n");
+} // End of respond_test